@@ -0,0 +1,213 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+
+use crate::error::AnalysisError;
+
+/// Dataset-scoped operations a `Principal` can be granted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DatasetOperation {
+    List,
+    Metadata,
+    Query,
+    Manage,
+}
+
+impl DatasetOperation {
+    fn parse(raw: &str) -> Result<Self, AnalysisError> {
+        match raw {
+            "list" => Ok(Self::List),
+            "metadata" => Ok(Self::Metadata),
+            "query" => Ok(Self::Query),
+            "manage" => Ok(Self::Manage),
+            other => Err(AnalysisError::ConfigError {
+                message: format!("Unknown dataset operation in MCP_ACCESS_CONTROL: {}", other),
+            }),
+        }
+    }
+}
+
+/// Dataset id used in a grant to mean "every dataset".
+const ALL_DATASETS: &str = "*";
+
+/// An authenticated caller and the dataset operations it has been granted,
+/// keyed by dataset id (`"*"` grants apply to every dataset).
+#[derive(Debug, Clone)]
+pub struct Principal {
+    pub name: String,
+    grants: HashMap<String, HashSet<DatasetOperation>>,
+}
+
+impl Principal {
+    /// The default principal used when `MCP_ACCESS_CONTROL` is unset, so
+    /// existing single-user deployments keep working without configuration.
+    fn allow_all_local() -> Self {
+        let mut grants = HashMap::new();
+        grants.insert(
+            ALL_DATASETS.to_string(),
+            [
+                DatasetOperation::List,
+                DatasetOperation::Metadata,
+                DatasetOperation::Query,
+                DatasetOperation::Manage,
+            ]
+            .into_iter()
+            .collect(),
+        );
+        Self {
+            name: "local".to_string(),
+            grants,
+        }
+    }
+
+    pub fn can(&self, dataset_id: &str, op: DatasetOperation) -> bool {
+        let granted = |id: &str| self.grants.get(id).is_some_and(|ops| ops.contains(&op));
+        granted(ALL_DATASETS) || granted(dataset_id)
+    }
+
+    /// Whether this principal holds `op` on the `"*"` (all-datasets) grant,
+    /// for operations like registering a brand-new dataset or browsing an
+    /// external share that aren't scoped to an existing dataset id.
+    pub fn can_global(&self, op: DatasetOperation) -> bool {
+        self.can(ALL_DATASETS, op)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PrincipalConfig {
+    token: String,
+    name: String,
+    grants: HashMap<String, Vec<String>>,
+}
+
+/// Maps bearer tokens / API keys to the `Principal` they authenticate as.
+///
+/// Configured via `MCP_ACCESS_CONTROL`, a JSON array of
+/// `{"token": "...", "name": "...", "grants": {"<dataset_id>": ["list", "metadata", "query", "manage"]}}`
+/// entries (`"*"` as the dataset id grants all datasets). When the variable
+/// is unset, every request authenticates as a single "allow-all local"
+/// principal, matching the server's previous unauthenticated behavior.
+#[derive(Debug, Clone)]
+pub struct AccessControl {
+    principals: HashMap<String, Principal>,
+    allow_all_local: bool,
+}
+
+impl AccessControl {
+    pub fn from_env() -> Result<Self, AnalysisError> {
+        match std::env::var("MCP_ACCESS_CONTROL") {
+            Ok(raw) => Self::parse(&raw),
+            Err(_) => Ok(Self {
+                principals: HashMap::new(),
+                allow_all_local: true,
+            }),
+        }
+    }
+
+    fn parse(raw: &str) -> Result<Self, AnalysisError> {
+        let configs: Vec<PrincipalConfig> =
+            serde_json::from_str(raw).map_err(|e| AnalysisError::ConfigError {
+                message: format!("Invalid MCP_ACCESS_CONTROL: {}", e),
+            })?;
+
+        let mut principals = HashMap::new();
+        for config in configs {
+            let mut grants = HashMap::new();
+            for (dataset_id, ops) in config.grants {
+                let ops = ops
+                    .iter()
+                    .map(|op| DatasetOperation::parse(op))
+                    .collect::<Result<HashSet<_>, _>>()?;
+                grants.insert(dataset_id, ops);
+            }
+            principals.insert(
+                config.token,
+                Principal {
+                    name: config.name,
+                    grants,
+                },
+            );
+        }
+
+        Ok(Self {
+            principals,
+            allow_all_local: false,
+        })
+    }
+
+    /// Returns the `Principal` a token authenticates as, or `None` if the
+    /// token is missing or unrecognized.
+    pub fn authenticate(&self, token: Option<&str>) -> Option<Principal> {
+        if self.allow_all_local {
+            return Some(Principal::allow_all_local());
+        }
+
+        self.principals.get(token?).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unconfigured_access_control_allows_all_local() {
+        std::env::remove_var("MCP_ACCESS_CONTROL");
+        let access_control = AccessControl::from_env().expect("should parse");
+
+        let principal = access_control
+            .authenticate(None)
+            .expect("should authenticate with no token when unconfigured");
+
+        assert!(principal.can("any-dataset", DatasetOperation::Manage));
+    }
+
+    #[test]
+    fn missing_or_unrecognized_token_is_not_authenticated() {
+        let access_control = AccessControl::parse(
+            r#"[{"token": "good-token", "name": "alice", "grants": {"ds1": ["query"]}}]"#,
+        )
+        .expect("should parse");
+
+        assert!(access_control.authenticate(None).is_none());
+        assert!(access_control.authenticate(Some("wrong-token")).is_none());
+    }
+
+    #[test]
+    fn principal_without_manage_grant_cannot_manage() {
+        // Regression test: every Manage-scoped MCP tool handler (delete,
+        // update metadata, refresh, resync, ...) relies on `Principal::can`
+        // actually denying operations the token wasn't granted - the bug
+        // this guards against was handlers skipping this check entirely,
+        // not `can` itself being wrong, but the check is only as good as
+        // the grants it asserts over.
+        let access_control = AccessControl::parse(
+            r#"[{"token": "query-only", "name": "alice", "grants": {"ds1": ["query"]}}]"#,
+        )
+        .expect("should parse");
+
+        let principal = access_control
+            .authenticate(Some("query-only"))
+            .expect("token should authenticate");
+
+        assert!(principal.can("ds1", DatasetOperation::Query));
+        assert!(!principal.can("ds1", DatasetOperation::Manage));
+        assert!(!principal.can_global(DatasetOperation::Manage));
+    }
+
+    #[test]
+    fn wildcard_grant_applies_to_every_dataset() {
+        let access_control = AccessControl::parse(
+            r#"[{"token": "admin", "name": "ops", "grants": {"*": ["manage"]}}]"#,
+        )
+        .expect("should parse");
+
+        let principal = access_control
+            .authenticate(Some("admin"))
+            .expect("token should authenticate");
+
+        assert!(principal.can("any-dataset-id", DatasetOperation::Manage));
+        assert!(principal.can_global(DatasetOperation::Manage));
+        assert!(!principal.can_global(DatasetOperation::Query));
+    }
+}
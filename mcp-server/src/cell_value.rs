@@ -0,0 +1,401 @@
+use arrow::array::*;
+use arrow::datatypes::{
+    DataType, Int16Type, Int32Type, Int64Type, Int8Type, TimeUnit, UInt16Type, UInt32Type,
+    UInt64Type, UInt8Type,
+};
+use chrono::NaiveDate;
+
+use crate::error::AnalysisError;
+
+/// A single decoded Arrow cell, keeping its native type instead of
+/// collapsing everything into a `String`. `Json` is the fallback for
+/// container types (`List`/`Struct`) and anything else we don't have a
+/// dedicated variant for.
+#[derive(Debug, Clone)]
+pub enum CellValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(String),
+    Date(NaiveDate),
+    /// Raw value plus the `TimeUnit` it's expressed in, so formatting can't
+    /// silently assume nanoseconds for a column that isn't.
+    Timestamp(i64, TimeUnit),
+    /// Unscaled `i128` plus the column's decimal scale.
+    Decimal128(i128, i8),
+    Binary(Vec<u8>),
+    Json(serde_json::Value),
+}
+
+impl std::fmt::Display for CellValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CellValue::Null => write!(f, "NULL"),
+            CellValue::Bool(b) => write!(f, "{}", b),
+            CellValue::Int(i) => write!(f, "{}", i),
+            CellValue::UInt(u) => write!(f, "{}", u),
+            CellValue::Float(v) => write!(f, "{}", v),
+            CellValue::Str(s) => write!(f, "{}", s),
+            CellValue::Date(d) => write!(f, "{}", d),
+            CellValue::Timestamp(value, unit) => match timestamp_to_datetime(*value, *unit) {
+                Some(dt) => write!(f, "{}", dt.format("%Y-%m-%d %H:%M:%S%.f")),
+                None => write!(f, "Invalid Timestamp"),
+            },
+            CellValue::Decimal128(value, scale) => write!(f, "{}", format_decimal128(*value, *scale)),
+            CellValue::Binary(bytes) => write!(f, "{}", hex_encode(bytes)),
+            CellValue::Json(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl serde::Serialize for CellValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            CellValue::Null => serializer.serialize_none(),
+            CellValue::Bool(b) => serializer.serialize_bool(*b),
+            CellValue::Int(i) => serializer.serialize_i64(*i),
+            CellValue::UInt(u) => serializer.serialize_u64(*u),
+            CellValue::Float(v) => serializer.serialize_f64(*v),
+            CellValue::Str(s) => serializer.serialize_str(s),
+            CellValue::Json(v) => v.serialize(serializer),
+            CellValue::Date(_)
+            | CellValue::Timestamp(_, _)
+            | CellValue::Decimal128(_, _)
+            | CellValue::Binary(_) => serializer.serialize_str(&self.to_string()),
+        }
+    }
+}
+
+fn timestamp_to_datetime(value: i64, unit: TimeUnit) -> Option<chrono::DateTime<chrono::Utc>> {
+    match unit {
+        TimeUnit::Second => chrono::DateTime::from_timestamp(value, 0),
+        TimeUnit::Millisecond => chrono::DateTime::from_timestamp_millis(value),
+        TimeUnit::Microsecond => chrono::DateTime::from_timestamp_micros(value),
+        TimeUnit::Nanosecond => chrono::DateTime::from_timestamp(
+            value / 1_000_000_000,
+            (value.rem_euclid(1_000_000_000)) as u32,
+        ),
+    }
+}
+
+fn format_decimal128(value: i128, scale: i8) -> String {
+    if scale <= 0 {
+        return (value * 10i128.pow((-scale) as u32)).to_string();
+    }
+
+    let scale = scale as u32;
+    let divisor = 10i128.pow(scale);
+    let integer_part = value / divisor;
+    let fractional_part = (value % divisor).abs();
+    format!(
+        "{}.{:0width$}",
+        integer_part,
+        fractional_part,
+        width = scale as usize
+    )
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn format_time_of_day(seconds_since_midnight: i64, nanos: u32) -> String {
+    chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+        (seconds_since_midnight.rem_euclid(86_400)) as u32,
+        nanos,
+    )
+    .map(|t| t.format("%H:%M:%S%.f").to_string())
+    .unwrap_or_else(|| "Invalid Time".to_string())
+}
+
+/// Decodes a single cell out of `array` at `index` into its typed
+/// `CellValue`, matching on the array's real Arrow `DataType` instead of
+/// assuming a fixed width/unit (the old stringifying path always downcast
+/// `Timestamp` columns to `TimestampNanosecondArray`, corrupting any column
+/// that wasn't actually nanosecond-precision).
+pub fn extract_arrow_value(array: &dyn Array, index: usize) -> CellValue {
+    if array.is_null(index) {
+        return CellValue::Null;
+    }
+
+    match array.data_type() {
+        DataType::Boolean => {
+            CellValue::Bool(array.as_any().downcast_ref::<BooleanArray>().unwrap().value(index))
+        }
+        DataType::Int8 => CellValue::Int(
+            array.as_any().downcast_ref::<Int8Array>().unwrap().value(index) as i64,
+        ),
+        DataType::Int16 => CellValue::Int(
+            array.as_any().downcast_ref::<Int16Array>().unwrap().value(index) as i64,
+        ),
+        DataType::Int32 => CellValue::Int(
+            array.as_any().downcast_ref::<Int32Array>().unwrap().value(index) as i64,
+        ),
+        DataType::Int64 => {
+            CellValue::Int(array.as_any().downcast_ref::<Int64Array>().unwrap().value(index))
+        }
+        DataType::UInt8 => CellValue::UInt(
+            array.as_any().downcast_ref::<UInt8Array>().unwrap().value(index) as u64,
+        ),
+        DataType::UInt16 => CellValue::UInt(
+            array.as_any().downcast_ref::<UInt16Array>().unwrap().value(index) as u64,
+        ),
+        DataType::UInt32 => CellValue::UInt(
+            array.as_any().downcast_ref::<UInt32Array>().unwrap().value(index) as u64,
+        ),
+        DataType::UInt64 => {
+            CellValue::UInt(array.as_any().downcast_ref::<UInt64Array>().unwrap().value(index))
+        }
+        DataType::Float32 => CellValue::Float(
+            array.as_any().downcast_ref::<Float32Array>().unwrap().value(index) as f64,
+        ),
+        DataType::Float64 => {
+            CellValue::Float(array.as_any().downcast_ref::<Float64Array>().unwrap().value(index))
+        }
+        DataType::Utf8 => CellValue::Str(
+            array
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap()
+                .value(index)
+                .to_string(),
+        ),
+        DataType::LargeUtf8 => CellValue::Str(
+            array
+                .as_any()
+                .downcast_ref::<LargeStringArray>()
+                .unwrap()
+                .value(index)
+                .to_string(),
+        ),
+        DataType::Binary => CellValue::Binary(
+            array
+                .as_any()
+                .downcast_ref::<BinaryArray>()
+                .unwrap()
+                .value(index)
+                .to_vec(),
+        ),
+        DataType::LargeBinary => CellValue::Binary(
+            array
+                .as_any()
+                .downcast_ref::<LargeBinaryArray>()
+                .unwrap()
+                .value(index)
+                .to_vec(),
+        ),
+        DataType::Date32 => {
+            let days = array.as_any().downcast_ref::<Date32Array>().unwrap().value(index);
+            match NaiveDate::from_num_days_from_ce_opt(days + 719_163) {
+                Some(date) => CellValue::Date(date),
+                None => CellValue::Null,
+            }
+        }
+        DataType::Date64 => {
+            let millis = array.as_any().downcast_ref::<Date64Array>().unwrap().value(index);
+            match chrono::DateTime::from_timestamp_millis(millis) {
+                Some(dt) => CellValue::Date(dt.date_naive()),
+                None => CellValue::Null,
+            }
+        }
+        DataType::Time32(unit) => {
+            let seconds = match unit {
+                TimeUnit::Second => {
+                    array.as_any().downcast_ref::<Time32SecondArray>().unwrap().value(index) as i64
+                }
+                TimeUnit::Millisecond => {
+                    array
+                        .as_any()
+                        .downcast_ref::<Time32MillisecondArray>()
+                        .unwrap()
+                        .value(index) as i64
+                        / 1_000
+                }
+                _ => unreachable!("Time32 only supports Second/Millisecond"),
+            };
+            CellValue::Str(format_time_of_day(seconds, 0))
+        }
+        DataType::Time64(unit) => {
+            let (seconds, nanos) = match unit {
+                TimeUnit::Microsecond => {
+                    let micros = array
+                        .as_any()
+                        .downcast_ref::<Time64MicrosecondArray>()
+                        .unwrap()
+                        .value(index);
+                    (micros / 1_000_000, ((micros % 1_000_000) * 1_000) as u32)
+                }
+                TimeUnit::Nanosecond => {
+                    let nanos = array
+                        .as_any()
+                        .downcast_ref::<Time64NanosecondArray>()
+                        .unwrap()
+                        .value(index);
+                    (nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32)
+                }
+                _ => unreachable!("Time64 only supports Microsecond/Nanosecond"),
+            };
+            CellValue::Str(format_time_of_day(seconds, nanos))
+        }
+        DataType::Timestamp(unit, _) => {
+            let value = match unit {
+                TimeUnit::Second => {
+                    array.as_any().downcast_ref::<TimestampSecondArray>().unwrap().value(index)
+                }
+                TimeUnit::Millisecond => array
+                    .as_any()
+                    .downcast_ref::<TimestampMillisecondArray>()
+                    .unwrap()
+                    .value(index),
+                TimeUnit::Microsecond => array
+                    .as_any()
+                    .downcast_ref::<TimestampMicrosecondArray>()
+                    .unwrap()
+                    .value(index),
+                TimeUnit::Nanosecond => array
+                    .as_any()
+                    .downcast_ref::<TimestampNanosecondArray>()
+                    .unwrap()
+                    .value(index),
+            };
+            CellValue::Timestamp(value, *unit)
+        }
+        DataType::Decimal128(_, scale) => {
+            let value = array
+                .as_any()
+                .downcast_ref::<Decimal128Array>()
+                .unwrap()
+                .value(index);
+            CellValue::Decimal128(value, *scale)
+        }
+        DataType::Dictionary(key_type, _) => extract_dictionary_value(array, index, key_type),
+        DataType::List(_) => extract_list_value(array, index),
+        DataType::Struct(_) => extract_struct_value(array, index),
+        other => CellValue::Json(serde_json::Value::String(format!(
+            "Unsupported Arrow type {:?}: {:?}",
+            other,
+            array.slice(index, 1)
+        ))),
+    }
+}
+
+macro_rules! resolve_dictionary_key {
+    ($array:expr, $index:expr, $key_native:ty) => {{
+        let dict = $array
+            .as_any()
+            .downcast_ref::<DictionaryArray<$key_native>>()
+            .unwrap();
+        let key_index = dict.keys().value($index);
+        extract_arrow_value(dict.values().as_ref(), key_index.try_into().unwrap_or(0))
+    }};
+}
+
+fn extract_dictionary_value(array: &dyn Array, index: usize, key_type: &DataType) -> CellValue {
+    match key_type {
+        DataType::Int8 => resolve_dictionary_key!(array, index, Int8Type),
+        DataType::Int16 => resolve_dictionary_key!(array, index, Int16Type),
+        DataType::Int32 => resolve_dictionary_key!(array, index, Int32Type),
+        DataType::Int64 => resolve_dictionary_key!(array, index, Int64Type),
+        DataType::UInt8 => resolve_dictionary_key!(array, index, UInt8Type),
+        DataType::UInt16 => resolve_dictionary_key!(array, index, UInt16Type),
+        DataType::UInt32 => resolve_dictionary_key!(array, index, UInt32Type),
+        DataType::UInt64 => resolve_dictionary_key!(array, index, UInt64Type),
+        other => CellValue::Json(serde_json::Value::String(format!(
+            "Unsupported dictionary key type: {:?}",
+            other
+        ))),
+    }
+}
+
+fn extract_list_value(array: &dyn Array, index: usize) -> CellValue {
+    let list = array.as_any().downcast_ref::<ListArray>().unwrap();
+    let values = list.value(index);
+
+    let items = (0..values.len())
+        .map(|i| cell_value_to_json(&extract_arrow_value(values.as_ref(), i)))
+        .collect();
+
+    CellValue::Json(serde_json::Value::Array(items))
+}
+
+fn extract_struct_value(array: &dyn Array, index: usize) -> CellValue {
+    let st = array.as_any().downcast_ref::<StructArray>().unwrap();
+
+    let mut map = serde_json::Map::new();
+    for (field, column) in st.fields().iter().zip(st.columns()) {
+        map.insert(
+            field.name().clone(),
+            cell_value_to_json(&extract_arrow_value(column.as_ref(), index)),
+        );
+    }
+
+    CellValue::Json(serde_json::Value::Object(map))
+}
+
+/// Decodes one Arrow IPC stream (as sent in a single `DataChunk`) into rows
+/// of `CellValue`, aligned positionally to `column_names`.
+pub fn decode_arrow_ipc_chunk(
+    arrow_data: &[u8],
+    column_names: &[String],
+) -> Result<Vec<Vec<CellValue>>, AnalysisError> {
+    use arrow::ipc::reader::StreamReader;
+    use std::io::Cursor;
+
+    if arrow_data.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let cursor = Cursor::new(arrow_data);
+    let reader = StreamReader::try_new(cursor, None).map_err(|e| AnalysisError::ConfigError {
+        message: format!("Failed to create Arrow IPC reader: {}", e),
+    })?;
+
+    let mut all_rows = Vec::new();
+
+    for batch_result in reader {
+        let batch = batch_result.map_err(|e| AnalysisError::QueryExecutionFailed {
+            message: format!("Failed to read Arrow batch: {}", e),
+        })?;
+
+        let row_count = batch.num_rows();
+        for row_idx in 0..row_count {
+            let mut row = Vec::with_capacity(column_names.len());
+
+            for col_idx in 0..column_names.len() {
+                if col_idx < batch.num_columns() {
+                    let column = batch.column(col_idx);
+                    row.push(extract_arrow_value(column.as_ref(), row_idx));
+                } else {
+                    row.push(CellValue::Null);
+                }
+            }
+
+            all_rows.push(row);
+        }
+    }
+
+    Ok(all_rows)
+}
+
+fn cell_value_to_json(value: &CellValue) -> serde_json::Value {
+    match value {
+        CellValue::Null => serde_json::Value::Null,
+        CellValue::Bool(b) => serde_json::Value::Bool(*b),
+        CellValue::Int(i) => serde_json::Value::Number((*i).into()),
+        CellValue::UInt(u) => serde_json::Value::Number((*u).into()),
+        CellValue::Float(v) => serde_json::Number::from_f64(*v)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        CellValue::Str(s) => serde_json::Value::String(s.clone()),
+        CellValue::Json(v) => v.clone(),
+        CellValue::Date(_)
+        | CellValue::Timestamp(_, _)
+        | CellValue::Decimal128(_, _)
+        | CellValue::Binary(_) => serde_json::Value::String(value.to_string()),
+    }
+}
@@ -0,0 +1,33 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AnalysisError {
+    #[error("Dataset not found: {dataset_id}")]
+    DatasetNotFound { dataset_id: String },
+
+    #[error("Query execution failed: {message}")]
+    QueryExecutionFailed { message: String },
+
+    #[error("Query engine is overloaded: {message}")]
+    ResourceExhausted { message: String },
+
+    #[error("IO error: {message}")]
+    IoError { message: String },
+
+    #[error("JSON serialization error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("Configuration error: {message}")]
+    ConfigError { message: String },
+
+    #[error("Access denied: {message}")]
+    AccessDenied { message: String },
+}
+
+impl From<std::io::Error> for AnalysisError {
+    fn from(err: std::io::Error) -> Self {
+        AnalysisError::IoError {
+            message: err.to_string(),
+        }
+    }
+}
@@ -13,15 +13,20 @@ pub mod proto {
     }
 }
 
+mod auth;
+mod cell_value;
 mod error;
 mod mcp_server;
 mod query_client;
+mod tls;
 
 use mcp_server::AnalysisService;
+use tls::TlsConfig;
 
 async fn handle_request(
     req: Request<Incoming>,
-    query_engine_endpoint: String,
+    query_engine_endpoints: Vec<String>,
+    tls_config: Option<TlsConfig>,
 ) -> Result<Response<String>, hyper::Error> {
     info!(
         "Received HTTP request: {} {}",
@@ -34,7 +39,7 @@ async fn handle_request(
             info!("HTTP upgrade successful");
             let io = TokioIo::new(upgraded);
 
-            match AnalysisService::new(query_engine_endpoint).await {
+            match AnalysisService::new(query_engine_endpoints, tls_config).await {
                 Ok(service) => match service.serve(io).await {
                     Ok(server) => {
                         info!("MCP server session started");
@@ -76,12 +81,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .parse()
         .expect("Invalid PORT");
 
-    let query_engine_endpoint = std::env::var("QUERY_ENGINE_ENDPOINT")
-        .unwrap_or_else(|_| "http://localhost:50051".to_string());
+    let query_engine_endpoints: Vec<String> = std::env::var("QUERY_ENGINE_ENDPOINTS")
+        .or_else(|_| std::env::var("QUERY_ENGINE_ENDPOINT"))
+        .unwrap_or_else(|_| "http://localhost:50051".to_string())
+        .split(',')
+        .map(|addr| addr.trim().to_string())
+        .filter(|addr| !addr.is_empty())
+        .collect();
+
+    let tls_config = TlsConfig::from_env()?;
 
     info!("Configuration loaded:");
     info!("  MCP Port: {}", mcp_port);
-    info!("  Query Engine Endpoint: {}", query_engine_endpoint);
+    info!("  Query Engine Endpoints: {}", query_engine_endpoints.join(", "));
+    match &tls_config {
+        Some(_) => info!("  Query Engine TLS: enabled"),
+        None => info!("  Query Engine TLS: disabled (plaintext)"),
+    }
 
     let addr: SocketAddr = ([0, 0, 0, 0], mcp_port).into();
     let tcp_listener = TcpListener::bind(addr).await?;
@@ -91,12 +107,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     while let Ok((stream, remote_addr)) = tcp_listener.accept().await {
         info!("New HTTP connection from {}", remote_addr);
 
-        let query_engine_endpoint = query_engine_endpoint.clone();
+        let query_engine_endpoints = query_engine_endpoints.clone();
+        let tls_config = tls_config.clone();
         tokio::spawn(async move {
             let io = TokioIo::new(stream);
             let service = service_fn(move |req| {
-                let endpoint = query_engine_endpoint.clone();
-                async move { handle_request(req, endpoint).await }
+                let endpoints = query_engine_endpoints.clone();
+                let tls_config = tls_config.clone();
+                async move { handle_request(req, endpoints, tls_config).await }
             });
 
             if let Err(e) = http1::Builder::new()
@@ -1,22 +1,39 @@
 use axum::{
-    extract::State,
+    extract::{Request, State},
     http::StatusCode,
-    response::Json,
+    middleware::{self, Next},
+    response::sse::{Event, Sse},
+    response::{IntoResponse, Json, Response},
     routing::{get, post},
-    Router,
+    Extension, Router,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
 use tower_http::cors::CorsLayer;
-use tracing::info;
+use tracing::{error, info, warn};
 
+use crate::auth::{AccessControl, DatasetOperation, Principal};
 use crate::error::AnalysisError;
 use crate::query_client::QueryEngineClient;
+use crate::tls::TlsConfig;
+
+/// How many catalog-change notifications a slow SSE client can lag behind
+/// before it starts missing them. Clients that fall further behind than
+/// this just see a gap (`BroadcastStream` skips on `Lagged`) rather than the
+/// whole server buffering for the slowest subscriber.
+const CATALOG_EVENTS_CAPACITY: usize = 64;
 
 pub struct McpServer {
     query_client: Arc<Mutex<QueryEngineClient>>,
+    catalog_events: broadcast::Sender<String>,
+    access_control: Arc<AccessControl>,
 }
 
 // MCP protocol structures
@@ -63,23 +80,121 @@ pub struct ExecuteQueryParams {
     pub limit: Option<i32>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteDatasetParams {
+    pub dataset_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateDatasetMetadataParams {
+    pub dataset_id: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshDatasetParams {
+    pub dataset_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResyncDatasetParams {
+    pub dataset_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExecuteQueryStreamParams {
+    pub dataset_id: String,
+    pub sql_query: String,
+    #[serde(default)]
+    pub limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListSharedTablesParams {
+    pub endpoint: String,
+    pub bearer_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddSharedDatasetParams {
+    pub name: String,
+    pub endpoint: String,
+    pub bearer_token: String,
+    pub share: String,
+    pub schema: String,
+    pub table: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub partition_filters: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SemanticSearchParams {
+    pub dataset_id: String,
+    pub text_column: String,
+    pub query: String,
+    pub k: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmitQueryParams {
+    pub dataset_id: String,
+    pub sql_query: String,
+    #[serde(default)]
+    pub limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetJobStatusParams {
+    pub job_id: String,
+}
+
 impl McpServer {
-    pub async fn new(query_engine_endpoint: String) -> Result<Self, AnalysisError> {
-        let query_client = QueryEngineClient::new(query_engine_endpoint).await?;
+    pub async fn new(
+        query_engine_endpoints: Vec<String>,
+        tls: Option<TlsConfig>,
+    ) -> Result<Self, AnalysisError> {
+        let query_client = QueryEngineClient::new(query_engine_endpoints, tls).await?;
+        let query_client = Arc::new(Mutex::new(query_client));
+        let (catalog_events, _) = broadcast::channel(CATALOG_EVENTS_CAPACITY);
+        let access_control = Arc::new(AccessControl::from_env()?);
+
+        spawn_catalog_watch_bridge(query_client.clone(), catalog_events.clone());
+
         Ok(Self {
-            query_client: Arc::new(Mutex::new(query_client)),
+            query_client,
+            catalog_events,
+            access_control,
         })
     }
 
     pub async fn start(&self, addr: SocketAddr) -> Result<(), AnalysisError> {
         info!("Starting MCP server on {}", addr);
 
-        let app = Router::new()
+        let state = AppState {
+            query_client: self.query_client.clone(),
+            catalog_events: self.catalog_events.clone(),
+            access_control: self.access_control.clone(),
+        };
+
+        let authenticated = Router::new()
             .route("/", post(handle_mcp_request))
-            .route("/health", get(health_check))
             .route("/tools", get(list_tools))
+            .route("/events", get(handle_catalog_events))
+            .route("/stream", post(handle_stream_request))
+            .layer(middleware::from_fn_with_state(state.clone(), auth_middleware));
+
+        let app = Router::new()
+            .route("/health", get(health_check))
+            .merge(authenticated)
             .layer(CorsLayer::permissive())
-            .with_state(self.query_client.clone());
+            .with_state(state);
 
         let listener = tokio::net::TcpListener::bind(addr).await?;
         axum::serve(listener, app).await?;
@@ -88,8 +203,294 @@ impl McpServer {
     }
 }
 
+#[derive(Clone)]
+struct AppState {
+    query_client: Arc<Mutex<QueryEngineClient>>,
+    catalog_events: broadcast::Sender<String>,
+    access_control: Arc<AccessControl>,
+}
+
+/// Validates the bearer token (or `X-Api-Key`) on every request to `/`,
+/// `/tools`, `/events` and `/stream` before it reaches a handler, inserting
+/// the resolved `Principal` as a request extension so downstream handlers
+/// can enforce per-dataset grants. `/health` stays open since it carries no
+/// dataset access.
+async fn auth_middleware(State(state): State<AppState>, mut req: Request, next: Next) -> Response {
+    let token = extract_token(req.headers());
+
+    match state.access_control.authenticate(token.as_deref()) {
+        Some(principal) => {
+            req.extensions_mut().insert(principal);
+            next.run(req).await
+        }
+        None => {
+            (StatusCode::UNAUTHORIZED, "Invalid or missing credentials").into_response()
+        }
+    }
+}
+
+/// Reads a caller's credential from either `Authorization: Bearer <token>`
+/// or `X-Api-Key: <token>`, whichever is present.
+fn extract_token(headers: &axum::http::HeaderMap) -> Option<String> {
+    if let Some(value) = headers.get(axum::http::header::AUTHORIZATION) {
+        if let Ok(value) = value.to_str() {
+            if let Some(token) = value.strip_prefix("Bearer ") {
+                return Some(token.to_string());
+            }
+        }
+    }
+
+    headers
+        .get("x-api-key")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+}
+
+/// Drains the query engine's `watch_catalog` gRPC stream into a local
+/// broadcast channel for the lifetime of the process, so any number of SSE
+/// subscribers can share one upstream stream instead of each opening its
+/// own. Reconnects with a fixed delay if the upstream stream ends, since
+/// `watch_catalog` is otherwise expected to run forever.
+fn spawn_catalog_watch_bridge(
+    query_client: Arc<Mutex<QueryEngineClient>>,
+    catalog_events: broadcast::Sender<String>,
+) {
+    tokio::spawn(async move {
+        loop {
+            let mut stream = {
+                let mut client = query_client.lock().await;
+                match client.watch_catalog().await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        error!("Failed to open catalog watch stream: {}", e);
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        continue;
+                    }
+                }
+            };
+
+            while let Some(item) = stream.next().await {
+                match item {
+                    Ok(dataset_id) => {
+                        // No subscribers is the common case and not an error.
+                        let _ = catalog_events.send(dataset_id);
+                    }
+                    Err(e) => {
+                        warn!("Catalog watch stream ended with error: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    });
+}
+
+/// Streams catalog change notifications (dataset ids) as Server-Sent Events,
+/// so the VS Code extension can repaint when datasets are added or updated
+/// instead of re-polling `list_datasets`. This sits alongside the plain
+/// JSON-RPC `/` endpoint rather than extending it, since MCP's request/reply
+/// shape has no subscription mechanism of its own. Events are filtered to
+/// datasets `principal` is granted at least list access to, the same as
+/// `handle_list_datasets` - otherwise any authenticated principal would
+/// learn about every dataset's changes regardless of their own grants.
+async fn handle_catalog_events(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = state.catalog_events.subscribe();
+    let stream = tokio_stream::wrappers::BroadcastStream::new(receiver)
+        .filter_map(|item| item.ok())
+        .filter(move |dataset_id| {
+            let allowed = principal.can(dataset_id, DatasetOperation::List);
+            async move { allowed }
+        })
+        .map(|dataset_id| Ok(Event::default().event("dataset_changed").data(dataset_id)));
+
+    Sse::new(stream).keep_alive(axum::response::sse::KeepAlive::default())
+}
+
+/// Builds a `tools/progress` JSON-RPC notification frame carrying an error,
+/// for a streamed request that fails before or during emission - by that
+/// point an SSE response is already committed, so the failure has to travel
+/// as an event rather than an HTTP error status.
+fn sse_progress_error(id: &Option<serde_json::Value>, code: i32, message: String) -> Event {
+    let notification = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "tools/progress",
+        "params": {
+            "id": id,
+            "error": { "code": code, "message": message }
+        }
+    });
+    Event::default()
+        .event("tools/progress")
+        .json_data(notification)
+        .unwrap_or_default()
+}
+
+/// Streaming counterpart to the buffered `tools/call` handling on `/`: POST
+/// the same JSON-RPC request here for `execute_query_stream` and get back an
+/// SSE response with one `tools/progress` event per row batch as DataFusion
+/// produces it, followed by a terminal event carrying `total_rows` and
+/// `execution_time_ms`, so large result sets render incrementally instead of
+/// waiting on the whole query. Every other tool name (or method) ends the
+/// stream immediately with a single error event; clients that don't need
+/// streaming should keep using the buffered `/` endpoint. Dropping the
+/// response body (client disconnect) drops this stream's receiver, which
+/// ends `execute_query_stream`'s forwarding task and, with it, the
+/// underlying gRPC call.
+async fn handle_stream_request(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(request): Json<McpRequest>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let (tx, rx) = mpsc::channel(8);
+    let id = request.id.clone();
+
+    tokio::spawn(async move {
+        if request.method != "tools/call" {
+            let _ = tx
+                .send(Ok(sse_progress_error(
+                    &id,
+                    -32601,
+                    format!("Method not found: {}", request.method),
+                )))
+                .await;
+            return;
+        }
+
+        let params = match request.params {
+            Some(p) => p,
+            None => {
+                let _ = tx
+                    .send(Ok(sse_progress_error(&id, -32602, "Invalid params".to_string())))
+                    .await;
+                return;
+            }
+        };
+
+        let tool_name = params.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+        if tool_name != "execute_query_stream" {
+            let _ = tx
+                .send(Ok(sse_progress_error(
+                    &id,
+                    -32602,
+                    format!(
+                        "Tool '{}' does not support streaming; use tools/call on / instead",
+                        tool_name
+                    ),
+                )))
+                .await;
+            return;
+        }
+
+        let arguments = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or(serde_json::json!({}));
+        let stream_params: ExecuteQueryStreamParams = match serde_json::from_value(arguments) {
+            Ok(p) => p,
+            Err(e) => {
+                let _ = tx
+                    .send(Ok(sse_progress_error(
+                        &id,
+                        -32602,
+                        format!("Invalid arguments for execute_query_stream: {}", e),
+                    )))
+                    .await;
+                return;
+            }
+        };
+
+        if !principal.can(&stream_params.dataset_id, DatasetOperation::Query) {
+            let _ = tx
+                .send(Ok(sse_progress_error(
+                    &id,
+                    -32603,
+                    format!(
+                        "{} is not granted query access to dataset {}",
+                        principal.name, stream_params.dataset_id
+                    ),
+                )))
+                .await;
+            return;
+        }
+
+        let mut batches = {
+            let mut client = state.query_client.lock().await;
+            match client
+                .execute_query_stream(
+                    &stream_params.dataset_id,
+                    &stream_params.sql_query,
+                    stream_params.limit,
+                )
+                .await
+            {
+                Ok(stream) => stream,
+                Err(e) => {
+                    let _ = tx
+                        .send(Ok(sse_progress_error(&id, -32603, e.to_string())))
+                        .await;
+                    return;
+                }
+            }
+        };
+
+        let mut total_rows = 0usize;
+        while let Some(item) = batches.next().await {
+            let batch = match item {
+                Ok(batch) => batch,
+                Err(e) => {
+                    let _ = tx
+                        .send(Ok(sse_progress_error(&id, -32603, e.to_string())))
+                        .await;
+                    return;
+                }
+            };
+
+            let notification = if let Some(chunk_index) = batch.chunk_index {
+                total_rows += batch.rows.len();
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": "tools/progress",
+                    "params": {
+                        "id": id,
+                        "chunk_index": chunk_index,
+                        "column_names": batch.column_names,
+                        "rows": batch.rows
+                    }
+                })
+            } else {
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": "tools/progress",
+                    "params": {
+                        "id": id,
+                        "done": true,
+                        "total_rows": total_rows,
+                        "execution_time_ms": batch.execution_time_ms
+                    }
+                })
+            };
+
+            let event = Event::default()
+                .event("tools/progress")
+                .json_data(notification)
+                .unwrap_or_default();
+            if tx.send(Ok(event)).await.is_err() {
+                return; // Receiver dropped (client disconnected)
+            }
+        }
+    });
+
+    Sse::new(ReceiverStream::new(rx)).keep_alive(axum::response::sse::KeepAlive::default())
+}
+
 async fn handle_mcp_request(
-    State(query_client): State<Arc<Mutex<QueryEngineClient>>>,
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Json(request): Json<McpRequest>,
 ) -> Json<McpResponse> {
     info!(
@@ -102,7 +503,9 @@ async fn handle_mcp_request(
         "initialized" => handle_initialized(request.id),
         "ping" => handle_ping(request.id),
         "tools/list" => handle_tools_list(request.id),
-        "tools/call" => handle_tool_call(query_client, request.id, request.params).await,
+        "tools/call" => {
+            handle_tool_call(state.query_client, &principal, request.id, request.params).await
+        }
         _ => McpResponse {
             jsonrpc: "2.0".to_string(),
             id: request.id,
@@ -209,6 +612,185 @@ fn handle_tools_list(id: Option<serde_json::Value>) -> McpResponse {
                     "required": ["dataset_id", "sql_query"]
                 }
             },
+            {
+                "name": "execute_query_stream",
+                "description": "Execute a SQL query on a dataset and stream results over SSE as they arrive instead of buffering the whole result set. Only usable against the /stream route, not tools/call on /.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dataset_id": {
+                            "type": "string",
+                            "description": "The ID of the dataset to query"
+                        },
+                        "sql_query": {
+                            "type": "string",
+                            "description": "The SQL query to execute"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of rows to return (optional)",
+                            "minimum": 1,
+                            "maximum": 10000
+                        }
+                    },
+                    "required": ["dataset_id", "sql_query"]
+                }
+            },
+            {
+                "name": "delete_dataset",
+                "description": "Delete a dataset from the catalog",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dataset_id": {
+                            "type": "string",
+                            "description": "The ID of the dataset to delete"
+                        }
+                    },
+                    "required": ["dataset_id"]
+                }
+            },
+            {
+                "name": "update_dataset_metadata",
+                "description": "Update a dataset's description and/or tags",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dataset_id": {
+                            "type": "string",
+                            "description": "The ID of the dataset to update"
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "New description (optional, leaves it unchanged if omitted)"
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "New tags (optional, leaves them unchanged if omitted)"
+                        }
+                    },
+                    "required": ["dataset_id"]
+                }
+            },
+            {
+                "name": "refresh_dataset",
+                "description": "Re-scan a dataset's storage path to pick up new files or schema changes",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dataset_id": {
+                            "type": "string",
+                            "description": "The ID of the dataset to refresh"
+                        }
+                    },
+                    "required": ["dataset_id"]
+                }
+            },
+            {
+                "name": "resync_dataset",
+                "description": "Incrementally re-sync a directory-backed dataset against its original source path, copying only new or changed files and dropping files removed upstream",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dataset_id": {
+                            "type": "string",
+                            "description": "The ID of the dataset to resync"
+                        }
+                    },
+                    "required": ["dataset_id"]
+                }
+            },
+            {
+                "name": "list_shared_tables",
+                "description": "Enumerate the shares, schemas and tables a Delta Sharing profile has access to",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "endpoint": {
+                            "type": "string",
+                            "description": "The Delta Sharing server's endpoint URL, from the share profile"
+                        },
+                        "bearer_token": {
+                            "type": "string",
+                            "description": "The short-lived bearer token from the share profile"
+                        }
+                    },
+                    "required": ["endpoint", "bearer_token"]
+                }
+            },
+            {
+                "name": "semantic_search",
+                "description": "Find the rows whose text column is most semantically similar to a query string, ranked by embedding cosine similarity",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dataset_id": {
+                            "type": "string",
+                            "description": "The ID of the dataset to search"
+                        },
+                        "text_column": {
+                            "type": "string",
+                            "description": "The name of the text column to embed and search over"
+                        },
+                        "query": {
+                            "type": "string",
+                            "description": "The free-text query to embed and match against"
+                        },
+                        "k": {
+                            "type": "integer",
+                            "description": "The maximum number of matches to return"
+                        }
+                    },
+                    "required": ["dataset_id", "text_column", "query", "k"]
+                }
+            },
+            {
+                "name": "add_shared_dataset",
+                "description": "Register a Delta Sharing table as a dataset, resolving its pre-signed Parquet files without copying the data",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": {
+                            "type": "string",
+                            "description": "Display name for the new dataset"
+                        },
+                        "endpoint": {
+                            "type": "string",
+                            "description": "The Delta Sharing server's endpoint URL, from the share profile"
+                        },
+                        "bearer_token": {
+                            "type": "string",
+                            "description": "The short-lived bearer token from the share profile"
+                        },
+                        "share": {
+                            "type": "string",
+                            "description": "The share name"
+                        },
+                        "schema": {
+                            "type": "string",
+                            "description": "The schema name within the share"
+                        },
+                        "table": {
+                            "type": "string",
+                            "description": "The table name within the schema"
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "Optional description for the dataset"
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": {"type": "string"}
+                        },
+                        "partition_filters": {
+                            "type": "object",
+                            "description": "Optional partition column/value pairs to restrict which files are imported"
+                        }
+                    },
+                    "required": ["name", "endpoint", "bearer_token", "share", "schema", "table"]
+                }
+            },
             {
                 "name": "mcp_reader-servic_query_dataset",
                 "description": "Query dataset for VS Code extension compatibility",
@@ -232,6 +814,44 @@ fn handle_tools_list(id: Option<serde_json::Value>) -> McpResponse {
                     },
                     "required": ["datasets"]
                 }
+            },
+            {
+                "name": "submit_query",
+                "description": "Submit a SQL query against a dataset as a background job and return its job id immediately, instead of waiting for the query to finish. Poll get_job_status with the returned job id for the result.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "dataset_id": {
+                            "type": "string",
+                            "description": "The ID of the dataset to query"
+                        },
+                        "sql_query": {
+                            "type": "string",
+                            "description": "The SQL query to execute"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of rows to return (optional)",
+                            "minimum": 1,
+                            "maximum": 10000
+                        }
+                    },
+                    "required": ["dataset_id", "sql_query"]
+                }
+            },
+            {
+                "name": "get_job_status",
+                "description": "Check on a job submitted with submit_query, returning its status and, once done, its result",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "job_id": {
+                            "type": "string",
+                            "description": "The job id returned by submit_query"
+                        }
+                    },
+                    "required": ["job_id"]
+                }
             }
         ]
     });
@@ -246,6 +866,7 @@ fn handle_tools_list(id: Option<serde_json::Value>) -> McpResponse {
 
 async fn handle_tool_call(
     query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
     id: Option<serde_json::Value>,
     params: Option<serde_json::Value>,
 ) -> McpResponse {
@@ -287,11 +908,22 @@ async fn handle_tool_call(
         .unwrap_or(serde_json::json!({}));
 
     let result = match tool_name {
-        "list_datasets" => handle_list_datasets(query_client).await,
-        "get_metadata" => handle_get_metadata(query_client, arguments).await,
-        "execute_query" => handle_execute_query(query_client, arguments).await,
+        "list_datasets" => handle_list_datasets(query_client, principal).await,
+        "get_metadata" => handle_get_metadata(query_client, principal, arguments).await,
+        "execute_query" => handle_execute_query(query_client, principal, arguments).await,
+        "delete_dataset" => handle_delete_dataset(query_client, principal, arguments).await,
+        "update_dataset_metadata" => {
+            handle_update_dataset_metadata(query_client, principal, arguments).await
+        }
+        "refresh_dataset" => handle_refresh_dataset(query_client, principal, arguments).await,
+        "resync_dataset" => handle_resync_dataset(query_client, principal, arguments).await,
+        "list_shared_tables" => handle_list_shared_tables(query_client, principal, arguments).await,
+        "add_shared_dataset" => handle_add_shared_dataset(query_client, principal, arguments).await,
+        "semantic_search" => handle_semantic_search(query_client, principal, arguments).await,
+        "submit_query" => handle_submit_query(query_client, principal, arguments).await,
+        "get_job_status" => handle_get_job_status(query_client, principal, arguments).await,
         "mcp_reader-servic_query_dataset" => {
-            handle_vscode_query_dataset(query_client, arguments).await
+            handle_vscode_query_dataset(query_client, principal, arguments).await
         }
         _ => Err(AnalysisError::ConfigError {
             message: format!("Unknown tool: {}", tool_name),
@@ -312,6 +944,16 @@ async fn handle_tool_call(
             })),
             error: None,
         },
+        Err(AnalysisError::AccessDenied { message }) => McpResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(McpError {
+                code: -32001,
+                message,
+                data: None,
+            }),
+        },
         Err(e) => McpResponse {
             jsonrpc: "2.0".to_string(),
             id,
@@ -327,15 +969,18 @@ async fn handle_tool_call(
 
 async fn handle_list_datasets(
     query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
 ) -> Result<String, AnalysisError> {
     let mut client = query_client.lock().await;
-    let datasets = client.list_datasets().await?;
+    let mut datasets = client.list_datasets().await?;
+    datasets.retain(|d| principal.can(&d.dataset_id, DatasetOperation::List));
     let response = serde_json::to_string_pretty(&datasets)?;
     Ok(response)
 }
 
 async fn handle_get_metadata(
     query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
     arguments: serde_json::Value,
 ) -> Result<String, AnalysisError> {
     let params: GetMetadataParams =
@@ -343,6 +988,15 @@ async fn handle_get_metadata(
             message: format!("Invalid arguments for get_metadata: {}", e),
         })?;
 
+    if !principal.can(&params.dataset_id, DatasetOperation::Metadata) {
+        return Err(AnalysisError::AccessDenied {
+            message: format!(
+                "{} is not granted metadata access to dataset {}",
+                principal.name, params.dataset_id
+            ),
+        });
+    }
+
     let mut client = query_client.lock().await;
     let metadata = client.get_metadata(&params.dataset_id).await?;
     let response = serde_json::to_string_pretty(&metadata)?;
@@ -351,6 +1005,7 @@ async fn handle_get_metadata(
 
 async fn handle_execute_query(
     query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
     arguments: serde_json::Value,
 ) -> Result<String, AnalysisError> {
     let params: ExecuteQueryParams =
@@ -358,6 +1013,15 @@ async fn handle_execute_query(
             message: format!("Invalid arguments for execute_query: {}", e),
         })?;
 
+    if !principal.can(&params.dataset_id, DatasetOperation::Query) {
+        return Err(AnalysisError::AccessDenied {
+            message: format!(
+                "{} is not granted query access to dataset {}",
+                principal.name, params.dataset_id
+            ),
+        });
+    }
+
     let mut client = query_client.lock().await;
     let result = client
         .execute_query(&params.dataset_id, &params.sql_query, params.limit)
@@ -373,6 +1037,324 @@ async fn handle_execute_query(
     Ok(serde_json::to_string_pretty(&response)?)
 }
 
+async fn handle_delete_dataset(
+    query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
+    arguments: serde_json::Value,
+) -> Result<String, AnalysisError> {
+    let params: DeleteDatasetParams =
+        serde_json::from_value(arguments).map_err(|e| AnalysisError::ConfigError {
+            message: format!("Invalid arguments for delete_dataset: {}", e),
+        })?;
+
+    if !principal.can(&params.dataset_id, DatasetOperation::Manage) {
+        return Err(AnalysisError::AccessDenied {
+            message: format!(
+                "{} is not granted manage access to dataset {}",
+                principal.name, params.dataset_id
+            ),
+        });
+    }
+
+    let mut client = query_client.lock().await;
+    client.delete_dataset(&params.dataset_id).await?;
+
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "dataset_id": params.dataset_id,
+        "deleted": true
+    }))?)
+}
+
+async fn handle_update_dataset_metadata(
+    query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
+    arguments: serde_json::Value,
+) -> Result<String, AnalysisError> {
+    let params: UpdateDatasetMetadataParams =
+        serde_json::from_value(arguments).map_err(|e| AnalysisError::ConfigError {
+            message: format!("Invalid arguments for update_dataset_metadata: {}", e),
+        })?;
+
+    if !principal.can(&params.dataset_id, DatasetOperation::Manage) {
+        return Err(AnalysisError::AccessDenied {
+            message: format!(
+                "{} is not granted manage access to dataset {}",
+                principal.name, params.dataset_id
+            ),
+        });
+    }
+
+    let mut client = query_client.lock().await;
+    let metadata = client
+        .update_dataset_metadata(&params.dataset_id, params.description, params.tags)
+        .await?;
+
+    Ok(serde_json::to_string_pretty(&metadata)?)
+}
+
+async fn handle_refresh_dataset(
+    query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
+    arguments: serde_json::Value,
+) -> Result<String, AnalysisError> {
+    let params: RefreshDatasetParams =
+        serde_json::from_value(arguments).map_err(|e| AnalysisError::ConfigError {
+            message: format!("Invalid arguments for refresh_dataset: {}", e),
+        })?;
+
+    if !principal.can(&params.dataset_id, DatasetOperation::Manage) {
+        return Err(AnalysisError::AccessDenied {
+            message: format!(
+                "{} is not granted manage access to dataset {}",
+                principal.name, params.dataset_id
+            ),
+        });
+    }
+
+    let mut client = query_client.lock().await;
+    client.refresh_dataset(&params.dataset_id).await?;
+
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "dataset_id": params.dataset_id,
+        "refreshed": true
+    }))?)
+}
+
+async fn handle_resync_dataset(
+    query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
+    arguments: serde_json::Value,
+) -> Result<String, AnalysisError> {
+    let params: ResyncDatasetParams =
+        serde_json::from_value(arguments).map_err(|e| AnalysisError::ConfigError {
+            message: format!("Invalid arguments for resync_dataset: {}", e),
+        })?;
+
+    if !principal.can(&params.dataset_id, DatasetOperation::Manage) {
+        return Err(AnalysisError::AccessDenied {
+            message: format!(
+                "{} is not granted manage access to dataset {}",
+                principal.name, params.dataset_id
+            ),
+        });
+    }
+
+    let mut client = query_client.lock().await;
+    let summary = client.resync_dataset(&params.dataset_id).await?;
+
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "dataset_id": params.dataset_id,
+        "files_added": summary.files_added,
+        "files_updated": summary.files_updated,
+        "files_removed": summary.files_removed
+    }))?)
+}
+
+async fn handle_list_shared_tables(
+    query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
+    arguments: serde_json::Value,
+) -> Result<String, AnalysisError> {
+    if !principal.can_global(DatasetOperation::Manage) {
+        return Err(AnalysisError::AccessDenied {
+            message: format!(
+                "{} is not granted manage access to browse shared tables",
+                principal.name
+            ),
+        });
+    }
+
+    let params: ListSharedTablesParams =
+        serde_json::from_value(arguments).map_err(|e| AnalysisError::ConfigError {
+            message: format!("Invalid arguments for list_shared_tables: {}", e),
+        })?;
+
+    let mut client = query_client.lock().await;
+    let tables = client
+        .list_shared_tables(&params.endpoint, &params.bearer_token)
+        .await?;
+
+    let tables: Vec<_> = tables
+        .into_iter()
+        .map(|t| {
+            serde_json::json!({
+                "share": t.share,
+                "schema": t.schema,
+                "name": t.name
+            })
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&tables)?)
+}
+
+async fn handle_semantic_search(
+    query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
+    arguments: serde_json::Value,
+) -> Result<String, AnalysisError> {
+    let params: SemanticSearchParams =
+        serde_json::from_value(arguments).map_err(|e| AnalysisError::ConfigError {
+            message: format!("Invalid arguments for semantic_search: {}", e),
+        })?;
+
+    if !principal.can(&params.dataset_id, DatasetOperation::Query) {
+        return Err(AnalysisError::AccessDenied {
+            message: format!(
+                "{} is not granted query access to dataset {}",
+                principal.name, params.dataset_id
+            ),
+        });
+    }
+
+    let mut client = query_client.lock().await;
+    let matches = client
+        .semantic_search(
+            &params.dataset_id,
+            &params.text_column,
+            &params.query,
+            params.k,
+        )
+        .await?;
+
+    let matches: Vec<_> = matches
+        .into_iter()
+        .map(|m| {
+            serde_json::json!({
+                "row_id": m.row_id,
+                "score": m.score
+            })
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&matches)?)
+}
+
+async fn handle_submit_query(
+    query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
+    arguments: serde_json::Value,
+) -> Result<String, AnalysisError> {
+    let params: SubmitQueryParams =
+        serde_json::from_value(arguments).map_err(|e| AnalysisError::ConfigError {
+            message: format!("Invalid arguments for submit_query: {}", e),
+        })?;
+
+    if !principal.can(&params.dataset_id, DatasetOperation::Query) {
+        return Err(AnalysisError::AccessDenied {
+            message: format!(
+                "{} is not granted query access to dataset {}",
+                principal.name, params.dataset_id
+            ),
+        });
+    }
+
+    let mut client = query_client.lock().await;
+    let job_id = client
+        .submit_query_job(&params.dataset_id, &params.sql_query, params.limit)
+        .await?;
+
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "job_id": job_id
+    }))?)
+}
+
+/// Checks on a job submitted via `submit_query`. `get_job_status` only takes
+/// a `job_id`, not a `dataset_id`, so the grant check has to happen after
+/// the lookup: the job's own payload (which the query engine echoes back in
+/// `job_payload_json`) is parsed for the `dataset_id` it was submitted
+/// against, and the caller must hold `Query` access to it. This stops a
+/// principal from learning another dataset's query results just by
+/// guessing/enumerating job ids.
+async fn handle_get_job_status(
+    query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
+    arguments: serde_json::Value,
+) -> Result<String, AnalysisError> {
+    let params: GetJobStatusParams =
+        serde_json::from_value(arguments).map_err(|e| AnalysisError::ConfigError {
+            message: format!("Invalid arguments for get_job_status: {}", e),
+        })?;
+
+    let mut client = query_client.lock().await;
+    let job = client.get_job_status(&params.job_id).await?;
+
+    if !job.found {
+        return Ok(serde_json::to_string_pretty(&serde_json::json!({
+            "job_id": params.job_id,
+            "found": false
+        }))?);
+    }
+
+    let dataset_id = serde_json::from_str::<serde_json::Value>(&job.job_payload_json)
+        .ok()
+        .and_then(|payload| payload.get("dataset_id").and_then(|v| v.as_str()).map(str::to_string))
+        .ok_or_else(|| AnalysisError::ConfigError {
+            message: format!("Job {} has no recoverable dataset_id", params.job_id),
+        })?;
+
+    if !principal.can(&dataset_id, DatasetOperation::Query) {
+        return Err(AnalysisError::AccessDenied {
+            message: format!(
+                "{} is not granted query access to dataset {}",
+                principal.name, dataset_id
+            ),
+        });
+    }
+
+    let result: Option<serde_json::Value> = if job.result_json.is_empty() {
+        None
+    } else {
+        serde_json::from_str(&job.result_json).ok()
+    };
+
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "job_id": params.job_id,
+        "found": true,
+        "status": job.status,
+        "result": result
+    }))?)
+}
+
+async fn handle_add_shared_dataset(
+    query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
+    arguments: serde_json::Value,
+) -> Result<String, AnalysisError> {
+    if !principal.can_global(DatasetOperation::Manage) {
+        return Err(AnalysisError::AccessDenied {
+            message: format!(
+                "{} is not granted manage access to register new datasets",
+                principal.name
+            ),
+        });
+    }
+
+    let params: AddSharedDatasetParams =
+        serde_json::from_value(arguments).map_err(|e| AnalysisError::ConfigError {
+            message: format!("Invalid arguments for add_shared_dataset: {}", e),
+        })?;
+
+    let mut client = query_client.lock().await;
+    let dataset_id = client
+        .add_shared_dataset(
+            &params.name,
+            &params.endpoint,
+            &params.bearer_token,
+            &params.share,
+            &params.schema,
+            &params.table,
+            params.description,
+            params.tags,
+            params.partition_filters,
+        )
+        .await?;
+
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "dataset_id": dataset_id
+    }))?)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct VsCodeDatasetQuery {
     datasets: Vec<VsCodeDataset>,
@@ -391,6 +1373,7 @@ struct VsCodeDataset {
 
 async fn handle_vscode_query_dataset(
     query_client: Arc<Mutex<QueryEngineClient>>,
+    principal: &Principal,
     arguments: serde_json::Value,
 ) -> Result<String, AnalysisError> {
     let params: VsCodeDatasetQuery =
@@ -407,11 +1390,44 @@ async fn handle_vscode_query_dataset(
         });
     }
 
-    let dataset = &params.datasets[0];
+    for dataset in &params.datasets {
+        if !principal.can(&dataset.path, DatasetOperation::Query) {
+            return Err(AnalysisError::AccessDenied {
+                message: format!(
+                    "{} is not granted query access to dataset {}",
+                    principal.name, dataset.path
+                ),
+            });
+        }
+    }
+
     let mut client = query_client.lock().await;
-    let result = client
-        .execute_query(&dataset.path, &dataset.sql, params.limit)
-        .await?;
+    let result = if params.datasets.len() == 1 {
+        let dataset = &params.datasets[0];
+        client
+            .execute_query(&dataset.path, &dataset.sql, params.limit)
+            .await?
+    } else {
+        // Every dataset is registered under its own `name` as the table
+        // alias so `sql` can join across them; we only need one of them to
+        // actually carry the combined query, so the first one that has
+        // non-empty SQL wins.
+        let aliases: Vec<(String, String)> = params
+            .datasets
+            .iter()
+            .map(|d| (d.name.clone(), d.path.clone()))
+            .collect();
+        let sql_query = params
+            .datasets
+            .iter()
+            .find(|d| !d.sql.is_empty())
+            .map(|d| d.sql.as_str())
+            .unwrap_or("");
+
+        client
+            .execute_multi_query(&aliases, sql_query, params.limit)
+            .await?
+    };
 
     if params.result_only.unwrap_or(false) {
         Ok(serde_json::to_string_pretty(&result.rows)?)
@@ -427,9 +1443,9 @@ async fn handle_vscode_query_dataset(
 }
 
 async fn health_check(
-    State(query_client): State<Arc<Mutex<QueryEngineClient>>>,
+    State(state): State<AppState>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let mut client = query_client.lock().await;
+    let mut client = state.query_client.lock().await;
     match client.health_check().await {
         Ok(_) => Ok(Json(serde_json::json!({
             "status": "healthy",
@@ -1,141 +1,893 @@
-use arrow::array::Array;
-use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
 use tonic::transport::Channel;
+use tonic::Code;
 use tracing::{error, info};
 
+use crate::cell_value::{decode_arrow_ipc_chunk, CellValue};
 use crate::error::AnalysisError;
 use crate::proto::analysis::{
-    analysis_service_client::AnalysisServiceClient, Dataset, DatasetMetadata, ExecuteQueryRequest,
-    GetMetadataRequest, ListDatasetsRequest,
+    analysis_service_client::AnalysisServiceClient, AddSharedDatasetRequest, Dataset,
+    DatasetAlias, DatasetMetadata, DeleteDatasetRequest, ExecuteMultiQueryRequest,
+    ExecuteQueryRequest, GetJobStatusRequest, GetMetadataRequest, HealthCheckRequest,
+    ListDatasetsRequest, ListSharedTablesRequest, RefreshDatasetRequest, ResyncDatasetRequest,
+    SemanticSearchRequest, SubmitQueryJobRequest, UpdateDatasetMetadataRequest,
+    WatchCatalogRequest,
 };
+use crate::tls::TlsConfig;
+
+const MAX_RETRY_ATTEMPTS: usize = 3;
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Re-maps a `tonic::Status` returned by the query engine into the
+/// `AnalysisError` variant it actually represents, so callers can
+/// distinguish a bad query from a missing dataset from a transient,
+/// retryable outage instead of seeing everything collapse to
+/// `ConfigError`.
+fn map_grpc_status(dataset_id: &str, status: tonic::Status) -> AnalysisError {
+    match status.code() {
+        Code::NotFound => AnalysisError::DatasetNotFound {
+            dataset_id: dataset_id.to_string(),
+        },
+        Code::InvalidArgument => AnalysisError::QueryExecutionFailed {
+            message: status.message().to_string(),
+        },
+        Code::ResourceExhausted | Code::Unavailable => AnalysisError::ResourceExhausted {
+            message: status.message().to_string(),
+        },
+        _ => AnalysisError::ConfigError {
+            message: format!("gRPC call failed: {}", status),
+        },
+    }
+}
+
+/// `INVALID_ARGUMENT` means the query itself is malformed, so retrying it
+/// against a different endpoint would just fail the same way. Every other
+/// code (including `NOT_FOUND`, since a replicated backend's endpoints
+/// don't necessarily all expose the same datasets) is worth a retry.
+fn is_retryable_code(code: Code) -> bool {
+    code != Code::InvalidArgument
+}
+
+/// One lazily-decoded piece of an `execute_query_stream` result: either a
+/// chunk of rows (`chunk_index: Some(_)`) or the final marker batch sent
+/// when the query completes (`chunk_index: None`), carrying the
+/// server-reported execution time instead of a data chunk.
+#[derive(Debug, Clone)]
+pub struct RowBatch {
+    pub column_names: Vec<String>,
+    pub rows: Vec<Vec<CellValue>>,
+    pub chunk_index: Option<i32>,
+    pub execution_time_ms: Option<u64>,
+}
+
+/// Counts of what a `resync_dataset` call changed, mirrored from
+/// `ResyncDatasetResponse` so callers on this side of the gRPC boundary
+/// don't have to depend on the generated response type directly.
+#[derive(Debug, Clone, Copy)]
+pub struct ResyncSummary {
+    pub files_added: i32,
+    pub files_updated: i32,
+    pub files_removed: i32,
+}
+
+/// A job's current status/result, mirrored from `GetJobStatusResponse` so
+/// callers on this side of the gRPC boundary don't have to depend on the
+/// generated proto type directly. `job_payload_json` is the JSON the job
+/// was submitted with, so a caller that only has a `job_id` (e.g. the MCP
+/// `get_job_status` tool) can recover which dataset it was scoped to.
+#[derive(Debug, Clone)]
+pub struct JobStatusInfo {
+    pub found: bool,
+    pub status: String,
+    pub job_payload_json: String,
+    pub result_json: String,
+}
+
+/// One table enumerated by `list_shared_tables`, mirrored from
+/// `SharedTableInfo` so callers don't have to depend on the generated proto
+/// type directly.
+#[derive(Debug, Clone)]
+pub struct SharedTableInfo {
+    pub share: String,
+    pub schema: String,
+    pub name: String,
+}
+
+/// One row returned by `semantic_search`, mirrored from `SemanticSearchMatch`
+/// so callers on this side of the gRPC boundary don't have to depend on the
+/// generated proto type directly.
+#[derive(Debug, Clone, Copy)]
+pub struct SemanticSearchMatch {
+    pub row_id: i64,
+    pub score: f32,
+}
 
 #[derive(Debug)]
 pub struct QueryResult {
-    pub rows: Vec<HashMap<String, String>>,
+    /// Each row is a `Vec<CellValue>` positionally aligned to
+    /// `column_names`, preserving the Arrow type of every cell instead of
+    /// stringifying it into a `HashMap<String, String>`.
+    pub rows: Vec<Vec<CellValue>>,
     pub column_names: Vec<String>,
     pub total_rows: usize,
     pub execution_time_ms: u64,
 }
 
+/// A single query engine connection plus the liveness state the background
+/// health-check loop and the round-robin selector need: whether it's
+/// currently considered healthy, and (while unhealthy) the backoff delay
+/// before it's worth re-probing again.
+#[derive(Debug)]
+struct Endpoint {
+    address: String,
+    client: AnalysisServiceClient<Channel>,
+    healthy: AtomicBool,
+    backoff: Mutex<Duration>,
+    next_probe_at: Mutex<Instant>,
+}
+
+/// Pools connections to every configured query engine endpoint and routes
+/// each RPC to a healthy one round-robin, failing over to the next endpoint
+/// on a transient transport error. A background task periodically probes
+/// endpoints via `HealthCheckRequest`, evicting ones that stop responding
+/// and re-probing them with exponential backoff before adding them back to
+/// rotation. This mirrors how a connection-pooled database driver keeps a
+/// replicated backend reachable without the caller noticing individual node
+/// failures.
 #[derive(Debug, Clone)]
 pub struct QueryEngineClient {
-    client: AnalysisServiceClient<Channel>,
+    endpoints: Arc<Vec<Endpoint>>,
+    next_index: Arc<AtomicUsize>,
 }
 
 impl QueryEngineClient {
-    pub async fn new(endpoint: String) -> Result<Self, AnalysisError> {
-        info!("Connecting to query engine at {}", endpoint);
+    pub async fn new(
+        endpoints: Vec<String>,
+        tls: Option<TlsConfig>,
+    ) -> Result<Self, AnalysisError> {
+        if endpoints.is_empty() {
+            return Err(AnalysisError::ConfigError {
+                message: "At least one query engine endpoint is required".to_string(),
+            });
+        }
+
+        let mut connected = Vec::with_capacity(endpoints.len());
+        for address in endpoints {
+            info!("Connecting to query engine at {}", address);
 
-        let client = AnalysisServiceClient::connect(endpoint)
-            .await
-            .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to connect to query engine: {}", e),
+            let mut channel = Channel::from_shared(address.clone()).map_err(|e| {
+                AnalysisError::ConfigError {
+                    message: format!("Invalid query engine endpoint {}: {}", address, e),
+                }
             })?;
 
-        info!("Successfully connected to query engine");
+            if let Some(tls) = tls.clone() {
+                channel = channel
+                    .tls_config(tls.into_client_tls_config()?)
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to configure TLS for {}: {}", address, e),
+                    })?;
+            }
+
+            let channel = channel
+                .connect()
+                .await
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!("Failed to connect to query engine {}: {}", address, e),
+                })?;
+
+            let client = AnalysisServiceClient::new(channel);
+
+            connected.push(Endpoint {
+                address,
+                client,
+                healthy: AtomicBool::new(true),
+                backoff: Mutex::new(INITIAL_BACKOFF),
+                next_probe_at: Mutex::new(Instant::now()),
+            });
+        }
+
+        info!("Successfully connected to {} query engine endpoint(s)", connected.len());
+
+        let client = Self {
+            endpoints: Arc::new(connected),
+            next_index: Arc::new(AtomicUsize::new(0)),
+        };
 
-        Ok(Self { client })
+        client.clone().spawn_health_check_loop();
+
+        Ok(client)
     }
 
-    pub async fn list_datasets(&mut self) -> Result<Vec<Dataset>, AnalysisError> {
-        let request = tonic::Request::new(ListDatasetsRequest {});
+    fn spawn_health_check_loop(self) {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+                self.probe_endpoints().await;
+            }
+        });
+    }
 
-        let response =
-            self.client
-                .list_datasets(request)
+    /// Re-probes every endpoint that's either currently healthy (to catch
+    /// regressions early) or unhealthy but past its backoff window.
+    async fn probe_endpoints(&self) {
+        for endpoint in self.endpoints.iter() {
+            let was_healthy = endpoint.healthy.load(Ordering::Relaxed);
+            if !was_healthy && *endpoint.next_probe_at.lock().unwrap() > Instant::now() {
+                continue;
+            }
+
+            let mut client = endpoint.client.clone();
+            let healthy = client
+                .health_check(tonic::Request::new(HealthCheckRequest {}))
                 .await
-                .map_err(|e| AnalysisError::ConfigError {
-                    message: format!("gRPC call failed: {}", e),
-                })?;
+                .is_ok();
+
+            if healthy {
+                if !was_healthy {
+                    info!("Query engine endpoint {} recovered", endpoint.address);
+                }
+                endpoint.healthy.store(true, Ordering::Relaxed);
+                *endpoint.backoff.lock().unwrap() = INITIAL_BACKOFF;
+            } else {
+                self.mark_unhealthy(endpoint);
+            }
+        }
+    }
 
-        Ok(response.into_inner().datasets)
+    /// Marks an endpoint unhealthy and schedules its next re-probe after the
+    /// current backoff, doubling the backoff (capped at `MAX_BACKOFF`) for
+    /// next time so a persistently down endpoint isn't hammered.
+    fn mark_unhealthy(&self, endpoint: &Endpoint) {
+        endpoint.healthy.store(false, Ordering::Relaxed);
+        let mut backoff = endpoint.backoff.lock().unwrap();
+        *endpoint.next_probe_at.lock().unwrap() = Instant::now() + *backoff;
+        error!(
+            "Query engine endpoint {} marked unhealthy, re-probing in {:?}",
+            endpoint.address, *backoff
+        );
+        *backoff = std::cmp::min(*backoff * 2, MAX_BACKOFF);
+    }
+
+    /// Picks the next endpoint round-robin, skipping any currently marked
+    /// unhealthy. Falls back to cycling through all endpoints (even
+    /// unhealthy ones) if none are healthy, so a total outage surfaces the
+    /// real RPC error rather than a synthetic "no endpoints" one.
+    fn next_client(&self) -> (usize, AnalysisServiceClient<Channel>) {
+        let len = self.endpoints.len();
+        let start = self.next_index.fetch_add(1, Ordering::Relaxed) % len;
+
+        for offset in 0..len {
+            let idx = (start + offset) % len;
+            if self.endpoints[idx].healthy.load(Ordering::Relaxed) {
+                return (idx, self.endpoints[idx].client.clone());
+            }
+        }
+
+        (start, self.endpoints[start].client.clone())
+    }
+
+    pub async fn list_datasets(&mut self) -> Result<Vec<Dataset>, AnalysisError> {
+        let mut last_err = None;
+
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+
+            match client.list_datasets(tonic::Request::new(ListDatasetsRequest {})).await {
+                Ok(response) => return Ok(response.into_inner().datasets),
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "list_datasets failed against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status("", status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AnalysisError::ConfigError {
+            message: "No healthy query engine endpoints available".to_string(),
+        }))
     }
 
     pub async fn get_metadata(
         &mut self,
         dataset_id: &str,
     ) -> Result<DatasetMetadata, AnalysisError> {
-        let request = tonic::Request::new(GetMetadataRequest {
+        let mut last_err = None;
+
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+            let request = tonic::Request::new(GetMetadataRequest {
+                dataset_id: dataset_id.to_string(),
+            });
+
+            match client.get_metadata(request).await {
+                Ok(response) => {
+                    return response.into_inner().metadata.ok_or_else(|| {
+                        AnalysisError::DatasetNotFound {
+                            dataset_id: dataset_id.to_string(),
+                        }
+                    });
+                }
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "get_metadata failed against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status(dataset_id, status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AnalysisError::DatasetNotFound {
             dataset_id: dataset_id.to_string(),
-        });
+        }))
+    }
 
-        let response =
-            self.client
-                .get_metadata(request)
-                .await
-                .map_err(|e| AnalysisError::ConfigError {
-                    message: format!("gRPC call failed: {}", e),
-                })?;
+    pub async fn delete_dataset(&mut self, dataset_id: &str) -> Result<(), AnalysisError> {
+        let mut last_err = None;
 
-        response
-            .into_inner()
-            .metadata
-            .ok_or_else(|| AnalysisError::DatasetNotFound {
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+            let request = tonic::Request::new(DeleteDatasetRequest {
                 dataset_id: dataset_id.to_string(),
-            })
+            });
+
+            match client.delete_dataset(request).await {
+                Ok(_) => return Ok(()),
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "delete_dataset failed against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status(dataset_id, status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AnalysisError::DatasetNotFound {
+            dataset_id: dataset_id.to_string(),
+        }))
     }
 
-    pub async fn execute_query(
+    pub async fn update_dataset_metadata(
+        &mut self,
+        dataset_id: &str,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> Result<DatasetMetadata, AnalysisError> {
+        let mut last_err = None;
+
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+            let request = tonic::Request::new(UpdateDatasetMetadataRequest {
+                dataset_id: dataset_id.to_string(),
+                description: description.clone().unwrap_or_default(),
+                tags: tags.clone().unwrap_or_default(),
+            });
+
+            match client.update_dataset_metadata(request).await {
+                Ok(response) => {
+                    return response.into_inner().metadata.ok_or_else(|| {
+                        AnalysisError::DatasetNotFound {
+                            dataset_id: dataset_id.to_string(),
+                        }
+                    });
+                }
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "update_dataset_metadata failed against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status(dataset_id, status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AnalysisError::DatasetNotFound {
+            dataset_id: dataset_id.to_string(),
+        }))
+    }
+
+    pub async fn refresh_dataset(&mut self, dataset_id: &str) -> Result<(), AnalysisError> {
+        let mut last_err = None;
+
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+            let request = tonic::Request::new(RefreshDatasetRequest {
+                dataset_id: dataset_id.to_string(),
+            });
+
+            match client.refresh_dataset(request).await {
+                Ok(_) => return Ok(()),
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "refresh_dataset failed against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status(dataset_id, status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AnalysisError::DatasetNotFound {
+            dataset_id: dataset_id.to_string(),
+        }))
+    }
+
+    pub async fn resync_dataset(&mut self, dataset_id: &str) -> Result<ResyncSummary, AnalysisError> {
+        let mut last_err = None;
+
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+            let request = tonic::Request::new(ResyncDatasetRequest {
+                dataset_id: dataset_id.to_string(),
+            });
+
+            match client.resync_dataset(request).await {
+                Ok(response) => {
+                    let response = response.into_inner();
+                    return Ok(ResyncSummary {
+                        files_added: response.files_added,
+                        files_updated: response.files_updated,
+                        files_removed: response.files_removed,
+                    });
+                }
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "resync_dataset failed against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status(dataset_id, status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AnalysisError::DatasetNotFound {
+            dataset_id: dataset_id.to_string(),
+        }))
+    }
+
+    /// Enqueues `sql_query` against `dataset_id` as a background job and
+    /// returns its id, for a caller that would rather poll `get_job_status`
+    /// than hold a request open for however long the query takes.
+    pub async fn submit_query_job(
         &mut self,
         dataset_id: &str,
         sql_query: &str,
         limit: Option<i32>,
-    ) -> Result<QueryResult, AnalysisError> {
-        let request = tonic::Request::new(ExecuteQueryRequest {
+    ) -> Result<String, AnalysisError> {
+        let mut last_err = None;
+
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+            let request = tonic::Request::new(SubmitQueryJobRequest {
+                dataset_id: dataset_id.to_string(),
+                sql_query: sql_query.to_string(),
+                limit: limit.unwrap_or(0),
+            });
+
+            match client.submit_query_job(request).await {
+                Ok(response) => return Ok(response.into_inner().job_id),
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "submit_query_job failed against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status(dataset_id, status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AnalysisError::DatasetNotFound {
             dataset_id: dataset_id.to_string(),
-            sql_query: sql_query.to_string(),
-            limit: limit.unwrap_or(1000),
-        });
+        }))
+    }
 
-        let mut stream = self
-            .client
-            .execute_query(request)
-            .await
-            .map_err(|e| AnalysisError::ConfigError {
-                message: format!("gRPC streaming call failed: {}", e),
-            })?
-            .into_inner();
+    /// Reads a job's current status/result after `submit_query_job`.
+    pub async fn get_job_status(&mut self, job_id: &str) -> Result<JobStatusInfo, AnalysisError> {
+        let mut last_err = None;
 
-        let mut all_rows = Vec::new();
-        let mut column_names = Vec::new();
-        let mut total_execution_time = 0u64;
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+            let request = tonic::Request::new(GetJobStatusRequest {
+                job_id: job_id.to_string(),
+            });
 
-        while let Some(response) =
-            stream
-                .message()
-                .await
-                .map_err(|e| AnalysisError::QueryExecutionFailed {
-                    message: format!("Stream error: {}", e),
-                })?
-        {
-            match response.response_type {
-                Some(crate::proto::analysis::execute_query_response::ResponseType::Metadata(
-                    metadata,
-                )) => {
-                    column_names = metadata.column_names;
-                    info!("Received metadata with {} columns", column_names.len());
+            match client.get_job_status(request).await {
+                Ok(response) => {
+                    let response = response.into_inner();
+                    return Ok(JobStatusInfo {
+                        found: response.found,
+                        status: response.status,
+                        job_payload_json: response.job_payload_json,
+                        result_json: response.result_json,
+                    });
                 }
-                Some(crate::proto::analysis::execute_query_response::ResponseType::DataChunk(
-                    chunk,
-                )) => {
-                    let chunk_rows =
-                        self.convert_arrow_ipc_to_rows(&chunk.arrow_ipc_data, &column_names)?;
-                    all_rows.extend(chunk_rows);
-                    info!("Processed chunk with {} rows", chunk.chunk_rows);
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "get_job_status failed against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status(job_id, status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
                 }
-                Some(crate::proto::analysis::execute_query_response::ResponseType::Complete(
-                    complete,
-                )) => {
-                    if let Ok(time_ms) = complete.execution_time_ms.parse::<u64>() {
-                        total_execution_time = time_ms;
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AnalysisError::QueryExecutionFailed {
+            message: format!("Failed to load job {}", job_id),
+        }))
+    }
+
+    pub async fn list_shared_tables(
+        &mut self,
+        endpoint: &str,
+        bearer_token: &str,
+    ) -> Result<Vec<SharedTableInfo>, AnalysisError> {
+        let mut last_err = None;
+
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+            let request = tonic::Request::new(ListSharedTablesRequest {
+                endpoint: endpoint.to_string(),
+                bearer_token: bearer_token.to_string(),
+            });
+
+            match client.list_shared_tables(request).await {
+                Ok(response) => {
+                    return Ok(response
+                        .into_inner()
+                        .tables
+                        .into_iter()
+                        .map(|t| SharedTableInfo {
+                            share: t.share,
+                            schema: t.schema,
+                            name: t.name,
+                        })
+                        .collect());
+                }
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "list_shared_tables failed against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status("", status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AnalysisError::ConfigError {
+            message: "No healthy query engine endpoint available".to_string(),
+        }))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_shared_dataset(
+        &mut self,
+        name: &str,
+        endpoint: &str,
+        bearer_token: &str,
+        share: &str,
+        schema: &str,
+        table: &str,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
+        partition_filters: std::collections::HashMap<String, String>,
+    ) -> Result<String, AnalysisError> {
+        let mut last_err = None;
+
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+            let request = tonic::Request::new(AddSharedDatasetRequest {
+                name: name.to_string(),
+                endpoint: endpoint.to_string(),
+                bearer_token: bearer_token.to_string(),
+                share: share.to_string(),
+                schema: schema.to_string(),
+                table: table.to_string(),
+                description: description.clone().unwrap_or_default(),
+                tags: tags.clone().unwrap_or_default(),
+                partition_filters: partition_filters.clone(),
+            });
+
+            match client.add_shared_dataset(request).await {
+                Ok(response) => {
+                    let response = response.into_inner();
+                    if response.success {
+                        return Ok(response.dataset_id);
                     }
-                    info!("Query completed in {}ms", total_execution_time);
+                    return Err(AnalysisError::ConfigError {
+                        message: response.message,
+                    });
+                }
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "add_shared_dataset failed against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status(share, status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AnalysisError::ConfigError {
+            message: "No healthy query engine endpoint available".to_string(),
+        }))
+    }
+
+    /// Finds the `k` rows of `dataset_id` whose `text_column` value is most
+    /// similar in meaning to `query`, ranked by cosine similarity.
+    pub async fn semantic_search(
+        &mut self,
+        dataset_id: &str,
+        text_column: &str,
+        query: &str,
+        k: i32,
+    ) -> Result<Vec<SemanticSearchMatch>, AnalysisError> {
+        let mut last_err = None;
+
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+            let request = tonic::Request::new(SemanticSearchRequest {
+                dataset_id: dataset_id.to_string(),
+                text_column: text_column.to_string(),
+                query: query.to_string(),
+                k,
+            });
+
+            match client.semantic_search(request).await {
+                Ok(response) => {
+                    return Ok(response
+                        .into_inner()
+                        .matches
+                        .into_iter()
+                        .map(|m| SemanticSearchMatch {
+                            row_id: m.row_id,
+                            score: m.score,
+                        })
+                        .collect());
+                }
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "semantic_search failed against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status(dataset_id, status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AnalysisError::ConfigError {
+            message: "No healthy query engine endpoint available".to_string(),
+        }))
+    }
+
+    /// Opens `execute_query` against a healthy endpoint (retrying endpoint
+    /// selection, not the query itself, the same way the other RPCs do) and
+    /// returns a `Stream` that lazily decodes each `DataChunk` into a
+    /// `RowBatch` as it arrives. The server's `mpsc::channel(32)` and this
+    /// stream's own bounded channel mean a slow consumer naturally slows
+    /// the producer instead of the whole result set being buffered in RAM.
+    /// A transport failure once the stream is open ends it with an `Err`
+    /// item rather than retrying on another endpoint, since some rows may
+    /// already have reached the caller.
+    pub async fn execute_query_stream(
+        &mut self,
+        dataset_id: &str,
+        sql_query: &str,
+        limit: Option<i32>,
+    ) -> Result<ReceiverStream<Result<RowBatch, AnalysisError>>, AnalysisError> {
+        let mut last_err = None;
+        let mut opened = None;
+
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+            let request = tonic::Request::new(ExecuteQueryRequest {
+                dataset_id: dataset_id.to_string(),
+                sql_query: sql_query.to_string(),
+                limit: limit.unwrap_or(1000),
+            });
+
+            match client.execute_query(request).await {
+                Ok(response) => {
+                    opened = Some(response.into_inner());
                     break;
                 }
-                None => {
-                    error!("Received empty response content");
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "execute_query_stream failed to open stream against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status(dataset_id, status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        let mut stream = opened.ok_or_else(|| {
+            last_err.unwrap_or_else(|| AnalysisError::QueryExecutionFailed {
+                message: "No healthy query engine endpoints available".to_string(),
+            })
+        })?;
+
+        let (tx, rx) = mpsc::channel(8);
+        let dataset_id = dataset_id.to_string();
+
+        tokio::spawn(async move {
+            let mut column_names = Vec::new();
+
+            loop {
+                let response = match stream.message().await {
+                    Ok(Some(response)) => response,
+                    Ok(None) => return,
+                    Err(status) => {
+                        let _ = tx.send(Err(map_grpc_status(&dataset_id, status))).await;
+                        return;
+                    }
+                };
+
+                match response.response_type {
+                    Some(crate::proto::analysis::execute_query_response::ResponseType::Metadata(
+                        metadata,
+                    )) => {
+                        column_names = metadata.column_names;
+                    }
+                    Some(crate::proto::analysis::execute_query_response::ResponseType::DataChunk(
+                        chunk,
+                    )) => {
+                        let rows =
+                            match decode_arrow_ipc_chunk(&chunk.arrow_ipc_data, &column_names) {
+                                Ok(rows) => rows,
+                                Err(e) => {
+                                    let _ = tx.send(Err(e)).await;
+                                    return;
+                                }
+                            };
+
+                        let batch = RowBatch {
+                            column_names: column_names.clone(),
+                            rows,
+                            chunk_index: Some(chunk.chunk_index),
+                            execution_time_ms: None,
+                        };
+
+                        if tx.send(Ok(batch)).await.is_err() {
+                            return; // Receiver dropped
+                        }
+                    }
+                    Some(crate::proto::analysis::execute_query_response::ResponseType::Complete(
+                        complete,
+                    )) => {
+                        if !complete.success {
+                            let _ = tx
+                                .send(Err(AnalysisError::QueryExecutionFailed {
+                                    message: complete.error_message,
+                                }))
+                                .await;
+                            return;
+                        }
+
+                        let execution_time_ms = complete.execution_time_ms.parse().ok();
+                        let _ = tx
+                            .send(Ok(RowBatch {
+                                column_names: column_names.clone(),
+                                rows: Vec::new(),
+                                chunk_index: None,
+                                execution_time_ms,
+                            }))
+                            .await;
+                        return;
+                    }
+                    None => {
+                        error!("Received empty response content");
+                    }
                 }
             }
+        });
+
+        Ok(ReceiverStream::new(rx))
+    }
+
+    pub async fn execute_query(
+        &mut self,
+        dataset_id: &str,
+        sql_query: &str,
+        limit: Option<i32>,
+    ) -> Result<QueryResult, AnalysisError> {
+        let mut stream = self.execute_query_stream(dataset_id, sql_query, limit).await?;
+
+        let mut all_rows = Vec::new();
+        let mut column_names = Vec::new();
+        let mut execution_time_ms = 0u64;
+
+        while let Some(item) = stream.next().await {
+            let batch = item?;
+            if !batch.column_names.is_empty() {
+                column_names = batch.column_names;
+            }
+            all_rows.extend(batch.rows);
+            if let Some(time_ms) = batch.execution_time_ms {
+                execution_time_ms = time_ms;
+            }
         }
 
         let total_rows = all_rows.len();
@@ -143,166 +895,255 @@ impl QueryEngineClient {
             rows: all_rows,
             column_names,
             total_rows,
-            execution_time_ms: total_execution_time,
+            execution_time_ms,
         })
     }
 
-    fn convert_arrow_ipc_to_rows(
-        &self,
-        arrow_data: &[u8],
-        column_names: &[String],
-    ) -> Result<Vec<HashMap<String, String>>, AnalysisError> {
-        use arrow::ipc::reader::StreamReader;
-        use std::io::Cursor;
-
-        info!(
-            "Converting Arrow IPC data ({} bytes) to rows for {} columns",
-            arrow_data.len(),
-            column_names.len()
-        );
+    /// Like `execute_query_stream`, but registers every `(alias, dataset_id)`
+    /// pair under its alias before running `sql_query` once, so a single
+    /// statement can join across datasets instead of being scoped to one.
+    pub async fn execute_multi_query_stream(
+        &mut self,
+        datasets: &[(String, String)],
+        sql_query: &str,
+        limit: Option<i32>,
+    ) -> Result<ReceiverStream<Result<RowBatch, AnalysisError>>, AnalysisError> {
+        let mut last_err = None;
+        let mut opened = None;
+
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+            let request = tonic::Request::new(ExecuteMultiQueryRequest {
+                datasets: datasets
+                    .iter()
+                    .map(|(alias, dataset_id)| DatasetAlias {
+                        alias: alias.clone(),
+                        dataset_id: dataset_id.clone(),
+                    })
+                    .collect(),
+                sql_query: sql_query.to_string(),
+                limit: limit.unwrap_or(1000),
+            });
 
-        if arrow_data.is_empty() {
-            return Ok(Vec::new());
+            match client.execute_multi_query(request).await {
+                Ok(response) => {
+                    opened = Some(response.into_inner());
+                    break;
+                }
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "execute_multi_query_stream failed to open stream against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status("", status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
         }
 
-        let cursor = Cursor::new(arrow_data);
-        let reader =
-            StreamReader::try_new(cursor, None).map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to create Arrow IPC reader: {}", e),
-            })?;
+        let mut stream = opened.ok_or_else(|| {
+            last_err.unwrap_or_else(|| AnalysisError::QueryExecutionFailed {
+                message: "No healthy query engine endpoints available".to_string(),
+            })
+        })?;
 
-        let mut all_rows = Vec::new();
+        let (tx, rx) = mpsc::channel(8);
 
-        for batch_result in reader {
-            let batch = batch_result.map_err(|e| AnalysisError::QueryExecutionFailed {
-                message: format!("Failed to read Arrow batch: {}", e),
-            })?;
+        tokio::spawn(async move {
+            let mut column_names = Vec::new();
 
-            let row_count = batch.num_rows();
-            for row_idx in 0..row_count {
-                let mut row = HashMap::new();
+            loop {
+                let response = match stream.message().await {
+                    Ok(Some(response)) => response,
+                    Ok(None) => return,
+                    Err(status) => {
+                        let _ = tx.send(Err(map_grpc_status("", status))).await;
+                        return;
+                    }
+                };
 
-                for (col_idx, column_name) in column_names.iter().enumerate() {
-                    if col_idx < batch.num_columns() {
-                        let column = batch.column(col_idx);
-                        let value = self.extract_arrow_value_as_string(column.as_ref(), row_idx);
-                        row.insert(column_name.clone(), value);
+                match response.response_type {
+                    Some(crate::proto::analysis::execute_query_response::ResponseType::Metadata(
+                        metadata,
+                    )) => {
+                        column_names = metadata.column_names;
                     }
-                }
+                    Some(crate::proto::analysis::execute_query_response::ResponseType::DataChunk(
+                        chunk,
+                    )) => {
+                        let rows =
+                            match decode_arrow_ipc_chunk(&chunk.arrow_ipc_data, &column_names) {
+                                Ok(rows) => rows,
+                                Err(e) => {
+                                    let _ = tx.send(Err(e)).await;
+                                    return;
+                                }
+                            };
 
-                all_rows.push(row);
+                        let batch = RowBatch {
+                            column_names: column_names.clone(),
+                            rows,
+                            chunk_index: Some(chunk.chunk_index),
+                            execution_time_ms: None,
+                        };
+
+                        if tx.send(Ok(batch)).await.is_err() {
+                            return; // Receiver dropped
+                        }
+                    }
+                    Some(crate::proto::analysis::execute_query_response::ResponseType::Complete(
+                        complete,
+                    )) => {
+                        if !complete.success {
+                            let _ = tx
+                                .send(Err(AnalysisError::QueryExecutionFailed {
+                                    message: complete.error_message,
+                                }))
+                                .await;
+                            return;
+                        }
+
+                        let execution_time_ms = complete.execution_time_ms.parse().ok();
+                        let _ = tx
+                            .send(Ok(RowBatch {
+                                column_names: column_names.clone(),
+                                rows: Vec::new(),
+                                chunk_index: None,
+                                execution_time_ms,
+                            }))
+                            .await;
+                        return;
+                    }
+                    None => {
+                        error!("Received empty response content");
+                    }
+                }
             }
-        }
+        });
 
-        info!(
-            "Successfully converted Arrow IPC data to {} rows",
-            all_rows.len()
-        );
-        Ok(all_rows)
+        Ok(ReceiverStream::new(rx))
     }
 
-    fn extract_arrow_value_as_string(&self, array: &dyn Array, index: usize) -> String {
-        if array.is_null(index) {
-            return "NULL".to_string();
-        }
+    /// Buffered convenience wrapper over [`Self::execute_multi_query_stream`],
+    /// mirroring [`Self::execute_query`] for queries that span more than one
+    /// dataset.
+    pub async fn execute_multi_query(
+        &mut self,
+        datasets: &[(String, String)],
+        sql_query: &str,
+        limit: Option<i32>,
+    ) -> Result<QueryResult, AnalysisError> {
+        let mut stream = self
+            .execute_multi_query_stream(datasets, sql_query, limit)
+            .await?;
 
-        use arrow::array::*;
-        use arrow::datatypes::DataType;
+        let mut all_rows = Vec::new();
+        let mut column_names = Vec::new();
+        let mut execution_time_ms = 0u64;
 
-        match array.data_type() {
-            DataType::Boolean => {
-                let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
-                array.value(index).to_string()
-            }
-            DataType::Int8 => {
-                let array = array.as_any().downcast_ref::<Int8Array>().unwrap();
-                array.value(index).to_string()
-            }
-            DataType::Int16 => {
-                let array = array.as_any().downcast_ref::<Int16Array>().unwrap();
-                array.value(index).to_string()
-            }
-            DataType::Int32 => {
-                let array = array.as_any().downcast_ref::<Int32Array>().unwrap();
-                array.value(index).to_string()
-            }
-            DataType::Int64 => {
-                let array = array.as_any().downcast_ref::<Int64Array>().unwrap();
-                array.value(index).to_string()
-            }
-            DataType::UInt8 => {
-                let array = array.as_any().downcast_ref::<UInt8Array>().unwrap();
-                array.value(index).to_string()
-            }
-            DataType::UInt16 => {
-                let array = array.as_any().downcast_ref::<UInt16Array>().unwrap();
-                array.value(index).to_string()
-            }
-            DataType::UInt32 => {
-                let array = array.as_any().downcast_ref::<UInt32Array>().unwrap();
-                array.value(index).to_string()
+        while let Some(item) = stream.next().await {
+            let batch = item?;
+            if !batch.column_names.is_empty() {
+                column_names = batch.column_names;
             }
-            DataType::UInt64 => {
-                let array = array.as_any().downcast_ref::<UInt64Array>().unwrap();
-                array.value(index).to_string()
+            all_rows.extend(batch.rows);
+            if let Some(time_ms) = batch.execution_time_ms {
+                execution_time_ms = time_ms;
             }
-            DataType::Float32 => {
-                let array = array.as_any().downcast_ref::<Float32Array>().unwrap();
-                array.value(index).to_string()
-            }
-            DataType::Float64 => {
-                let array = array.as_any().downcast_ref::<Float64Array>().unwrap();
-                array.value(index).to_string()
-            }
-            DataType::Utf8 => {
-                let array = array.as_any().downcast_ref::<StringArray>().unwrap();
-                array.value(index).to_string()
-            }
-            DataType::LargeUtf8 => {
-                let array = array.as_any().downcast_ref::<LargeStringArray>().unwrap();
-                array.value(index).to_string()
-            }
-            DataType::Date32 => {
-                let array = array.as_any().downcast_ref::<Date32Array>().unwrap();
-                let days = array.value(index);
-                let date = chrono::NaiveDate::from_num_days_from_ce_opt(days + 719163);
-                date.map(|d| d.to_string())
-                    .unwrap_or_else(|| "Invalid Date".to_string())
-            }
-            DataType::Date64 => {
-                let array = array.as_any().downcast_ref::<Date64Array>().unwrap();
-                let millis = array.value(index);
-                let datetime = chrono::DateTime::from_timestamp_millis(millis);
-                datetime
-                    .map(|dt| dt.format("%Y-%m-%d").to_string())
-                    .unwrap_or_else(|| "Invalid Date".to_string())
-            }
-            DataType::Timestamp(unit, _) => {
-                use arrow::datatypes::TimeUnit;
-                let array = array
-                    .as_any()
-                    .downcast_ref::<TimestampNanosecondArray>()
-                    .unwrap();
-                let nanos = array.value(index);
-                let seconds = match unit {
-                    TimeUnit::Second => nanos,
-                    TimeUnit::Millisecond => nanos / 1_000_000,
-                    TimeUnit::Microsecond => nanos / 1_000,
-                    TimeUnit::Nanosecond => nanos / 1_000_000_000,
-                };
-                let datetime = chrono::DateTime::from_timestamp(seconds, 0);
-                datetime
-                    .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
-                    .unwrap_or_else(|| "Invalid Timestamp".to_string())
-            }
-            _ => {
-                format!("{:?}", array.slice(index, 1))
+        }
+
+        let total_rows = all_rows.len();
+        Ok(QueryResult {
+            rows: all_rows,
+            column_names,
+            total_rows,
+            execution_time_ms,
+        })
+    }
+
+    /// Opens `watch_catalog` against a healthy endpoint and returns a
+    /// `Stream` of changed dataset ids. Like `execute_query_stream`, only
+    /// opening the stream is retried across endpoints; a transport failure
+    /// once it's open ends it with an `Err` item rather than silently
+    /// reconnecting elsewhere, since the caller would otherwise have no way
+    /// to tell it may have missed notifications in between.
+    pub async fn watch_catalog(
+        &mut self,
+    ) -> Result<ReceiverStream<Result<String, AnalysisError>>, AnalysisError> {
+        let mut last_err = None;
+        let mut opened = None;
+
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            let (idx, mut client) = self.next_client();
+
+            match client.watch_catalog(tonic::Request::new(WatchCatalogRequest {})).await {
+                Ok(response) => {
+                    opened = Some(response.into_inner());
+                    break;
+                }
+                Err(status) => {
+                    let retryable = is_retryable_code(status.code());
+                    error!(
+                        "watch_catalog failed to open stream against {} (attempt {}/{}): {}",
+                        self.endpoints[idx].address, attempt, MAX_RETRY_ATTEMPTS, status
+                    );
+                    self.mark_unhealthy(&self.endpoints[idx]);
+                    let err = map_grpc_status("", status);
+                    if !retryable {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
             }
         }
+
+        let mut stream = opened.ok_or_else(|| {
+            last_err.unwrap_or_else(|| AnalysisError::ConfigError {
+                message: "No healthy query engine endpoints available".to_string(),
+            })
+        })?;
+
+        let (tx, rx) = mpsc::channel(32);
+
+        tokio::spawn(async move {
+            loop {
+                match stream.message().await {
+                    Ok(Some(response)) => {
+                        if tx.send(Ok(response.dataset_id)).await.is_err() {
+                            return; // Receiver dropped
+                        }
+                    }
+                    Ok(None) => return,
+                    Err(status) => {
+                        let _ = tx.send(Err(map_grpc_status("", status))).await;
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(ReceiverStream::new(rx))
     }
 
-    pub async fn _health_check(&mut self) -> Result<(), AnalysisError> {
-        let _ = self.list_datasets().await?;
-        Ok(())
+    /// Reports whether the pool has at least one endpoint the background
+    /// health-check loop currently considers healthy, rather than issuing a
+    /// fresh RPC (that's what the background loop is already doing).
+    pub async fn health_check(&mut self) -> Result<(), AnalysisError> {
+        if self
+            .endpoints
+            .iter()
+            .any(|e| e.healthy.load(Ordering::Relaxed))
+        {
+            Ok(())
+        } else {
+            Err(AnalysisError::ResourceExhausted {
+                message: "No healthy query engine endpoints available".to_string(),
+            })
+        }
     }
 }
@@ -0,0 +1,79 @@
+use tonic::transport::{Certificate, ClientTlsConfig, Identity};
+
+use crate::error::AnalysisError;
+
+/// TLS material for dialing query engine endpoints, read from
+/// `TLS_CA_CERT_PATH` (a custom CA to trust instead of the system roots),
+/// `TLS_DOMAIN_NAME` (SNI/hostname override, for when an endpoint's address
+/// doesn't match the name on its certificate), and
+/// `TLS_CLIENT_CERT_PATH`/`TLS_CLIENT_KEY_PATH` (this client's own identity
+/// for mTLS). Absent entirely means `QueryEngineClient` dials plaintext,
+/// matching the previous behavior.
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub ca_cert_path: Option<String>,
+    pub domain_name: Option<String>,
+    pub client_cert_path: Option<String>,
+    pub client_key_path: Option<String>,
+}
+
+impl TlsConfig {
+    /// Returns `None` if none of the `TLS_*` variables are set.
+    pub fn from_env() -> Result<Option<Self>, AnalysisError> {
+        let ca_cert_path = std::env::var("TLS_CA_CERT_PATH").ok();
+        let domain_name = std::env::var("TLS_DOMAIN_NAME").ok();
+        let client_cert_path = std::env::var("TLS_CLIENT_CERT_PATH").ok();
+        let client_key_path = std::env::var("TLS_CLIENT_KEY_PATH").ok();
+
+        if ca_cert_path.is_none()
+            && domain_name.is_none()
+            && client_cert_path.is_none()
+            && client_key_path.is_none()
+        {
+            return Ok(None);
+        }
+
+        if client_cert_path.is_some() != client_key_path.is_some() {
+            return Err(AnalysisError::ConfigError {
+                message: "TLS_CLIENT_CERT_PATH and TLS_CLIENT_KEY_PATH must both be set to enable mTLS".to_string(),
+            });
+        }
+
+        Ok(Some(Self {
+            ca_cert_path,
+            domain_name,
+            client_cert_path,
+            client_key_path,
+        }))
+    }
+
+    pub fn into_client_tls_config(self) -> Result<ClientTlsConfig, AnalysisError> {
+        let mut tls = ClientTlsConfig::new();
+
+        tls = match &self.ca_cert_path {
+            Some(ca_path) => {
+                let ca = std::fs::read(ca_path).map_err(|e| AnalysisError::ConfigError {
+                    message: format!("Failed to read TLS CA cert {}: {}", ca_path, e),
+                })?;
+                tls.ca_certificate(Certificate::from_pem(ca))
+            }
+            None => tls.with_native_roots(),
+        };
+
+        if let Some(domain) = self.domain_name {
+            tls = tls.domain_name(domain);
+        }
+
+        if let (Some(cert_path), Some(key_path)) = (self.client_cert_path, self.client_key_path) {
+            let cert = std::fs::read(&cert_path).map_err(|e| AnalysisError::ConfigError {
+                message: format!("Failed to read TLS client cert {}: {}", cert_path, e),
+            })?;
+            let key = std::fs::read(&key_path).map_err(|e| AnalysisError::ConfigError {
+                message: format!("Failed to read TLS client key {}: {}", key_path, e),
+            })?;
+            tls = tls.identity(Identity::from_pem(cert, key));
+        }
+
+        Ok(tls)
+    }
+}
@@ -0,0 +1,51 @@
+use object_store::{azure::MicrosoftAzureBuilder, ObjectStore};
+use std::sync::Arc;
+use url::Url;
+
+use crate::error::AnalysisError;
+use crate::resilient_store::retry_config_from_env;
+
+/// Builds an Azure Blob Storage object store from an `azure://container/...` URL.
+///
+/// Ambient credentials (account name/key, SAS token, client secret) are picked up
+/// from the standard `AZURE_STORAGE_*` environment variables via
+/// [`MicrosoftAzureBuilder::from_env`]. Query params on the URL take precedence
+/// over the environment so a single process can talk to multiple accounts at once:
+///
+///   azure://my-container/path?account=myaccount&access_key=...
+///   &sas_token=...&endpoint=http://localhost:10000/devstoreaccount1
+pub fn create_azure_client(
+    container: &str,
+    source_url: &Url,
+) -> Result<Arc<dyn ObjectStore>, AnalysisError> {
+    let mut builder = MicrosoftAzureBuilder::from_env()
+        .with_container_name(container)
+        .with_retry(retry_config_from_env());
+
+    for (key, value) in source_url.query_pairs() {
+        builder = match key.as_ref() {
+            "account" => builder.with_account(value.as_ref()),
+            "access_key" => builder.with_access_key(value.as_ref()),
+            "sas_token" => builder.with_config(
+                object_store::azure::AzureConfigKey::SasKey,
+                value.as_ref(),
+            ),
+            "endpoint" => builder.with_endpoint(value.as_ref()),
+            "client_id" => builder.with_client_id(value.as_ref()),
+            "client_secret" => builder.with_client_secret(value.as_ref()),
+            "tenant_id" => builder.with_tenant_id(value.as_ref()),
+            _ => builder,
+        };
+    }
+
+    let store = builder
+        .build()
+        .map_err(|e| AnalysisError::ConfigError {
+            message: format!(
+                "Failed to create Azure Blob client for container '{}': {}",
+                container, e
+            ),
+        })?;
+
+    Ok(Arc::new(store))
+}
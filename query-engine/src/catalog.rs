@@ -1,14 +1,31 @@
+use diesel_derive_enum::DbEnum;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+use crate::error::AnalysisError;
+
+/// Backed by a native Postgres `data_format` enum (see the
+/// `native_data_format_enum` migration) rather than an unconstrained text
+/// column, so the database itself rejects a row with an invalid format
+/// instead of `load_metadata` having to silently coerce one.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::DataFormat"]
+#[DbValueStyle = "snake_case"]
 pub enum DataFormat {
     #[serde(rename = "csv")]
     Csv,
     #[serde(rename = "parquet")]
     Parquet,
+    #[serde(rename = "json")]
+    Json,
+    #[serde(rename = "ndjson")]
+    Ndjson,
+    #[serde(rename = "avro")]
+    Avro,
+    #[serde(rename = "arrow_ipc")]
+    ArrowIpc,
 }
 
 impl DataFormat {
@@ -16,6 +33,10 @@ impl DataFormat {
         match self {
             DataFormat::Csv => "csv",
             DataFormat::Parquet => "parquet",
+            DataFormat::Json => "json",
+            DataFormat::Ndjson => "ndjson",
+            DataFormat::Avro => "avro",
+            DataFormat::ArrowIpc => "arrow_ipc",
         }
     }
 }
@@ -26,6 +47,92 @@ impl std::fmt::Display for DataFormat {
     }
 }
 
+impl TryFrom<&str> for DataFormat {
+    type Error = AnalysisError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "csv" => Ok(DataFormat::Csv),
+            "parquet" => Ok(DataFormat::Parquet),
+            "json" => Ok(DataFormat::Json),
+            "ndjson" => Ok(DataFormat::Ndjson),
+            "avro" => Ok(DataFormat::Avro),
+            "arrow_ipc" | "arrow-ipc" => Ok(DataFormat::ArrowIpc),
+            other => Err(AnalysisError::ConfigError {
+                message: format!("Unrecognized dataset format: {}", other),
+            }),
+        }
+    }
+}
+
+/// Compression applied to a dataset's underlying files, independent of `DataFormat`
+/// (e.g. a CSV dataset can be stored as `.csv.gz`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum CompressionType {
+    #[serde(rename = "gzip")]
+    Gzip,
+    #[serde(rename = "bzip2")]
+    Bzip2,
+    #[serde(rename = "xz")]
+    Xz,
+    #[serde(rename = "zstd")]
+    Zstd,
+}
+
+impl CompressionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompressionType::Gzip => "gzip",
+            CompressionType::Bzip2 => "bzip2",
+            CompressionType::Xz => "xz",
+            CompressionType::Zstd => "zstd",
+        }
+    }
+}
+
+impl std::fmt::Display for CompressionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl TryFrom<&str> for CompressionType {
+    type Error = AnalysisError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "gzip" | "gz" => Ok(CompressionType::Gzip),
+            "bzip2" | "bz2" => Ok(CompressionType::Bzip2),
+            "xz" => Ok(CompressionType::Xz),
+            "zstd" | "zst" => Ok(CompressionType::Zstd),
+            other => Err(AnalysisError::ConfigError {
+                message: format!("Unrecognized compression type: {}", other),
+            }),
+        }
+    }
+}
+
+impl CompressionType {
+    /// Infers compression from a file's extension (e.g. `data.csv.gz` ->
+    /// `Gzip`), the same suffixes `datafusion_engine::file_extension` appends
+    /// when building a `ListingTableUrl` for a compressed format. Returns
+    /// `None` for an uncompressed file rather than erroring, since most files
+    /// are.
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        if filename.ends_with(".gz") {
+            Some(CompressionType::Gzip)
+        } else if filename.ends_with(".bz2") {
+            Some(CompressionType::Bzip2)
+        } else if filename.ends_with(".xz") {
+            Some(CompressionType::Xz)
+        } else if filename.ends_with(".zst") {
+            Some(CompressionType::Zstd)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatasetCatalog {
     pub version: String,
@@ -48,6 +155,18 @@ pub struct CatalogDatasetEntry {
     pub updated_at: DateTime<Utc>,
     pub dataset_path: String,
     pub metadata_path: String,
+    /// Upper bound on the dataset's total storage size, enforced at query time
+    /// against the live usage tracked by `DataFusionEngine`. `None` means unlimited.
+    #[serde(default)]
+    pub max_size_bytes: Option<i64>,
+    /// Upper bound on the dataset's total row count. `None` means unlimited.
+    #[serde(default)]
+    pub max_row_count: Option<i32>,
+    /// The external path this dataset was imported from (e.g. `s3://bucket/prefix/`),
+    /// kept around so `resync_dataset` can re-list it later. `None` for datasets
+    /// imported before this was tracked.
+    #[serde(default)]
+    pub source_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +176,26 @@ pub struct DatasetFile {
     pub size_bytes: i64,
     pub row_count: i32,
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub compression: Option<CompressionType>,
+    /// Hex-encoded SHA-256 digest of the file's bytes. `storage_path` is
+    /// derived from this digest (`blobs/{content_hash}`), so two files with
+    /// identical content - whether re-imported or shared across datasets -
+    /// resolve to the same blob. `None` only for files persisted before
+    /// content-addressed storage was introduced.
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// The `ObjectMeta::e_tag` reported by the source store when this file was
+    /// last copied, used by `resync_dataset` to tell whether the upstream
+    /// object has changed without re-reading its bytes. `None` if the source
+    /// store didn't report one (not every backend does).
+    #[serde(default)]
+    pub upstream_etag: Option<String>,
+    /// The `ObjectMeta::last_modified` reported by the source store when this
+    /// file was last copied, used alongside `upstream_etag` for the same
+    /// change-detection purpose.
+    #[serde(default)]
+    pub upstream_last_modified: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,6 +214,12 @@ pub struct DatasetMetadataFile {
     pub files: Vec<DatasetFile>,
     pub columns: Vec<ColumnMetadata>,
     pub statistics: HashMap<String, String>,
+    #[serde(default)]
+    pub max_size_bytes: Option<i64>,
+    #[serde(default)]
+    pub max_row_count: Option<i32>,
+    #[serde(default)]
+    pub source_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,217 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::catalog::{CatalogDatasetEntry, DatasetMetadataFile};
+
+const DEFAULT_TTL_SECS: u64 = 60;
+const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+/// TTL/size knobs for `DatasetManager`'s catalog cache, read from
+/// `CATALOG_CACHE_*` environment variables.
+#[derive(Debug, Clone)]
+pub struct CatalogCacheConfig {
+    pub ttl: Duration,
+    pub max_entries: usize,
+    /// Local-filesystem directory to cache `DatasetMetadataFile` blobs in.
+    /// `None` disables the on-disk cache (every `load_metadata` hits the
+    /// database directly).
+    pub metadata_cache_dir: Option<PathBuf>,
+}
+
+impl CatalogCacheConfig {
+    pub fn from_env() -> Self {
+        let ttl = Duration::from_secs(
+            std::env::var("CATALOG_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_TTL_SECS),
+        );
+
+        let max_entries = std::env::var("CATALOG_CACHE_MAX_ENTRIES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_ENTRIES);
+
+        let metadata_cache_dir = std::env::var("CATALOG_METADATA_CACHE_DIR")
+            .ok()
+            .map(PathBuf::from);
+
+        Self {
+            ttl,
+            max_entries,
+            metadata_cache_dir,
+        }
+    }
+}
+
+struct Cached<T> {
+    value: T,
+    inserted_at: Instant,
+}
+
+/// In-memory cache of `CatalogDatasetEntry` rows and the full dataset
+/// listing, so repeated `list_datasets`/`get_dataset` calls don't always hit
+/// Postgres. Writes made through this process (`insert`/`invalidate`) take
+/// effect immediately; entries otherwise expire after `ttl` so changes made
+/// by another process are only tolerated as stale for that long.
+pub struct DatasetEntryCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: RwLock<HashMap<String, Cached<CatalogDatasetEntry>>>,
+    insertion_order: RwLock<VecDeque<String>>,
+    listing: RwLock<Option<Cached<Vec<CatalogDatasetEntry>>>>,
+}
+
+impl DatasetEntryCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            entries: RwLock::new(HashMap::new()),
+            insertion_order: RwLock::new(VecDeque::new()),
+            listing: RwLock::new(None),
+        }
+    }
+
+    fn is_fresh(&self, inserted_at: Instant) -> bool {
+        inserted_at.elapsed() < self.ttl
+    }
+
+    pub async fn get(&self, dataset_id: &str) -> Option<CatalogDatasetEntry> {
+        let entries = self.entries.read().await;
+        entries
+            .get(dataset_id)
+            .filter(|cached| self.is_fresh(cached.inserted_at))
+            .map(|cached| cached.value.clone())
+    }
+
+    pub async fn insert(&self, entry: CatalogDatasetEntry) {
+        let id = entry.id.clone();
+
+        {
+            let mut entries = self.entries.write().await;
+            if entries.insert(id.clone(), Cached {
+                value: entry,
+                inserted_at: Instant::now(),
+            }).is_none() {
+                let mut order = self.insertion_order.write().await;
+                order.push_back(id);
+
+                while order.len() > self.max_entries {
+                    if let Some(evicted) = order.pop_front() {
+                        entries.remove(&evicted);
+                    }
+                }
+            }
+        }
+
+        self.invalidate_listing().await;
+    }
+
+    pub async fn invalidate(&self, dataset_id: &str) {
+        self.entries.write().await.remove(dataset_id);
+        self.invalidate_listing().await;
+    }
+
+    pub async fn get_listing(&self) -> Option<Vec<CatalogDatasetEntry>> {
+        let listing = self.listing.read().await;
+        listing
+            .as_ref()
+            .filter(|cached| self.is_fresh(cached.inserted_at))
+            .map(|cached| cached.value.clone())
+    }
+
+    pub async fn set_listing(&self, entries: Vec<CatalogDatasetEntry>) {
+        *self.listing.write().await = Some(Cached {
+            value: entries,
+            inserted_at: Instant::now(),
+        });
+    }
+
+    async fn invalidate_listing(&self) {
+        *self.listing.write().await = None;
+    }
+}
+
+/// Caches the (comparatively large) `DatasetMetadataFile` - files, columns,
+/// statistics - on local disk so repeated `load_metadata` calls for the same
+/// dataset don't keep re-querying the catalog database. Disabled entirely
+/// when `dir` is `None`.
+pub struct MetadataDiskCache {
+    dir: Option<PathBuf>,
+    ttl: Duration,
+}
+
+impl MetadataDiskCache {
+    pub fn new(dir: Option<PathBuf>, ttl: Duration) -> Self {
+        Self { dir, ttl }
+    }
+
+    fn path_for(&self, dir: &std::path::Path, dataset_id: &str) -> PathBuf {
+        dir.join(format!("{}.json", dataset_id))
+    }
+
+    pub async fn get(&self, dataset_id: &str) -> Option<DatasetMetadataFile> {
+        let dir = self.dir.as_ref()?;
+        let path = self.path_for(dir, dataset_id);
+
+        let modified_at = tokio::fs::metadata(&path)
+            .await
+            .ok()?
+            .modified()
+            .ok()?;
+        if modified_at.elapsed().map(|age| age > self.ttl).unwrap_or(true) {
+            return None;
+        }
+
+        let bytes = tokio::fs::read(&path).await.ok()?;
+        match serde_json::from_slice(&bytes) {
+            Ok(metadata) => Some(metadata),
+            Err(e) => {
+                warn!(
+                    "Discarding unreadable metadata cache entry for {}: {}",
+                    dataset_id, e
+                );
+                None
+            }
+        }
+    }
+
+    pub async fn put(&self, metadata: &DatasetMetadataFile) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+
+        if let Err(e) = tokio::fs::create_dir_all(dir).await {
+            warn!("Failed to create metadata cache directory {:?}: {}", dir, e);
+            return;
+        }
+
+        let path = self.path_for(dir, &metadata.id);
+        match serde_json::to_vec(metadata) {
+            Ok(bytes) => {
+                if let Err(e) = tokio::fs::write(&path, bytes).await {
+                    warn!("Failed to write metadata cache entry {:?}: {}", path, e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize metadata for {}: {}", metadata.id, e),
+        }
+    }
+
+    pub async fn invalidate(&self, dataset_id: &str) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+
+        let path = self.path_for(dir, dataset_id);
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to remove metadata cache entry {:?}: {}", path, e);
+            }
+        }
+    }
+}
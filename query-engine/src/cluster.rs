@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tonic::transport::Channel;
+use tracing::warn;
+
+use crate::domain::{QueryDataChunk, QueryMetadata, QueryStreamResult};
+use crate::error::AnalysisError;
+use crate::proto::analysis::{
+    analysis_service_client::AnalysisServiceClient, ExecuteQueryRequest, GetMetadataRequest,
+    HealthCheckRequest,
+};
+
+/// Cluster-wide namespace/tenant key and peer gRPC addresses for this node,
+/// read from `CLUSTER_NAMESPACE`/`CLUSTER_PEERS` in `main.rs`. The namespace
+/// keeps multiple logical clusters isolated within the same deployment,
+/// alongside the existing `agentic_analytics`/`public` catalog namespace used
+/// within a single node's `DataFusionEngine`.
+#[derive(Debug, Clone)]
+pub struct ClusterConfig {
+    pub namespace: String,
+    pub peers: Vec<String>,
+}
+
+impl ClusterConfig {
+    pub fn from_env() -> Self {
+        let namespace =
+            std::env::var("CLUSTER_NAMESPACE").unwrap_or_else(|_| "default".to_string());
+
+        let peers = std::env::var("CLUSTER_PEERS")
+            .ok()
+            .map(|raw| {
+                raw.split(',')
+                    .map(|addr| addr.trim().to_string())
+                    .filter(|addr| !addr.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self { namespace, peers }
+    }
+
+    pub fn is_standalone(&self) -> bool {
+        self.peers.is_empty()
+    }
+}
+
+/// Forwards sub-queries to peer nodes and reassembles their streamed
+/// Arrow-IPC chunks into a local `QueryStreamResult`, propagates newly added
+/// datasets to peers, and reports peer liveness for
+/// `AnalysisEngine::health_check`. Peer connections are cached the same way
+/// `DataFusionEngine` caches registered object stores: lazily established,
+/// guarded by an `RwLock`.
+pub struct ClusterCoordinator {
+    config: ClusterConfig,
+    peer_clients: Arc<RwLock<HashMap<String, AnalysisServiceClient<Channel>>>>,
+}
+
+impl ClusterCoordinator {
+    pub fn new(config: ClusterConfig) -> Self {
+        Self {
+            config,
+            peer_clients: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.config.namespace
+    }
+
+    pub fn peers(&self) -> &[String] {
+        &self.config.peers
+    }
+
+    async fn client_for(
+        &self,
+        peer: &str,
+    ) -> Result<AnalysisServiceClient<Channel>, AnalysisError> {
+        if let Some(client) = self.peer_clients.read().await.get(peer) {
+            return Ok(client.clone());
+        }
+
+        let client =
+            AnalysisServiceClient::connect(peer.to_string())
+                .await
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!("Failed to connect to cluster peer {}: {}", peer, e),
+                })?;
+
+        self.peer_clients
+            .write()
+            .await
+            .insert(peer.to_string(), client.clone());
+
+        Ok(client)
+    }
+
+    /// Forwards a query to a specific peer and reassembles its streamed
+    /// `ExecuteQuery` response into a `QueryStreamResult`.
+    pub async fn execute_remote_query(
+        &self,
+        peer: &str,
+        dataset_id: &str,
+        sql_query: &str,
+        limit: Option<i32>,
+    ) -> Result<QueryStreamResult, AnalysisError> {
+        let mut client = self.client_for(peer).await?;
+
+        let request = tonic::Request::new(ExecuteQueryRequest {
+            dataset_id: dataset_id.to_string(),
+            sql_query: sql_query.to_string(),
+            limit: limit.unwrap_or(0),
+        });
+
+        let mut stream = client
+            .execute_query(request)
+            .await
+            .map_err(|e| AnalysisError::ConfigError {
+                message: format!("gRPC call to peer {} failed: {}", peer, e),
+            })?
+            .into_inner();
+
+        let mut metadata: Option<QueryMetadata> = None;
+        let mut chunks = Vec::new();
+
+        while let Some(response) =
+            stream
+                .message()
+                .await
+                .map_err(|e| AnalysisError::QueryExecutionFailed {
+                    message: format!("Stream error from peer {}: {}", peer, e),
+                })?
+        {
+            match response.response_type {
+                Some(crate::proto::analysis::execute_query_response::ResponseType::Metadata(
+                    metadata_proto,
+                )) => {
+                    metadata = Some(QueryMetadata {
+                        arrow_schema: metadata_proto.arrow_schema,
+                        column_names: metadata_proto.column_names,
+                        estimated_rows: metadata_proto.estimated_rows,
+                    });
+                }
+                Some(crate::proto::analysis::execute_query_response::ResponseType::DataChunk(
+                    chunk,
+                )) => {
+                    chunks.push(QueryDataChunk {
+                        arrow_ipc_data: chunk.arrow_ipc_data,
+                        chunk_rows: chunk.chunk_rows,
+                        chunk_index: chunk.chunk_index,
+                    });
+                }
+                Some(
+                    crate::proto::analysis::execute_query_response::ResponseType::Complete(
+                        complete,
+                    ),
+                ) => {
+                    if !complete.success {
+                        return Err(AnalysisError::QueryExecutionFailed {
+                            message: complete.error_message,
+                        });
+                    }
+                }
+                None => {}
+            }
+        }
+
+        Ok(QueryStreamResult { metadata, chunks })
+    }
+
+    /// Best-effort propagation of a newly added dataset to every peer, so any
+    /// node in the namespace can resolve it via `get_table` without waiting
+    /// for its own lazy registration path to be triggered by a client
+    /// request. Peers that can't be reached are logged and skipped rather
+    /// than failing the caller's `add_dataset` request.
+    pub async fn notify_dataset_added(&self, dataset_id: &str) {
+        for peer in &self.config.peers {
+            let mut client = match self.client_for(peer).await {
+                Ok(client) => client,
+                Err(e) => {
+                    warn!(
+                        "Could not reach cluster peer {} to propagate dataset '{}': {}",
+                        peer, dataset_id, e
+                    );
+                    continue;
+                }
+            };
+
+            let request = tonic::Request::new(GetMetadataRequest {
+                dataset_id: dataset_id.to_string(),
+            });
+
+            if let Err(e) = client.get_metadata(request).await {
+                warn!(
+                    "Failed to propagate dataset '{}' registration to peer {}: {}",
+                    dataset_id, peer, e
+                );
+            }
+        }
+    }
+
+    /// Checks liveness of every configured peer via its `health_check` RPC.
+    /// Returns the addresses of peers that failed to respond healthy.
+    pub async fn unhealthy_peers(&self) -> Vec<String> {
+        let mut unhealthy = Vec::new();
+
+        for peer in &self.config.peers {
+            let healthy = match self.client_for(peer).await {
+                Ok(mut client) => client
+                    .health_check(tonic::Request::new(HealthCheckRequest {}))
+                    .await
+                    .is_ok(),
+                Err(_) => false,
+            };
+
+            if !healthy {
+                warn!("Cluster peer {} is unhealthy", peer);
+                unhealthy.push(peer.clone());
+            }
+        }
+
+        unhealthy
+    }
+}
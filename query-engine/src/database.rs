@@ -1,302 +1,1744 @@
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool, Integer, Jsonb, Nullable, Text, Uuid as UuidType};
 use diesel_async::{
-    pooled_connection::{deadpool::Pool, AsyncDieselConnectionManager},
+    pooled_connection::{deadpool::Pool, AsyncDieselConnectionManager, ManagerConfig},
+    sync_connection_wrapper::SyncConnectionWrapper,
     AsyncConnection, AsyncPgConnection, RunQueryDsl,
 };
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use futures::{future::BoxFuture, Stream};
 use std::collections::HashMap;
-use tracing::info;
+use tokio_postgres::{AsyncMessage, NoTls};
+use tracing::{info, warn};
+use uuid::Uuid;
 
 use crate::catalog::{
-    CatalogDatasetEntry, ColumnMetadata, DataFormat, DatasetFile, DatasetMetadataFile,
+    CatalogDatasetEntry, ColumnMetadata, CompressionType, DataFormat, DatasetFile,
+    DatasetMetadataFile,
 };
 use crate::error::AnalysisError;
+use crate::job_queue::{Job, JobRow};
 use crate::models::*;
+use crate::notifications::{CatalogNotifier, CATALOG_CHANGED_CHANNEL, JOB_QUEUE_CHANNEL};
 use crate::schema::*;
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+pub const SQLITE_MIGRATIONS: EmbeddedMigrations = embed_migrations!("sqlite_migrations");
+
+/// Which `DbPool` variant `database_url`'s scheme selects. Mirrors vaultwarden's
+/// `generate_connections!` dispatch: the scheme, not a separate config flag,
+/// is the single source of truth for which backend is active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DbBackend {
+    Postgres,
+    Sqlite,
+}
+
+impl DbBackend {
+    fn from_url(database_url: &str) -> Result<Self, AnalysisError> {
+        if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+            Ok(DbBackend::Postgres)
+        } else if database_url.starts_with("sqlite://") {
+            Ok(DbBackend::Sqlite)
+        } else {
+            Err(AnalysisError::ConfigError {
+                message: format!(
+                    "Unsupported database URL scheme (expected postgres:// or sqlite://): {}",
+                    database_url
+                ),
+            })
+        }
+    }
+}
+
+/// One pool variant per backend feature; the variant of a disabled backend
+/// is compiled out. The `AsyncPgConnection` and `tokio_postgres` imports
+/// above and `crate::notifications` are not feature-gated, so every build
+/// still depends on the Postgres crates.
+#[derive(Clone)]
+enum DbPool {
+    #[cfg(feature = "backend-postgres")]
+    Postgres(Pool<AsyncPgConnection>),
+    #[cfg(feature = "backend-sqlite")]
+    Sqlite(Pool<SyncConnectionWrapper<diesel::sqlite::SqliteConnection>>),
+}
 
 #[derive(Clone)]
 pub struct DatabaseManager {
-    pool: Pool<AsyncPgConnection>,
+    pool: DbPool,
+    /// `LISTEN`/`NOTIFY`-backed pub/sub, used for `watch_catalog` and the job
+    /// queue's wakeup signal. Postgres-only: SQLite has no equivalent, so
+    /// this is `None` on that backend and the features built on top of it
+    /// degrade rather than erroring the whole `DatabaseManager`.
+    notifier: Option<CatalogNotifier>,
+}
+
+/// Opens a connection the same way `AsyncDieselConnectionManager`'s default
+/// `custom_setup` does, except it also `LISTEN`s on
+/// [`CATALOG_CHANGED_CHANNEL`] and forwards every `AsyncMessage::Notification`
+/// the server sends back into `notification_tx`, the way pict-rs drives its
+/// pub/sub off a raw `tokio_postgres` connection. Every connection the pool
+/// opens ends up listening, so a notification is forwarded regardless of
+/// which pooled connection happens to be idle when Postgres sends it.
+#[cfg(feature = "backend-postgres")]
+fn establish_listening_connection(
+    database_url: &str,
+    notification_tx: flume::Sender<tokio_postgres::Notification>,
+) -> BoxFuture<diesel::ConnectionResult<AsyncPgConnection>> {
+    let database_url = database_url.to_string();
+    Box::pin(async move {
+        let (client, mut connection) = tokio_postgres::connect(&database_url, NoTls)
+            .await
+            .map_err(|e| diesel::ConnectionError::BadConnection(e.to_string()))?;
+
+        tokio::spawn(async move {
+            loop {
+                match std::future::poll_fn(|cx| connection.poll_message(cx)).await {
+                    Some(Ok(AsyncMessage::Notification(notification))) => {
+                        let _ = notification_tx.send(notification);
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        warn!("Postgres notification connection error: {}", e);
+                        break;
+                    }
+                    None => break,
+                }
+            }
+        });
+
+        client
+            .batch_execute(&format!(
+                "LISTEN {}; LISTEN {}",
+                CATALOG_CHANGED_CHANNEL, JOB_QUEUE_CHANNEL
+            ))
+            .await
+            .map_err(|e| diesel::ConnectionError::BadConnection(e.to_string()))?;
+
+        AsyncPgConnection::try_from(client).await
+    })
+}
+
+/// Issues `NOTIFY <channel>` with `payload`, via `pg_notify` so the payload
+/// can be bound as a parameter instead of interpolated into the SQL text.
+/// Called inside the same transaction as the write it announces, so
+/// watchers never observe a notification for a change that then rolls back.
+/// Postgres-only; the SQLite code paths that would otherwise call this skip
+/// it instead, since SQLite has no pub/sub primitive to notify through.
+#[cfg(feature = "backend-postgres")]
+async fn notify(
+    conn: &mut AsyncPgConnection,
+    channel: &str,
+    payload: &str,
+) -> Result<(), AnalysisError> {
+    diesel::sql_query("SELECT pg_notify($1, $2)")
+        .bind::<Text, _>(channel)
+        .bind::<Text, _>(payload)
+        .execute(conn)
+        .await
+        .map_err(|e| AnalysisError::ConfigError {
+            message: format!("Failed to send {} notification: {}", channel, e),
+        })?;
+
+    Ok(())
+}
+
+/// Row shape for the hand-written SQLite catalog queries below. SQLite has no
+/// native UUID/JSONB/array/timestamptz types, so every column comes back as
+/// `Text` (or a plain integer) and is parsed into its domain type by
+/// `TryFrom`, the same division of labor `JobRow` already uses for job_queue.
+#[cfg(feature = "backend-sqlite")]
+#[derive(Debug, diesel::QueryableByName)]
+struct SqliteDatasetRow {
+    #[diesel(sql_type = Text)]
+    id: String,
+    #[diesel(sql_type = Text)]
+    uuid: String,
+    #[diesel(sql_type = Text)]
+    name: String,
+    #[diesel(sql_type = Text)]
+    description: String,
+    #[diesel(sql_type = Text)]
+    format: String,
+    #[diesel(sql_type = BigInt)]
+    size_bytes: i64,
+    #[diesel(sql_type = Integer)]
+    row_count: i32,
+    #[diesel(sql_type = Text)]
+    tags: String,
+    #[diesel(sql_type = Text)]
+    created_at: String,
+    #[diesel(sql_type = Text)]
+    updated_at: String,
+    #[diesel(sql_type = Text)]
+    dataset_path: String,
+    #[diesel(sql_type = Text)]
+    metadata_path: String,
+    #[diesel(sql_type = Nullable<BigInt>)]
+    max_size_bytes: Option<i64>,
+    #[diesel(sql_type = Nullable<Integer>)]
+    max_row_count: Option<i32>,
+    #[diesel(sql_type = Nullable<Text>)]
+    source_path: Option<String>,
+}
+
+#[cfg(feature = "backend-sqlite")]
+impl TryFrom<SqliteDatasetRow> for CatalogDatasetEntry {
+    type Error = AnalysisError;
+
+    fn try_from(row: SqliteDatasetRow) -> Result<Self, Self::Error> {
+        Ok(CatalogDatasetEntry {
+            id: row.id,
+            uuid: Uuid::parse_str(&row.uuid).map_err(|e| AnalysisError::ConfigError {
+                message: format!("Invalid dataset uuid: {}", e),
+            })?,
+            name: row.name,
+            description: row.description,
+            format: DataFormat::try_from(row.format.as_str())?,
+            size_bytes: row.size_bytes,
+            row_count: row.row_count,
+            tags: serde_json::from_str(&row.tags).map_err(|e| AnalysisError::ConfigError {
+                message: format!("Invalid dataset tags: {}", e),
+            })?,
+            created_at: parse_sqlite_timestamp(&row.created_at)?,
+            updated_at: parse_sqlite_timestamp(&row.updated_at)?,
+            dataset_path: row.dataset_path,
+            metadata_path: row.metadata_path,
+            max_size_bytes: row.max_size_bytes,
+            max_row_count: row.max_row_count,
+            source_path: row.source_path,
+        })
+    }
+}
+
+#[cfg(feature = "backend-sqlite")]
+#[derive(Debug, diesel::QueryableByName)]
+struct SqliteDatasetFileRow {
+    #[diesel(sql_type = Text)]
+    filename: String,
+    #[diesel(sql_type = Text)]
+    storage_path: String,
+    #[diesel(sql_type = BigInt)]
+    size_bytes: i64,
+    #[diesel(sql_type = Integer)]
+    row_count: i32,
+    #[diesel(sql_type = Text)]
+    created_at: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    content_hash: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    upstream_etag: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    upstream_last_modified: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    compression: Option<String>,
+}
+
+#[cfg(feature = "backend-sqlite")]
+impl TryFrom<SqliteDatasetFileRow> for DatasetFile {
+    type Error = AnalysisError;
+
+    fn try_from(row: SqliteDatasetFileRow) -> Result<Self, Self::Error> {
+        let upstream_last_modified = row
+            .upstream_last_modified
+            .as_deref()
+            .map(parse_sqlite_timestamp)
+            .transpose()?;
+
+        Ok(DatasetFile {
+            filename: row.filename,
+            storage_path: row.storage_path,
+            size_bytes: row.size_bytes,
+            row_count: row.row_count,
+            created_at: parse_sqlite_timestamp(&row.created_at)?,
+            compression: row
+                .compression
+                .as_deref()
+                .and_then(|c| CompressionType::try_from(c).ok()),
+            content_hash: row.content_hash,
+            upstream_etag: row.upstream_etag,
+            upstream_last_modified,
+        })
+    }
+}
+
+#[cfg(feature = "backend-sqlite")]
+#[derive(Debug, diesel::QueryableByName)]
+struct SqliteDatasetColumnRow {
+    #[diesel(sql_type = Text)]
+    name: String,
+    #[diesel(sql_type = Text)]
+    data_type: String,
+    #[diesel(sql_type = Bool)]
+    nullable: bool,
+    #[diesel(sql_type = Text)]
+    description: String,
+    #[diesel(sql_type = Text)]
+    statistics: String,
+}
+
+#[cfg(feature = "backend-sqlite")]
+impl TryFrom<SqliteDatasetColumnRow> for ColumnMetadata {
+    type Error = AnalysisError;
+
+    fn try_from(row: SqliteDatasetColumnRow) -> Result<Self, Self::Error> {
+        let statistics = serde_json::from_str(&row.statistics).unwrap_or_default();
+
+        Ok(ColumnMetadata {
+            name: row.name,
+            data_type: row.data_type,
+            nullable: row.nullable,
+            description: row.description,
+            statistics,
+        })
+    }
+}
+
+#[cfg(feature = "backend-sqlite")]
+#[derive(Debug, diesel::QueryableByName)]
+struct SqliteDatasetStatisticRow {
+    #[diesel(sql_type = Text)]
+    stat_key: String,
+    #[diesel(sql_type = Text)]
+    stat_value: String,
+}
+
+#[cfg(feature = "backend-sqlite")]
+fn parse_sqlite_timestamp(value: &str) -> Result<DateTime<Utc>, AnalysisError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| AnalysisError::ConfigError {
+            message: format!("Invalid timestamp '{}': {}", value, e),
+        })
+}
+
+/// Pool sizing/timeouts for `DatabaseManager::new`, read from env in
+/// `main.rs` the same way `ClusterConfig`/`TlsConfig` are. Applied to
+/// whichever `DbPool` variant the URL scheme selects.
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    pub max_size: usize,
+    pub wait_timeout: std::time::Duration,
+    pub create_timeout: std::time::Duration,
+    pub recycle_timeout: std::time::Duration,
+}
+
+impl DatabaseConfig {
+    pub fn from_env() -> Self {
+        let max_size = std::env::var("DB_POOL_MAX_SIZE")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .expect("Invalid DB_POOL_MAX_SIZE");
+
+        let wait_timeout = std::time::Duration::from_millis(
+            std::env::var("DB_POOL_WAIT_TIMEOUT_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .expect("Invalid DB_POOL_WAIT_TIMEOUT_MS"),
+        );
+
+        let create_timeout = std::time::Duration::from_millis(
+            std::env::var("DB_POOL_CREATE_TIMEOUT_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .expect("Invalid DB_POOL_CREATE_TIMEOUT_MS"),
+        );
+
+        let recycle_timeout = std::time::Duration::from_millis(
+            std::env::var("DB_POOL_RECYCLE_TIMEOUT_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .expect("Invalid DB_POOL_RECYCLE_TIMEOUT_MS"),
+        );
+
+        Self {
+            max_size,
+            wait_timeout,
+            create_timeout,
+            recycle_timeout,
+        }
+    }
+
+    fn timeouts(&self) -> deadpool::managed::Timeouts {
+        deadpool::managed::Timeouts {
+            wait: Some(self.wait_timeout),
+            create: Some(self.create_timeout),
+            recycle: Some(self.recycle_timeout),
+        }
+    }
+}
+
+/// Runs `SELECT 1` over a freshly created or about-to-be-recycled
+/// connection, mirroring the `post_create`/`pre_recycle` `Hook` pict-rs
+/// registers on its own deadpool. Returning an error here drops the
+/// connection instead of handing a stale/broken one to a caller.
+fn health_check_hook<C>() -> deadpool::managed::Hook<AsyncDieselConnectionManager<C>>
+where
+    C: AsyncConnection + 'static,
+{
+    deadpool::managed::Hook::async_fn(|conn, _| {
+        Box::pin(async move {
+            diesel::sql_query("SELECT 1")
+                .execute(conn)
+                .await
+                .map(|_| ())
+                .map_err(|e| {
+                    deadpool::managed::HookError::Message(
+                        format!("pool connection health check failed: {}", e).into(),
+                    )
+                })
+        })
+    })
 }
 
 impl DatabaseManager {
-    pub async fn new(database_url: &str) -> Result<Self, AnalysisError> {
-        let config = AsyncDieselConnectionManager::<AsyncPgConnection>::new(database_url);
-        let pool = Pool::builder(config)
-            .build()
-            .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to create database pool: {}", e),
-            })?;
+    pub async fn new(
+        database_url: &str,
+        db_config: &DatabaseConfig,
+    ) -> Result<Self, AnalysisError> {
+        let manager = match DbBackend::from_url(database_url)? {
+            #[cfg(feature = "backend-postgres")]
+            DbBackend::Postgres => {
+                let (notification_tx, notification_rx) = flume::unbounded();
+
+                let mut manager_config = ManagerConfig::default();
+                manager_config.custom_setup = Box::new(move |config| {
+                    establish_listening_connection(config, notification_tx.clone())
+                });
 
-        let manager = Self { pool };
-        manager.run_migrations(database_url).await?;
+                let config = AsyncDieselConnectionManager::<AsyncPgConnection>::new_with_config(
+                    database_url,
+                    manager_config,
+                );
+                let pool = Pool::builder(config)
+                    .max_size(db_config.max_size)
+                    .timeouts(db_config.timeouts())
+                    .post_create(health_check_hook())
+                    .pre_recycle(health_check_hook())
+                    .build()
+                    .map_err(|e| AnalysisError::PoolError {
+                        message: format!("Failed to create database pool: {}", e),
+                    })?;
+
+                let notifier = CatalogNotifier::new();
+                notifier.clone().spawn_delegate(notification_rx);
+
+                Self {
+                    pool: DbPool::Postgres(pool),
+                    notifier: Some(notifier),
+                }
+            }
+            #[cfg(feature = "backend-sqlite")]
+            DbBackend::Sqlite => {
+                info!("Using SQLite catalog backend (no LISTEN/NOTIFY support)");
+
+                let config = AsyncDieselConnectionManager::<
+                    SyncConnectionWrapper<diesel::sqlite::SqliteConnection>,
+                >::new(database_url);
+                let pool = Pool::builder(config)
+                    .max_size(db_config.max_size)
+                    .timeouts(db_config.timeouts())
+                    .post_create(health_check_hook())
+                    .pre_recycle(health_check_hook())
+                    .build()
+                    .map_err(|e| AnalysisError::PoolError {
+                        message: format!("Failed to create database pool: {}", e),
+                    })?;
+
+                Self {
+                    pool: DbPool::Sqlite(pool),
+                    notifier: None,
+                }
+            }
+            #[allow(unreachable_patterns)]
+            backend => {
+                return Err(AnalysisError::ConfigError {
+                    message: format!(
+                        "Database backend {:?} selected by the URL isn't enabled in this build",
+                        backend
+                    ),
+                })
+            }
+        };
+
+        manager.run_migrations().await?;
 
         Ok(manager)
     }
 
-    pub async fn run_migrations(&self, database_url: &str) -> Result<(), AnalysisError> {
-        use diesel::Connection;
-        use diesel::PgConnection;
+    /// Logs current pool utilization (size/available/waiting) after a
+    /// checkout, so saturation shows up in logs before it escalates into
+    /// `PoolError`s from timed-out `wait`s.
+    #[cfg(feature = "backend-postgres")]
+    fn log_pool_status(pool: &Pool<AsyncPgConnection>) {
+        tracing::debug!(status = ?pool.status(), "checked out database connection");
+    }
+
+    #[cfg(feature = "backend-sqlite")]
+    fn log_pool_status(pool: &Pool<SyncConnectionWrapper<diesel::sqlite::SqliteConnection>>) {
+        tracing::debug!(status = ?pool.status(), "checked out database connection");
+    }
 
-        // For migrations, we need to use a synchronous connection
-        // This is a limitation of diesel_migrations which doesn't support async yet
+    /// Yields dataset ids as they're added or updated, driven off Postgres
+    /// `NOTIFY catalog_changed`. On the SQLite backend there's nothing to
+    /// drive this off, so it yields a stream that never produces an item
+    /// rather than erroring; callers that need live updates should stick to
+    /// the Postgres backend.
+    pub fn watch_catalog(&self) -> impl Stream<Item = String> {
+        match &self.notifier {
+            Some(notifier) => futures::future::Either::Left(notifier.watch_catalog()),
+            None => {
+                warn!("watch_catalog has no effect on the sqlite backend");
+                futures::future::Either::Right(futures::stream::pending())
+            }
+        }
+    }
 
-        let mut connection =
-            PgConnection::establish(&database_url).map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to establish connection for migrations: {}", e),
-            })?;
+    /// Blocks until `enqueue_job` notifies `job_queue_changed`, for a worker
+    /// to await between `claim_job` attempts instead of busy-polling. Since
+    /// a notification sent while nobody's waiting is lost, callers should
+    /// still bound the wait with a timeout and retry `claim_job` as a
+    /// backstop. On the SQLite backend, which has no job queue support,
+    /// returns immediately.
+    pub async fn wait_for_job_queue(&self) {
+        match &self.notifier {
+            Some(notifier) => notifier.wait(JOB_QUEUE_CHANNEL).await,
+            None => {}
+        }
+    }
 
-        connection
-            .run_pending_migrations(MIGRATIONS)
-            .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to run migrations: {}", e),
-            })?;
+    /// Runs pending migrations over a connection checked out of `pool`,
+    /// rather than opening a second synchronous connection outside it.
+    /// `diesel_migrations::MigrationHarness` is synchronous, so the checked
+    /// out connection is wrapped in `AsyncConnectionWrapper` and driven from
+    /// a `spawn_blocking` worker; a panic on that worker is re-raised here
+    /// rather than silently swallowed.
+    pub async fn run_migrations(&self) -> Result<(), AnalysisError> {
+        use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
 
-        Ok(())
+        let result = match &self.pool {
+            #[cfg(feature = "backend-postgres")]
+            DbPool::Postgres(pool) => {
+                let conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!(
+                                "Failed to get database connection for migrations: {}",
+                                e
+                            ),
+                        })?;
+                let conn = deadpool::managed::Object::take(conn);
+
+                tokio::task::spawn_blocking(move || {
+                    let mut wrapper: AsyncConnectionWrapper<AsyncPgConnection> =
+                        AsyncConnectionWrapper::from(conn);
+                    wrapper
+                        .run_pending_migrations(MIGRATIONS)
+                        .map(|_| ())
+                        .map_err(|e| AnalysisError::ConfigError {
+                            message: format!("Failed to run migrations: {}", e),
+                        })
+                })
+                .await
+            }
+            #[cfg(feature = "backend-sqlite")]
+            DbPool::Sqlite(pool) => {
+                let conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!(
+                                "Failed to get database connection for migrations: {}",
+                                e
+                            ),
+                        })?;
+                let conn = deadpool::managed::Object::take(conn);
+
+                tokio::task::spawn_blocking(move || {
+                    let mut wrapper: AsyncConnectionWrapper<
+                        SyncConnectionWrapper<diesel::sqlite::SqliteConnection>,
+                    > = AsyncConnectionWrapper::from(conn);
+                    wrapper
+                        .run_pending_migrations(SQLITE_MIGRATIONS)
+                        .map(|_| ())
+                        .map_err(|e| AnalysisError::ConfigError {
+                            message: format!("Failed to run migrations: {}", e),
+                        })
+                })
+                .await
+            }
+        };
+
+        match result {
+            Ok(migration_result) => migration_result,
+            Err(join_err) => match join_err.try_into_panic() {
+                Ok(panic) => std::panic::resume_unwind(panic),
+                Err(join_err) => Err(AnalysisError::ConfigError {
+                    message: format!("Migration task failed: {}", join_err),
+                }),
+            },
+        }
     }
 
     pub async fn add_dataset(&self, entry: &CatalogDatasetEntry) -> Result<(), AnalysisError> {
-        use crate::schema::datasets::dsl::*;
         info!("Adding dataset {} to catalog", entry.name);
-        let mut conn = self
-            .pool
-            .get()
-            .await
-            .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to get database connection: {}", e),
-            })?;
 
-        let new_dataset = NewDataset {
-            id: &entry.id,
-            uuid: &entry.uuid,
-            name: &entry.name,
-            description: &entry.description,
-            format: entry.format.as_str(),
-            size_bytes: entry.size_bytes,
-            row_count: entry.row_count,
-            tags: &entry.tags,
-            created_at: entry.created_at,
-            updated_at: entry.updated_at,
-            dataset_path: &entry.dataset_path,
-            metadata_path: &entry.metadata_path,
-        };
+        match &self.pool {
+            #[cfg(feature = "backend-postgres")]
+            DbPool::Postgres(pool) => {
+                use crate::schema::datasets::dsl::*;
 
-        diesel_async::RunQueryDsl::execute(
-            diesel::insert_into(datasets).values(&new_dataset),
-            &mut conn,
-        )
-        .await
-        .map_err(|e| AnalysisError::ConfigError {
-            message: format!("Failed to insert dataset: {}", e),
-        })?;
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
 
-        Ok(())
+                let new_dataset = NewDataset {
+                    id: &entry.id,
+                    uuid: &entry.uuid,
+                    name: &entry.name,
+                    description: &entry.description,
+                    format: entry.format.clone(),
+                    size_bytes: entry.size_bytes,
+                    row_count: entry.row_count,
+                    tags: &entry.tags,
+                    created_at: entry.created_at,
+                    updated_at: entry.updated_at,
+                    dataset_path: &entry.dataset_path,
+                    metadata_path: &entry.metadata_path,
+                    max_size_bytes: entry.max_size_bytes,
+                    max_row_count: entry.max_row_count,
+                    source_path: entry.source_path.as_deref(),
+                };
+
+                let dataset_id = entry.id.clone();
+
+                conn.transaction::<_, AnalysisError, _>(|conn| {
+                    Box::pin(async move {
+                        diesel_async::RunQueryDsl::execute(
+                            diesel::insert_into(datasets).values(&new_dataset),
+                            conn,
+                        )
+                        .await
+                        .map_err(|e| AnalysisError::ConfigError {
+                            message: format!("Failed to insert dataset: {}", e),
+                        })?;
+
+                        notify(conn, CATALOG_CHANGED_CHANNEL, &dataset_id).await
+                    })
+                })
+                .await
+            }
+            #[cfg(feature = "backend-sqlite")]
+            DbPool::Sqlite(pool) => {
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
+
+                let tags = serde_json::to_string(&entry.tags).map_err(|e| {
+                    AnalysisError::ConfigError {
+                        message: format!("Failed to serialize dataset tags: {}", e),
+                    }
+                })?;
+
+                diesel::sql_query(
+                    "INSERT INTO datasets (
+                        id, uuid, name, description, format, size_bytes, row_count, tags,
+                        created_at, updated_at, dataset_path, metadata_path,
+                        max_size_bytes, max_row_count, source_path
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind::<Text, _>(&entry.id)
+                .bind::<Text, _>(entry.uuid.to_string())
+                .bind::<Text, _>(&entry.name)
+                .bind::<Text, _>(&entry.description)
+                .bind::<Text, _>(entry.format.as_str())
+                .bind::<BigInt, _>(entry.size_bytes)
+                .bind::<Integer, _>(entry.row_count)
+                .bind::<Text, _>(tags)
+                .bind::<Text, _>(entry.created_at.to_rfc3339())
+                .bind::<Text, _>(entry.updated_at.to_rfc3339())
+                .bind::<Text, _>(&entry.dataset_path)
+                .bind::<Text, _>(&entry.metadata_path)
+                .bind::<Nullable<BigInt>, _>(entry.max_size_bytes)
+                .bind::<Nullable<Integer>, _>(entry.max_row_count)
+                .bind::<Nullable<Text>, _>(&entry.source_path)
+                .execute(&mut conn)
+                .await
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!("Failed to insert dataset: {}", e),
+                })?;
+
+                Ok(())
+            }
+        }
     }
 
     pub async fn get_dataset(
         &self,
         dataset_id: &str,
     ) -> Result<Option<CatalogDatasetEntry>, AnalysisError> {
-        use crate::schema::datasets::dsl::*;
+        match &self.pool {
+            #[cfg(feature = "backend-postgres")]
+            DbPool::Postgres(pool) => {
+                use crate::schema::datasets::dsl::*;
 
-        let mut conn = self
-            .pool
-            .get()
-            .await
-            .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to get database connection: {}", e),
-            })?;
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
 
-        let dataset = datasets
-            .filter(id.eq(dataset_id))
-            .get_result::<Dataset>(&mut conn)
-            .await
-            .optional()
-            .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to fetch dataset: {}", e),
-            })?;
+                let dataset = datasets
+                    .filter(id.eq(dataset_id))
+                    .get_result::<Dataset>(&mut conn)
+                    .await
+                    .optional()
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to fetch dataset: {}", e),
+                    })?;
+
+                Ok(dataset.map(|d| d.into()))
+            }
+            #[cfg(feature = "backend-sqlite")]
+            DbPool::Sqlite(pool) => {
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
+
+                let row = diesel::sql_query("SELECT * FROM datasets WHERE id = ?")
+                    .bind::<Text, _>(dataset_id)
+                    .get_result::<SqliteDatasetRow>(&mut conn)
+                    .await
+                    .optional()
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to fetch dataset: {}", e),
+                    })?;
 
-        Ok(dataset.map(|d| d.into()))
+                row.map(CatalogDatasetEntry::try_from).transpose()
+            }
+        }
     }
 
     pub async fn list_datasets(&self) -> Result<Vec<CatalogDatasetEntry>, AnalysisError> {
-        use crate::schema::datasets::dsl::*;
+        match &self.pool {
+            #[cfg(feature = "backend-postgres")]
+            DbPool::Postgres(pool) => {
+                use crate::schema::datasets::dsl::*;
 
-        let mut conn = self
-            .pool
-            .get()
-            .await
-            .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to get database connection: {}", e),
-            })?;
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
 
-        let dataset_list = datasets
-            .order(created_at.desc())
-            .get_results::<Dataset>(&mut conn)
-            .await
-            .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to fetch datasets: {}", e),
-            })?;
+                let dataset_list = datasets
+                    .order(created_at.desc())
+                    .get_results::<Dataset>(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to fetch datasets: {}", e),
+                    })?;
+
+                Ok(dataset_list.into_iter().map(|d| d.into()).collect())
+            }
+            #[cfg(feature = "backend-sqlite")]
+            DbPool::Sqlite(pool) => {
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
+
+                let rows = diesel::sql_query("SELECT * FROM datasets ORDER BY created_at DESC")
+                    .get_results::<SqliteDatasetRow>(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to fetch datasets: {}", e),
+                    })?;
 
-        Ok(dataset_list.into_iter().map(|d| d.into()).collect())
+                rows.into_iter().map(CatalogDatasetEntry::try_from).collect()
+            }
+        }
     }
 
     pub async fn save_metadata(&self, metadata: &DatasetMetadataFile) -> Result<(), AnalysisError> {
         info!("Saving metadata for dataset {}", metadata.id);
-        let mut conn = self
-            .pool
-            .get()
-            .await
-            .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to get database connection: {}", e),
-            })?;
 
-        conn.transaction::<_, AnalysisError, _>(|conn| {
-            Box::pin(async move {
+        match &self.pool {
+            #[cfg(feature = "backend-postgres")]
+            DbPool::Postgres(pool) => {
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
+
+                conn.transaction::<_, AnalysisError, _>(|conn| {
+                    Box::pin(async move {
+                        for file in &metadata.files {
+                            let new_file = NewDatasetFile {
+                                dataset_id: &metadata.id,
+                                filename: &file.filename,
+                                storage_path: &file.storage_path,
+                                size_bytes: file.size_bytes,
+                                row_count: file.row_count,
+                                created_at: file.created_at,
+                                content_hash: file.content_hash.as_deref(),
+                                upstream_etag: file.upstream_etag.as_deref(),
+                                upstream_last_modified: file.upstream_last_modified,
+                                compression: file.compression.as_ref().map(CompressionType::as_str),
+                            };
+
+                            diesel::insert_into(dataset_files::table)
+                                .values(&new_file)
+                                .execute(conn)
+                                .await
+                                .map_err(|e| AnalysisError::ConfigError {
+                                    message: format!("Failed to insert dataset file: {}", e),
+                                })?;
+                        }
+
+                        for column in &metadata.columns {
+                            let statistics_json = serde_json::to_value(&column.statistics)
+                                .map_err(|e| AnalysisError::ConfigError {
+                                    message: format!(
+                                        "Failed to serialize column statistics: {}",
+                                        e
+                                    ),
+                                })?;
+
+                            let new_column = NewDatasetColumn {
+                                dataset_id: &metadata.id,
+                                name: &column.name,
+                                data_type: &column.data_type,
+                                nullable: column.nullable,
+                                description: &column.description,
+                                statistics: &statistics_json,
+                            };
+
+                            diesel::insert_into(dataset_columns::table)
+                                .values(&new_column)
+                                .execute(conn)
+                                .await
+                                .map_err(|e| AnalysisError::ConfigError {
+                                    message: format!("Failed to insert dataset column: {}", e),
+                                })?;
+                        }
+
+                        for (key, value) in &metadata.statistics {
+                            let new_stat = NewDatasetStatistic {
+                                dataset_id: &metadata.id,
+                                stat_key: key,
+                                stat_value: value,
+                            };
+
+                            diesel::insert_into(dataset_statistics::table)
+                                .values(&new_stat)
+                                .on_conflict((
+                                    dataset_statistics::dataset_id,
+                                    dataset_statistics::stat_key,
+                                ))
+                                .do_update()
+                                .set(dataset_statistics::stat_value.eq(value))
+                                .execute(conn)
+                                .await
+                                .map_err(|e| AnalysisError::ConfigError {
+                                    message: format!(
+                                        "Failed to insert dataset statistic: {}",
+                                        e
+                                    ),
+                                })?;
+                        }
+
+                        notify(conn, CATALOG_CHANGED_CHANNEL, &metadata.id).await
+                    })
+                })
+                .await
+            }
+            #[cfg(feature = "backend-sqlite")]
+            DbPool::Sqlite(pool) => {
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
+
                 for file in &metadata.files {
-                    let new_file = NewDatasetFile {
-                        dataset_id: &metadata.id,
-                        filename: &file.filename,
-                        storage_path: &file.storage_path,
-                        size_bytes: file.size_bytes,
-                        row_count: file.row_count,
-                        created_at: file.created_at,
-                    };
-
-                    diesel::insert_into(dataset_files::table)
-                        .values(&new_file)
+                    diesel::sql_query(
+                        "INSERT INTO dataset_files (
+                            dataset_id, filename, storage_path, size_bytes, row_count, created_at,
+                            content_hash, upstream_etag, upstream_last_modified, compression
+                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    )
+                    .bind::<Text, _>(&metadata.id)
+                    .bind::<Text, _>(&file.filename)
+                    .bind::<Text, _>(&file.storage_path)
+                    .bind::<BigInt, _>(file.size_bytes)
+                    .bind::<Integer, _>(file.row_count)
+                    .bind::<Text, _>(file.created_at.to_rfc3339())
+                    .bind::<Nullable<Text>, _>(&file.content_hash)
+                    .bind::<Nullable<Text>, _>(&file.upstream_etag)
+                    .bind::<Nullable<Text>, _>(file.upstream_last_modified.map(|dt| dt.to_rfc3339()))
+                    .bind::<Nullable<Text>, _>(file.compression.as_ref().map(CompressionType::as_str))
+                    .execute(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to insert dataset file: {}", e),
+                    })?;
+                }
+
+                for column in &metadata.columns {
+                    let statistics_json = serde_json::to_string(&column.statistics).map_err(
+                        |e| AnalysisError::ConfigError {
+                            message: format!("Failed to serialize column statistics: {}", e),
+                        },
+                    )?;
+
+                    diesel::sql_query(
+                        "INSERT INTO dataset_columns (
+                            dataset_id, name, data_type, nullable, description, statistics
+                        ) VALUES (?, ?, ?, ?, ?, ?)",
+                    )
+                    .bind::<Text, _>(&metadata.id)
+                    .bind::<Text, _>(&column.name)
+                    .bind::<Text, _>(&column.data_type)
+                    .bind::<Bool, _>(column.nullable)
+                    .bind::<Text, _>(&column.description)
+                    .bind::<Text, _>(statistics_json)
+                    .execute(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to insert dataset column: {}", e),
+                    })?;
+                }
+
+                for (key, value) in &metadata.statistics {
+                    diesel::sql_query(
+                        "INSERT INTO dataset_statistics (dataset_id, stat_key, stat_value)
+                         VALUES (?, ?, ?)
+                         ON CONFLICT (dataset_id, stat_key) DO UPDATE SET stat_value = excluded.stat_value",
+                    )
+                    .bind::<Text, _>(&metadata.id)
+                    .bind::<Text, _>(key)
+                    .bind::<Text, _>(value)
+                    .execute(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to insert dataset statistic: {}", e),
+                    })?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn load_metadata(
+        &self,
+        dataset_id: &str,
+    ) -> Result<DatasetMetadataFile, AnalysisError> {
+        match &self.pool {
+            #[cfg(feature = "backend-postgres")]
+            DbPool::Postgres(pool) => {
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
+
+                let dataset = datasets::table
+                    .filter(datasets::id.eq(dataset_id))
+                    .get_result::<Dataset>(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::DatasetNotFound {
+                        dataset_id: format!(
+                            "Failed to load dataset metadata for {}: {}",
+                            dataset_id, e
+                        ),
+                    })?;
+
+                let files = dataset_files::table
+                    .filter(dataset_files::dataset_id.eq(dataset_id))
+                    .get_results::<DatasetFileModel>(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to load dataset files: {}", e),
+                    })?;
+
+                let columns = dataset_columns::table
+                    .filter(dataset_columns::dataset_id.eq(dataset_id))
+                    .get_results::<DatasetColumnModel>(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to load dataset columns: {}", e),
+                    })?;
+
+                let stats = dataset_statistics::table
+                    .filter(dataset_statistics::dataset_id.eq(dataset_id))
+                    .get_results::<DatasetStatisticModel>(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to load dataset statistics: {}", e),
+                    })?;
+
+                let files_vec: Vec<DatasetFile> = files.into_iter().map(|f| f.into()).collect();
+                let columns_vec: Vec<ColumnMetadata> =
+                    columns.into_iter().map(|c| c.into()).collect();
+                let statistics: HashMap<String, String> = stats
+                    .into_iter()
+                    .map(|s| (s.stat_key, s.stat_value))
+                    .collect();
+
+                Ok(DatasetMetadataFile {
+                    id: dataset.id,
+                    uuid: dataset.uuid,
+                    name: dataset.name,
+                    description: dataset.description,
+                    format: dataset.format,
+                    size_bytes: dataset.size_bytes,
+                    row_count: dataset.row_count,
+                    tags: dataset.tags,
+                    created_at: dataset.created_at,
+                    updated_at: dataset.updated_at,
+                    dataset_path: dataset.dataset_path,
+                    files: files_vec,
+                    columns: columns_vec,
+                    statistics,
+                    max_size_bytes: dataset.max_size_bytes,
+                    max_row_count: dataset.max_row_count,
+                    source_path: dataset.source_path,
+                })
+            }
+            #[cfg(feature = "backend-sqlite")]
+            DbPool::Sqlite(pool) => {
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
+
+                let dataset = diesel::sql_query("SELECT * FROM datasets WHERE id = ?")
+                    .bind::<Text, _>(dataset_id)
+                    .get_result::<SqliteDatasetRow>(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::DatasetNotFound {
+                        dataset_id: format!(
+                            "Failed to load dataset metadata for {}: {}",
+                            dataset_id, e
+                        ),
+                    })?;
+
+                let files = diesel::sql_query(
+                    "SELECT filename, storage_path, size_bytes, row_count, created_at, content_hash,
+                            upstream_etag, upstream_last_modified, compression
+                     FROM dataset_files WHERE dataset_id = ?",
+                )
+                .bind::<Text, _>(dataset_id)
+                .get_results::<SqliteDatasetFileRow>(&mut conn)
+                .await
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!("Failed to load dataset files: {}", e),
+                })?;
+
+                let columns = diesel::sql_query(
+                    "SELECT name, data_type, nullable, description, statistics
+                     FROM dataset_columns WHERE dataset_id = ?",
+                )
+                .bind::<Text, _>(dataset_id)
+                .get_results::<SqliteDatasetColumnRow>(&mut conn)
+                .await
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!("Failed to load dataset columns: {}", e),
+                })?;
+
+                let stats = diesel::sql_query(
+                    "SELECT stat_key, stat_value FROM dataset_statistics WHERE dataset_id = ?",
+                )
+                .bind::<Text, _>(dataset_id)
+                .get_results::<SqliteDatasetStatisticRow>(&mut conn)
+                .await
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!("Failed to load dataset statistics: {}", e),
+                })?;
+
+                let files_vec: Vec<DatasetFile> = files
+                    .into_iter()
+                    .map(DatasetFile::try_from)
+                    .collect::<Result<_, _>>()?;
+                let columns_vec: Vec<ColumnMetadata> = columns
+                    .into_iter()
+                    .map(ColumnMetadata::try_from)
+                    .collect::<Result<_, _>>()?;
+                let statistics: HashMap<String, String> = stats
+                    .into_iter()
+                    .map(|s| (s.stat_key, s.stat_value))
+                    .collect();
+
+                let CatalogDatasetEntry {
+                    id,
+                    uuid,
+                    name,
+                    description,
+                    format,
+                    size_bytes,
+                    row_count,
+                    tags,
+                    created_at,
+                    updated_at,
+                    dataset_path,
+                    max_size_bytes,
+                    max_row_count,
+                    source_path,
+                    ..
+                } = CatalogDatasetEntry::try_from(dataset)?;
+
+                Ok(DatasetMetadataFile {
+                    id,
+                    uuid,
+                    name,
+                    description,
+                    format,
+                    size_bytes,
+                    row_count,
+                    tags,
+                    created_at,
+                    updated_at,
+                    dataset_path,
+                    files: files_vec,
+                    columns: columns_vec,
+                    statistics,
+                    max_size_bytes,
+                    max_row_count,
+                    source_path,
+                })
+            }
+        }
+    }
+
+    /// Deletes a dataset and all of its child rows (files, columns,
+    /// statistics). Returns `false` rather than erroring if `dataset_id`
+    /// doesn't exist, so callers can decide how to surface "not found".
+    pub async fn delete_dataset(&self, dataset_id: &str) -> Result<bool, AnalysisError> {
+        info!("Deleting dataset {} from catalog", dataset_id);
+
+        match &self.pool {
+            #[cfg(feature = "backend-postgres")]
+            DbPool::Postgres(pool) => {
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
+
+                conn.transaction::<_, AnalysisError, _>(|conn| {
+                    Box::pin(async move {
+                        diesel::delete(
+                            dataset_statistics::table
+                                .filter(dataset_statistics::dataset_id.eq(dataset_id)),
+                        )
+                        .execute(conn)
+                        .await
+                        .map_err(|e| AnalysisError::ConfigError {
+                            message: format!("Failed to delete dataset statistics: {}", e),
+                        })?;
+
+                        diesel::delete(
+                            dataset_columns::table
+                                .filter(dataset_columns::dataset_id.eq(dataset_id)),
+                        )
+                        .execute(conn)
+                        .await
+                        .map_err(|e| AnalysisError::ConfigError {
+                            message: format!("Failed to delete dataset columns: {}", e),
+                        })?;
+
+                        diesel::delete(
+                            dataset_files::table.filter(dataset_files::dataset_id.eq(dataset_id)),
+                        )
                         .execute(conn)
                         .await
                         .map_err(|e| AnalysisError::ConfigError {
-                            message: format!("Failed to insert dataset file: {}", e),
+                            message: format!("Failed to delete dataset files: {}", e),
+                        })?;
+
+                        let deleted =
+                            diesel::delete(datasets::table.filter(datasets::id.eq(dataset_id)))
+                                .execute(conn)
+                                .await
+                                .map_err(|e| AnalysisError::ConfigError {
+                                    message: format!("Failed to delete dataset: {}", e),
+                                })?;
+
+                        Ok(deleted > 0)
+                    })
+                })
+                .await
+            }
+            #[cfg(feature = "backend-sqlite")]
+            DbPool::Sqlite(pool) => {
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
                         })?;
+                Self::log_pool_status(pool);
+
+                diesel::sql_query("DELETE FROM dataset_statistics WHERE dataset_id = ?")
+                    .bind::<Text, _>(dataset_id)
+                    .execute(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to delete dataset statistics: {}", e),
+                    })?;
+
+                diesel::sql_query("DELETE FROM dataset_columns WHERE dataset_id = ?")
+                    .bind::<Text, _>(dataset_id)
+                    .execute(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to delete dataset columns: {}", e),
+                    })?;
+
+                diesel::sql_query("DELETE FROM dataset_files WHERE dataset_id = ?")
+                    .bind::<Text, _>(dataset_id)
+                    .execute(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to delete dataset files: {}", e),
+                    })?;
+
+                let deleted = diesel::sql_query("DELETE FROM datasets WHERE id = ?")
+                    .bind::<Text, _>(dataset_id)
+                    .execute(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to delete dataset: {}", e),
+                    })?;
+
+                Ok(deleted > 0)
+            }
+        }
+    }
+
+    /// Updates a dataset's description and/or tags, leaving any field left
+    /// as `None` unchanged. Returns `false` if `dataset_id` doesn't exist.
+    pub async fn update_dataset_metadata(
+        &self,
+        dataset_id: &str,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> Result<bool, AnalysisError> {
+        info!("Updating metadata for dataset {}", dataset_id);
+
+        if description.is_none() && tags.is_none() {
+            return Ok(self.get_dataset(dataset_id).await?.is_some());
+        }
+
+        let now = Utc::now();
+
+        match &self.pool {
+            #[cfg(feature = "backend-postgres")]
+            DbPool::Postgres(pool) => {
+                use crate::schema::datasets::dsl;
+
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
+
+                let updated = match (description, tags) {
+                    (Some(description), Some(tags)) => {
+                        diesel::update(dsl::datasets.filter(dsl::id.eq(dataset_id)))
+                            .set((
+                                dsl::description.eq(description),
+                                dsl::tags.eq(tags),
+                                dsl::updated_at.eq(now),
+                            ))
+                            .execute(&mut conn)
+                            .await
+                    }
+                    (Some(description), None) => {
+                        diesel::update(dsl::datasets.filter(dsl::id.eq(dataset_id)))
+                            .set((dsl::description.eq(description), dsl::updated_at.eq(now)))
+                            .execute(&mut conn)
+                            .await
+                    }
+                    (None, Some(tags)) => {
+                        diesel::update(dsl::datasets.filter(dsl::id.eq(dataset_id)))
+                            .set((dsl::tags.eq(tags), dsl::updated_at.eq(now)))
+                            .execute(&mut conn)
+                            .await
+                    }
+                    (None, None) => unreachable!("handled above"),
                 }
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!("Failed to update dataset metadata: {}", e),
+                })?;
 
-                for column in &metadata.columns {
-                    let statistics_json =
-                        serde_json::to_value(&column.statistics).map_err(|e| {
+                Ok(updated > 0)
+            }
+            #[cfg(feature = "backend-sqlite")]
+            DbPool::Sqlite(pool) => {
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
+
+                let updated = match (description, tags) {
+                    (Some(description), Some(tags)) => {
+                        let tags_json = serde_json::to_string(&tags).map_err(|e| {
                             AnalysisError::ConfigError {
-                                message: format!("Failed to serialize column statistics: {}", e),
+                                message: format!("Failed to serialize dataset tags: {}", e),
                             }
                         })?;
 
-                    let new_column = NewDatasetColumn {
-                        dataset_id: &metadata.id,
-                        name: &column.name,
-                        data_type: &column.data_type,
-                        nullable: column.nullable,
-                        description: &column.description,
-                        statistics: &statistics_json,
-                    };
-
-                    diesel::insert_into(dataset_columns::table)
-                        .values(&new_column)
-                        .execute(conn)
+                        diesel::sql_query(
+                            "UPDATE datasets SET description = ?, tags = ?, updated_at = ? WHERE id = ?",
+                        )
+                        .bind::<Text, _>(description)
+                        .bind::<Text, _>(tags_json)
+                        .bind::<Text, _>(now.to_rfc3339())
+                        .bind::<Text, _>(dataset_id)
+                        .execute(&mut conn)
                         .await
-                        .map_err(|e| AnalysisError::ConfigError {
-                            message: format!("Failed to insert dataset column: {}", e),
+                    }
+                    (Some(description), None) => diesel::sql_query(
+                        "UPDATE datasets SET description = ?, updated_at = ? WHERE id = ?",
+                    )
+                    .bind::<Text, _>(description)
+                    .bind::<Text, _>(now.to_rfc3339())
+                    .bind::<Text, _>(dataset_id)
+                    .execute(&mut conn)
+                    .await,
+                    (None, Some(tags)) => {
+                        let tags_json = serde_json::to_string(&tags).map_err(|e| {
+                            AnalysisError::ConfigError {
+                                message: format!("Failed to serialize dataset tags: {}", e),
+                            }
                         })?;
+
+                        diesel::sql_query(
+                            "UPDATE datasets SET tags = ?, updated_at = ? WHERE id = ?",
+                        )
+                        .bind::<Text, _>(tags_json)
+                        .bind::<Text, _>(now.to_rfc3339())
+                        .bind::<Text, _>(dataset_id)
+                        .execute(&mut conn)
+                        .await
+                    }
+                    (None, None) => unreachable!("handled above"),
                 }
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!("Failed to update dataset metadata: {}", e),
+                })?;
 
-                for (key, value) in &metadata.statistics {
-                    let new_stat = NewDatasetStatistic {
-                        dataset_id: &metadata.id,
-                        stat_key: key,
-                        stat_value: value,
-                    };
-
-                    diesel::insert_into(dataset_statistics::table)
-                        .values(&new_stat)
-                        .on_conflict((dataset_statistics::dataset_id, dataset_statistics::stat_key))
-                        .do_update()
-                        .set(dataset_statistics::stat_value.eq(value))
+                Ok(updated > 0)
+            }
+        }
+    }
+
+    /// Applies the result of a `resync_dataset` diff: upserts `upserts` (new or
+    /// changed files, keyed by `(dataset_id, filename)`), deletes
+    /// `removed_filenames`, and updates the dataset's aggregate `row_count`/
+    /// `size_bytes`/`updated_at`. On Postgres all of this runs in one
+    /// transaction, so a partial sync never leaves the catalog's aggregates
+    /// out of sync with its file rows. On SQLite the statements run one by
+    /// one with no enclosing transaction, so an error partway through leaves
+    /// the statements before it applied.
+    pub async fn apply_dataset_resync(
+        &self,
+        dataset_id: &str,
+        upserts: &[DatasetFile],
+        removed_filenames: &[String],
+        row_count: i64,
+        size_bytes: i64,
+    ) -> Result<(), AnalysisError> {
+        info!(
+            "Applying resync for dataset {}: {} upserted, {} removed",
+            dataset_id,
+            upserts.len(),
+            removed_filenames.len()
+        );
+
+        let now = Utc::now();
+
+        match &self.pool {
+            #[cfg(feature = "backend-postgres")]
+            DbPool::Postgres(pool) => {
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
+
+                let dataset_id = dataset_id.to_string();
+
+                conn.transaction::<_, AnalysisError, _>(|conn| {
+                    Box::pin(async move {
+                        for file in upserts {
+                            let new_file = NewDatasetFile {
+                                dataset_id: &dataset_id,
+                                filename: &file.filename,
+                                storage_path: &file.storage_path,
+                                size_bytes: file.size_bytes,
+                                row_count: file.row_count,
+                                created_at: file.created_at,
+                                content_hash: file.content_hash.as_deref(),
+                                upstream_etag: file.upstream_etag.as_deref(),
+                                upstream_last_modified: file.upstream_last_modified,
+                                compression: file.compression.as_ref().map(CompressionType::as_str),
+                            };
+
+                            diesel::insert_into(dataset_files::table)
+                                .values(&new_file)
+                                .on_conflict((dataset_files::dataset_id, dataset_files::filename))
+                                .do_update()
+                                .set((
+                                    dataset_files::storage_path.eq(&file.storage_path),
+                                    dataset_files::size_bytes.eq(file.size_bytes),
+                                    dataset_files::row_count.eq(file.row_count),
+                                    dataset_files::content_hash.eq(&file.content_hash),
+                                    dataset_files::upstream_etag.eq(&file.upstream_etag),
+                                    dataset_files::upstream_last_modified
+                                        .eq(file.upstream_last_modified),
+                                    dataset_files::compression
+                                        .eq(file.compression.as_ref().map(CompressionType::as_str)),
+                                ))
+                                .execute(conn)
+                                .await
+                                .map_err(|e| AnalysisError::ConfigError {
+                                    message: format!("Failed to upsert dataset file: {}", e),
+                                })?;
+                        }
+
+                        for filename in removed_filenames {
+                            diesel::delete(
+                                dataset_files::table.filter(
+                                    dataset_files::dataset_id
+                                        .eq(&dataset_id)
+                                        .and(dataset_files::filename.eq(filename)),
+                                ),
+                            )
+                            .execute(conn)
+                            .await
+                            .map_err(|e| AnalysisError::ConfigError {
+                                message: format!("Failed to delete dataset file: {}", e),
+                            })?;
+                        }
+
+                        diesel::update(
+                            datasets::table.filter(datasets::id.eq(&dataset_id)),
+                        )
+                        .set((
+                            datasets::row_count.eq(row_count as i32),
+                            datasets::size_bytes.eq(size_bytes),
+                            datasets::updated_at.eq(now),
+                        ))
                         .execute(conn)
                         .await
                         .map_err(|e| AnalysisError::ConfigError {
-                            message: format!("Failed to insert dataset statistic: {}", e),
+                            message: format!("Failed to update dataset aggregates: {}", e),
                         })?;
+
+                        notify(conn, CATALOG_CHANGED_CHANNEL, &dataset_id).await
+                    })
+                })
+                .await
+            }
+            #[cfg(feature = "backend-sqlite")]
+            DbPool::Sqlite(pool) => {
+                let mut conn =
+                    pool.get()
+                        .await
+                        .map_err(|e| AnalysisError::PoolError {
+                            message: format!("Failed to get database connection: {}", e),
+                        })?;
+                Self::log_pool_status(pool);
+
+                for file in upserts {
+                    diesel::sql_query(
+                        "INSERT INTO dataset_files (
+                            dataset_id, filename, storage_path, size_bytes, row_count, created_at,
+                            content_hash, upstream_etag, upstream_last_modified, compression
+                        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                        ON CONFLICT (dataset_id, filename) DO UPDATE SET
+                            storage_path = excluded.storage_path,
+                            size_bytes = excluded.size_bytes,
+                            row_count = excluded.row_count,
+                            content_hash = excluded.content_hash,
+                            upstream_etag = excluded.upstream_etag,
+                            upstream_last_modified = excluded.upstream_last_modified,
+                            compression = excluded.compression",
+                    )
+                    .bind::<Text, _>(dataset_id)
+                    .bind::<Text, _>(&file.filename)
+                    .bind::<Text, _>(&file.storage_path)
+                    .bind::<BigInt, _>(file.size_bytes)
+                    .bind::<Integer, _>(file.row_count)
+                    .bind::<Text, _>(file.created_at.to_rfc3339())
+                    .bind::<Nullable<Text>, _>(&file.content_hash)
+                    .bind::<Nullable<Text>, _>(&file.upstream_etag)
+                    .bind::<Nullable<Text>, _>(file.upstream_last_modified.map(|dt| dt.to_rfc3339()))
+                    .bind::<Nullable<Text>, _>(file.compression.as_ref().map(CompressionType::as_str))
+                    .execute(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to upsert dataset file: {}", e),
+                    })?;
                 }
 
+                for filename in removed_filenames {
+                    diesel::sql_query(
+                        "DELETE FROM dataset_files WHERE dataset_id = ? AND filename = ?",
+                    )
+                    .bind::<Text, _>(dataset_id)
+                    .bind::<Text, _>(filename)
+                    .execute(&mut conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to delete dataset file: {}", e),
+                    })?;
+                }
+
+                diesel::sql_query(
+                    "UPDATE datasets SET row_count = ?, size_bytes = ?, updated_at = ? WHERE id = ?",
+                )
+                .bind::<Integer, _>(row_count as i32)
+                .bind::<BigInt, _>(size_bytes)
+                .bind::<Text, _>(now.to_rfc3339())
+                .bind::<Text, _>(dataset_id)
+                .execute(&mut conn)
+                .await
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!("Failed to update dataset aggregates: {}", e),
+                })?;
+
                 Ok(())
+            }
+        }
+    }
+
+    /// Inserts `payload` onto `queue` and returns the new job's id. Postgres
+    /// only: `claim_job`'s `SKIP LOCKED` semantics have no SQLite
+    /// equivalent, so the job queue isn't available on that backend.
+    #[cfg(feature = "backend-postgres")]
+    pub async fn enqueue_job(
+        &self,
+        queue: &str,
+        payload: &serde_json::Value,
+    ) -> Result<Uuid, AnalysisError> {
+        info!("Enqueuing job on queue {}", queue);
+
+        #[derive(diesel::QueryableByName)]
+        struct IdRow {
+            #[diesel(sql_type = UuidType)]
+            id: Uuid,
+        }
+
+        let DbPool::Postgres(pool) = &self.pool else {
+            return Err(AnalysisError::ConfigError {
+                message: "The job queue is only supported on the postgres backend".to_string(),
+            });
+        };
+
+        let mut conn = pool.get().await.map_err(|e| AnalysisError::PoolError {
+            message: format!("Failed to get database connection: {}", e),
+        })?;
+        Self::log_pool_status(pool);
+
+        let queue_owned = queue.to_string();
+        let payload = payload.clone();
+
+        let row: IdRow = conn
+            .transaction::<_, AnalysisError, _>(|conn| {
+                Box::pin(async move {
+                    let row = diesel::sql_query(
+                        "INSERT INTO job_queue (queue, job) VALUES ($1, $2) RETURNING id",
+                    )
+                    .bind::<Text, _>(&queue_owned)
+                    .bind::<Jsonb, _>(&payload)
+                    .get_result::<IdRow>(conn)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!("Failed to enqueue job: {}", e),
+                    })?;
+
+                    notify(conn, JOB_QUEUE_CHANNEL, &queue_owned).await?;
+
+                    Ok(row)
+                })
             })
-        })
+            .await?;
+
+        Ok(row.id)
+    }
+
+    /// Atomically claims the oldest `new` job on `queue`, flipping it to
+    /// `running` and returning it, or `None` if `queue` has nothing pending.
+    /// `FOR UPDATE SKIP LOCKED` means multiple workers can call this
+    /// concurrently against the same queue without double-claiming a job.
+    /// Postgres only; see [`Self::enqueue_job`].
+    #[cfg(feature = "backend-postgres")]
+    pub async fn claim_job(&self, queue: &str) -> Result<Option<Job>, AnalysisError> {
+        let DbPool::Postgres(pool) = &self.pool else {
+            return Err(AnalysisError::ConfigError {
+                message: "The job queue is only supported on the postgres backend".to_string(),
+            });
+        };
+
+        let mut conn = pool.get().await.map_err(|e| AnalysisError::PoolError {
+            message: format!("Failed to get database connection: {}", e),
+        })?;
+        Self::log_pool_status(pool);
+
+        let rows = diesel::sql_query(
+            "UPDATE job_queue SET status = 'running'
+             WHERE id = (
+                 SELECT id FROM job_queue
+                 WHERE queue = $1 AND status = 'new'
+                 ORDER BY created_at
+                 FOR UPDATE SKIP LOCKED
+                 LIMIT 1
+             )
+             RETURNING id, queue, job, status::text AS status, result, created_at",
+        )
+        .bind::<Text, _>(queue)
+        .get_results::<JobRow>(&mut conn)
         .await
+        .map_err(|e| AnalysisError::ConfigError {
+            message: format!("Failed to claim job: {}", e),
+        })?;
+
+        Ok(rows.into_iter().next().map(Job::from))
     }
 
-    pub async fn load_metadata(
+    /// Marks a job `done` and records its result. Postgres only; see
+    /// [`Self::enqueue_job`].
+    #[cfg(feature = "backend-postgres")]
+    pub async fn complete_job(
         &self,
-        dataset_id: &str,
-    ) -> Result<DatasetMetadataFile, AnalysisError> {
-        let mut conn = self
-            .pool
-            .get()
-            .await
-            .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to get database connection: {}", e),
-            })?;
+        job_id: Uuid,
+        result: &serde_json::Value,
+    ) -> Result<(), AnalysisError> {
+        let DbPool::Postgres(pool) = &self.pool else {
+            return Err(AnalysisError::ConfigError {
+                message: "The job queue is only supported on the postgres backend".to_string(),
+            });
+        };
 
-        let dataset = datasets::table
-            .filter(datasets::id.eq(dataset_id))
-            .get_result::<Dataset>(&mut conn)
-            .await
-            .map_err(|e| AnalysisError::DatasetNotFound {
-                dataset_id: format!("Failed to load dataset metadata for {}: {}", dataset_id, e),
-            })?;
+        let mut conn = pool.get().await.map_err(|e| AnalysisError::PoolError {
+            message: format!("Failed to get database connection: {}", e),
+        })?;
+        Self::log_pool_status(pool);
 
-        let files = dataset_files::table
-            .filter(dataset_files::dataset_id.eq(dataset_id))
-            .get_results::<DatasetFileModel>(&mut conn)
+        diesel::sql_query("UPDATE job_queue SET status = 'done', result = $2 WHERE id = $1")
+            .bind::<UuidType, _>(job_id)
+            .bind::<Jsonb, _>(result)
+            .execute(&mut conn)
             .await
             .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to load dataset files: {}", e),
+                message: format!("Failed to complete job {}: {}", job_id, e),
             })?;
 
-        let columns = dataset_columns::table
-            .filter(dataset_columns::dataset_id.eq(dataset_id))
-            .get_results::<DatasetColumnModel>(&mut conn)
-            .await
-            .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to load dataset columns: {}", e),
-            })?;
+        Ok(())
+    }
+
+    /// Marks a job `failed`, recording `error` as its result so callers
+    /// polling for the outcome can surface why it didn't succeed. Postgres
+    /// only; see [`Self::enqueue_job`].
+    #[cfg(feature = "backend-postgres")]
+    pub async fn fail_job(&self, job_id: Uuid, error: &str) -> Result<(), AnalysisError> {
+        let DbPool::Postgres(pool) = &self.pool else {
+            return Err(AnalysisError::ConfigError {
+                message: "The job queue is only supported on the postgres backend".to_string(),
+            });
+        };
+
+        let mut conn = pool.get().await.map_err(|e| AnalysisError::PoolError {
+            message: format!("Failed to get database connection: {}", e),
+        })?;
+        Self::log_pool_status(pool);
+
+        let result = serde_json::json!({ "error": error });
 
-        let stats = dataset_statistics::table
-            .filter(dataset_statistics::dataset_id.eq(dataset_id))
-            .get_results::<DatasetStatisticModel>(&mut conn)
+        diesel::sql_query("UPDATE job_queue SET status = 'failed', result = $2 WHERE id = $1")
+            .bind::<UuidType, _>(job_id)
+            .bind::<Jsonb, _>(&result)
+            .execute(&mut conn)
             .await
             .map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to load dataset statistics: {}", e),
+                message: format!("Failed to fail job {}: {}", job_id, e),
             })?;
 
-        let format = match dataset.format.as_str() {
-            "csv" => DataFormat::Csv,
-            "parquet" => DataFormat::Parquet,
-            _ => DataFormat::Csv,
+        Ok(())
+    }
+
+    /// Reads a job's current status/result without claiming it, for a
+    /// client polling after `enqueue_job`. Unlike `claim_job`, this never
+    /// mutates `status`. Postgres only; see [`Self::enqueue_job`].
+    #[cfg(feature = "backend-postgres")]
+    pub async fn get_job(&self, job_id: Uuid) -> Result<Option<Job>, AnalysisError> {
+        let DbPool::Postgres(pool) = &self.pool else {
+            return Err(AnalysisError::ConfigError {
+                message: "The job queue is only supported on the postgres backend".to_string(),
+            });
         };
 
-        let files_vec: Vec<DatasetFile> = files.into_iter().map(|f| f.into()).collect();
-        let columns_vec: Vec<ColumnMetadata> = columns.into_iter().map(|c| c.into()).collect();
-        let statistics: HashMap<String, String> = stats
-            .into_iter()
-            .map(|s| (s.stat_key, s.stat_value))
-            .collect();
-
-        Ok(DatasetMetadataFile {
-            id: dataset.id,
-            uuid: dataset.uuid,
-            name: dataset.name,
-            description: dataset.description,
-            format,
-            size_bytes: dataset.size_bytes,
-            row_count: dataset.row_count,
-            tags: dataset.tags,
-            created_at: dataset.created_at,
-            updated_at: dataset.updated_at,
-            dataset_path: dataset.dataset_path,
-            files: files_vec,
-            columns: columns_vec,
-            statistics,
-        })
+        let mut conn = pool.get().await.map_err(|e| AnalysisError::PoolError {
+            message: format!("Failed to get database connection: {}", e),
+        })?;
+        Self::log_pool_status(pool);
+
+        let rows = diesel::sql_query(
+            "SELECT id, queue, job, status::text AS status, result, created_at
+             FROM job_queue WHERE id = $1",
+        )
+        .bind::<UuidType, _>(job_id)
+        .get_results::<JobRow>(&mut conn)
+        .await
+        .map_err(|e| AnalysisError::ConfigError {
+            message: format!("Failed to load job {}: {}", job_id, e),
+        })?;
+
+        Ok(rows.into_iter().next().map(Job::from))
     }
 }
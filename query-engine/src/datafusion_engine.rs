@@ -1,6 +1,12 @@
+use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::arrow::ipc::writer::StreamWriter;
+use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::catalog::{CatalogProvider, MemoryCatalogProvider, MemorySchemaProvider};
+use datafusion::common::stats::Precision;
+use datafusion::datasource::file_format::arrow::ArrowFormat;
+use datafusion::datasource::file_format::avro::AvroFormat;
 use datafusion::datasource::file_format::csv::CsvFormat;
+use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
 use datafusion::datasource::file_format::json::JsonFormat;
 use datafusion::datasource::file_format::parquet::ParquetFormat;
 use datafusion::datasource::listing::{
@@ -10,23 +16,370 @@ use datafusion::execution::config::SessionConfig;
 use datafusion::execution::context::SessionContext;
 use datafusion::execution::object_store::ObjectStoreUrl;
 use datafusion::execution::runtime_env::RuntimeEnvBuilder;
-use object_store::{aws::AmazonS3Builder, gcp::GoogleCloudStorageBuilder, ObjectStore};
-use std::collections::HashSet;
+use datafusion::physical_plan::SendableRecordBatchStream;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use object_store::http::HttpBuilder;
+use object_store::ObjectStore;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 use url::Url;
 
+use crate::catalog::CompressionType;
 use crate::dataset_manager::DatasetInfo;
+use crate::delta_sharing_client::SharedTableFile;
 use crate::domain::{ColumnInfo, QueryDataChunk, QueryMetadata, QueryStreamResult};
 use crate::error::AnalysisError;
+use crate::gcs_client::create_gcs_client;
+use crate::metrics::Metrics;
+use crate::resilient_store::wrap_object_store;
+use crate::s3_client::create_s3_client;
+
+/// Builds the `ListingOptions` for a given file format, applying the requested
+/// file-level compression. Registered per format name in `DataFusionEngine::new`
+/// so new formats can be added without touching `register_dataset`.
+type ListingOptionsFactory = fn(FileCompressionType) -> ListingOptions;
+
+/// Double-quotes a SQL identifier, escaping any embedded `"` by doubling it
+/// (the standard SQL-identifier escape), so a column name can never break
+/// out of the identifier position it's interpolated into.
+fn quote_identifier(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+fn file_extension(base: &str, compression: FileCompressionType) -> String {
+    match compression {
+        FileCompressionType::GZIP => format!("{}.gz", base),
+        FileCompressionType::BZIP2 => format!("{}.bz2", base),
+        FileCompressionType::XZ => format!("{}.xz", base),
+        FileCompressionType::ZSTD => format!("{}.zst", base),
+        FileCompressionType::UNCOMPRESSED => base.to_string(),
+    }
+}
+
+fn csv_listing_options(compression: FileCompressionType) -> ListingOptions {
+    let csv_format = CsvFormat::default()
+        .with_has_header(true)
+        .with_delimiter(b',')
+        .with_file_compression_type(compression);
+    ListingOptions::new(Arc::new(csv_format))
+        .with_file_extension(file_extension("csv", compression))
+        .with_collect_stat(true)
+}
+
+fn json_listing_options(compression: FileCompressionType) -> ListingOptions {
+    let json_format = JsonFormat::default().with_file_compression_type(compression);
+    ListingOptions::new(Arc::new(json_format))
+        .with_file_extension(file_extension("json", compression))
+        .with_collect_stat(true)
+}
+
+fn parquet_listing_options(_compression: FileCompressionType) -> ListingOptions {
+    let parquet_format = ParquetFormat::default();
+    ListingOptions::new(Arc::new(parquet_format)).with_collect_stat(true)
+}
+
+fn avro_listing_options(_compression: FileCompressionType) -> ListingOptions {
+    let avro_format = AvroFormat::default();
+    ListingOptions::new(Arc::new(avro_format)).with_collect_stat(true)
+}
+
+fn arrow_ipc_listing_options(_compression: FileCompressionType) -> ListingOptions {
+    let arrow_format = ArrowFormat::default();
+    ListingOptions::new(Arc::new(arrow_format))
+        .with_file_extension(".arrow")
+        .with_collect_stat(true)
+}
+
+fn default_format_factories() -> HashMap<&'static str, ListingOptionsFactory> {
+    let mut factories: HashMap<&'static str, ListingOptionsFactory> = HashMap::new();
+    factories.insert("csv", csv_listing_options);
+    factories.insert("json", json_listing_options);
+    factories.insert("ndjson", json_listing_options);
+    factories.insert("parquet", parquet_listing_options);
+    factories.insert("avro", avro_listing_options);
+    factories.insert("arrow_ipc", arrow_ipc_listing_options);
+    factories
+}
+
+fn compression_type(compression: &Option<CompressionType>) -> FileCompressionType {
+    match compression {
+        Some(CompressionType::Gzip) => FileCompressionType::GZIP,
+        Some(CompressionType::Bzip2) => FileCompressionType::BZIP2,
+        Some(CompressionType::Xz) => FileCompressionType::XZ,
+        Some(CompressionType::Zstd) => FileCompressionType::ZSTD,
+        None => FileCompressionType::UNCOMPRESSED,
+    }
+}
+
+fn precision_value(precision: &Precision<usize>) -> Option<usize> {
+    match precision {
+        Precision::Exact(value) | Precision::Inexact(value) => Some(*value),
+        Precision::Absent => None,
+    }
+}
+
+const CHUNK_SIZE: usize = 1000;
+
+fn encode_chunk(
+    schema: &SchemaRef,
+    batch: &RecordBatch,
+    chunk_index: i32,
+) -> Result<QueryDataChunk, AnalysisError> {
+    let mut chunk_data = Vec::new();
+    {
+        let mut writer = StreamWriter::try_new(&mut chunk_data, schema)?;
+        writer.write(batch)?;
+        writer.finish()?;
+    }
+
+    Ok(QueryDataChunk {
+        arrow_ipc_data: chunk_data,
+        chunk_rows: batch.num_rows() as i32,
+        chunk_index,
+    })
+}
+
+/// State driving `chunk_stream`'s `unfold`: the upstream batch stream, plus a
+/// batch left over from a previous poll that still has rows beyond `CHUNK_SIZE`.
+struct ChunkStreamState {
+    batches: SendableRecordBatchStream,
+    schema: SchemaRef,
+    chunk_index: i32,
+    pending: Option<(RecordBatch, usize)>,
+}
+
+/// Re-slices an upstream `SendableRecordBatchStream` into `CHUNK_SIZE`-row
+/// `QueryDataChunk`s as batches arrive, so memory stays bounded by chunk size
+/// regardless of how many rows the query ultimately returns.
+fn chunk_stream(
+    batches: SendableRecordBatchStream,
+    schema: SchemaRef,
+) -> impl Stream<Item = Result<QueryDataChunk, AnalysisError>> {
+    stream::unfold(
+        ChunkStreamState {
+            batches,
+            schema,
+            chunk_index: 0,
+            pending: None,
+        },
+        |mut state| async move {
+            loop {
+                if let Some((batch, start_row)) = state.pending.take() {
+                    let end_row = std::cmp::min(start_row + CHUNK_SIZE, batch.num_rows());
+                    let chunk_batch = batch.slice(start_row, end_row - start_row);
+                    let item = encode_chunk(&state.schema, &chunk_batch, state.chunk_index);
+                    state.chunk_index += 1;
+                    if end_row < batch.num_rows() {
+                        state.pending = Some((batch, end_row));
+                    }
+                    return Some((item, state));
+                }
+
+                match state.batches.next().await {
+                    Some(Ok(batch)) if batch.num_rows() > 0 => {
+                        state.pending = Some((batch, 0));
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => {
+                        return Some((
+                            Err(AnalysisError::QueryExecutionFailed {
+                                message: e.to_string(),
+                            }),
+                            state,
+                        ));
+                    }
+                    None => return None,
+                }
+            }
+        },
+    )
+}
+
+/// Live usage for a registered dataset, tracked against its optional quota.
+/// Populated from the `ListingTable`'s inferred statistics when the dataset is
+/// registered, so it reflects the dataset's actual files rather than the
+/// catalog's static `size_bytes`/`row_count` snapshot.
+///
+/// `size_bytes`/`row_count` are `None` when DataFusion couldn't determine an
+/// exact or inexact value (`Precision::Absent`) - e.g. a format whose
+/// `ListingOptions` don't collect statistics, or a scan DataFusion gave up
+/// estimating. Treat that the same as "unknown", not "zero": a quota can't be
+/// verified against a stat nobody actually measured.
+#[derive(Debug, Clone, Default)]
+struct DatasetUsage {
+    size_bytes: Option<i64>,
+    row_count: Option<i64>,
+    max_size_bytes: Option<i64>,
+    max_row_count: Option<i32>,
+}
+
+impl DatasetUsage {
+    /// Fails closed: a quota that can't be checked because its stat is
+    /// unknown is treated as a violation, not silently allowed through the
+    /// way `.unwrap_or(0)` used to. [`DataFusionEngine::recompute_dataset_usage`]
+    /// re-reads the registered table's statistics and clears the violation
+    /// only if they now report the stat; it does not change how statistics
+    /// are collected.
+    fn quota_violation(&self, dataset_id: &str) -> Option<AnalysisError> {
+        if let Some(max_size_bytes) = self.max_size_bytes {
+            match self.size_bytes {
+                Some(size_bytes) if size_bytes > max_size_bytes => {
+                    return Some(AnalysisError::QuotaExceeded {
+                        dataset_id: dataset_id.to_string(),
+                        message: format!(
+                            "scan would read {} bytes, exceeding the {} byte quota",
+                            size_bytes, max_size_bytes
+                        ),
+                    });
+                }
+                None => {
+                    return Some(AnalysisError::QuotaExceeded {
+                        dataset_id: dataset_id.to_string(),
+                        message: format!(
+                            "dataset size is unknown (statistics not collected) and cannot be verified against the {} byte quota; recompute usage first",
+                            max_size_bytes
+                        ),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(max_row_count) = self.max_row_count {
+            match self.row_count {
+                Some(row_count) if row_count > max_row_count as i64 => {
+                    return Some(AnalysisError::QuotaExceeded {
+                        dataset_id: dataset_id.to_string(),
+                        message: format!(
+                            "scan would read {} rows, exceeding the {} row quota",
+                            row_count, max_row_count
+                        ),
+                    });
+                }
+                None => {
+                    return Some(AnalysisError::QuotaExceeded {
+                        dataset_id: dataset_id.to_string(),
+                        message: format!(
+                            "dataset row count is unknown (statistics not collected) and cannot be verified against the {} row quota; recompute usage first",
+                            max_row_count
+                        ),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod dataset_usage_tests {
+    use super::*;
+
+    #[test]
+    fn within_known_quota_is_not_a_violation() {
+        let usage = DatasetUsage {
+            size_bytes: Some(100),
+            row_count: Some(10),
+            max_size_bytes: Some(1_000),
+            max_row_count: Some(100),
+        };
+
+        assert!(usage.quota_violation("ds").is_none());
+    }
+
+    #[test]
+    fn known_stat_over_quota_is_a_violation() {
+        let usage = DatasetUsage {
+            size_bytes: Some(2_000),
+            row_count: Some(10),
+            max_size_bytes: Some(1_000),
+            max_row_count: None,
+        };
+
+        assert!(matches!(
+            usage.quota_violation("ds"),
+            Some(AnalysisError::QuotaExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn unknown_size_with_a_configured_quota_fails_closed() {
+        // Regression test: `Precision::Absent` statistics used to be
+        // collapsed into `0` via `.unwrap_or(0)`, which let a dataset with
+        // an unmeasured size sail through a configured quota. `size_bytes:
+        // None` must be treated as a violation, not as "0 bytes".
+        let usage = DatasetUsage {
+            size_bytes: None,
+            row_count: Some(10),
+            max_size_bytes: Some(1_000),
+            max_row_count: None,
+        };
+
+        assert!(matches!(
+            usage.quota_violation("ds"),
+            Some(AnalysisError::QuotaExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn unknown_row_count_with_a_configured_quota_fails_closed() {
+        let usage = DatasetUsage {
+            size_bytes: Some(100),
+            row_count: None,
+            max_size_bytes: None,
+            max_row_count: Some(100),
+        };
+
+        assert!(matches!(
+            usage.quota_violation("ds"),
+            Some(AnalysisError::QuotaExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn unknown_stats_without_any_quota_configured_is_not_a_violation() {
+        let usage = DatasetUsage {
+            size_bytes: None,
+            row_count: None,
+            max_size_bytes: None,
+            max_row_count: None,
+        };
+
+        assert!(usage.quota_violation("ds").is_none());
+    }
+}
 
 pub struct DataFusionEngine {
     ctx: SessionContext,
     registered_buckets: Arc<RwLock<HashSet<String>>>,
+    format_factories: HashMap<&'static str, ListingOptionsFactory>,
+    metrics: Arc<Metrics>,
+    dataset_usage: Arc<RwLock<HashMap<String, DatasetUsage>>>,
 }
 
 impl DataFusionEngine {
+    /// Builds a fresh, short-lived `SessionContext` for a single query,
+    /// sharing this engine's `RuntimeEnv` (object store registry, memory
+    /// pool) but starting with its own private, empty catalog list. Scratch
+    /// registrations like `base` (single-dataset queries) or a client-chosen
+    /// join alias live only in this scope, so two concurrent queries can
+    /// never collide on the same table name, and nothing needs to be
+    /// deregistered afterwards - the scope is simply dropped when the caller
+    /// is done with it. The permanent `agentic_analytics` catalog is
+    /// re-attached by reference so a query can still be written against a
+    /// dataset's own id.
+    pub(crate) fn new_query_scope(&self) -> SessionContext {
+        let scope = SessionContext::new_with_config_rt(SessionConfig::new(), self.ctx.runtime_env());
+        if let Some(catalog) = self.ctx.catalog("agentic_analytics") {
+            scope.register_catalog("agentic_analytics", catalog);
+        }
+        scope
+    }
+
     async fn get_table(
         &self,
         dataset_id: &str,
@@ -47,7 +400,7 @@ impl DataFusionEngine {
             })
     }
 
-    pub async fn new(bucket_name: String) -> Result<Self, AnalysisError> {
+    pub async fn new(bucket_name: String, metrics: Arc<Metrics>) -> Result<Self, AnalysisError> {
         info!(
             "Initializing DataFusion engine with GCS bucket: {}",
             bucket_name
@@ -79,48 +432,44 @@ impl DataFusionEngine {
         Ok(Self {
             ctx,
             registered_buckets: Arc::new(RwLock::new(HashSet::new())),
+            format_factories: default_format_factories(),
+            metrics,
+            dataset_usage: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
     pub async fn register_dataset(&self, dataset: &DatasetInfo) -> Result<(), AnalysisError> {
-        let file_path = if !dataset.files.is_empty() {
-            dataset.files[0].storage_path.clone()
+        let file = if !dataset.files.is_empty() {
+            dataset.files[0].clone()
         } else {
             return Err(AnalysisError::ConfigError {
                 message: format!("No files found for dataset {}", dataset.id),
             });
         };
 
-        self.register_object_store(file_path.clone()).await?;
+        self.register_object_store(file.storage_path.clone()).await?;
 
-        let table_url = ListingTableUrl::parse(&file_path)?;
-        let listing_options = match dataset.format.as_str() {
-            "csv" => {
-                let csv_format = CsvFormat::default()
-                    .with_has_header(true)
-                    .with_delimiter(b',');
-                ListingOptions::new(Arc::new(csv_format))
-            }
-            "json" => {
-                let json_format = JsonFormat::default();
-                ListingOptions::new(Arc::new(json_format))
-            }
-            "parquet" => {
-                let parquet_format = ParquetFormat::default();
-                ListingOptions::new(Arc::new(parquet_format))
-            }
-            _ => {
-                return Err(AnalysisError::ConfigError {
-                    message: format!("Unsupported file format: {}", dataset.format),
-                });
-            }
-        };
+        let table_url = ListingTableUrl::parse(&file.storage_path)?;
+        let factory = self
+            .format_factories
+            .get(dataset.format.as_str())
+            .ok_or_else(|| AnalysisError::ConfigError {
+                message: format!("Unsupported file format: {}", dataset.format),
+            })?;
+
+        let listing_options = factory(compression_type(&file.compression));
 
         let mut config = ListingTableConfig::new(table_url).with_listing_options(listing_options);
         config = config.infer_schema(&self.ctx.state()).await?;
 
         let table = ListingTable::try_new(config)?;
 
+        let usage = self.infer_dataset_usage(&table, dataset).await;
+        self.dataset_usage
+            .write()
+            .await
+            .insert(dataset.id.clone(), usage);
+
         let catalog =
             self.ctx
                 .catalog("agentic_analytics")
@@ -135,13 +484,204 @@ impl DataFusionEngine {
         schema.register_table(dataset.id.to_string(), Arc::new(table))?;
 
         info!("Registered dataset '{}' with DataFusion", dataset.id);
+        self.metrics.datasets_registered_total.inc();
+        Ok(())
+    }
+
+    /// Registers a Delta Sharing table's current pre-signed Parquet files as
+    /// `dataset_id`, without ever copying their bytes into our own storage.
+    /// Each file gets its own single-object HTTP store rooted at its
+    /// (unique, query-string-bearing) pre-signed URL, registered under a
+    /// synthetic `deltasharing://{dataset_id}-{index}` object store URL -
+    /// `ObjectStoreUrl` only keys on scheme+authority, so per-file object
+    /// stores need distinct authorities even though they're all logically
+    /// one table. `ListingTableConfig::new_with_multi_paths` then stitches
+    /// those single-file listings into one table with a unified schema.
+    pub async fn register_delta_share_table(
+        &self,
+        dataset_id: &str,
+        files: &[SharedTableFile],
+    ) -> Result<(), AnalysisError> {
+        if files.is_empty() {
+            return Err(AnalysisError::ConfigError {
+                message: format!(
+                    "Delta Sharing table backing dataset {} returned no files",
+                    dataset_id
+                ),
+            });
+        }
+
+        let mut table_urls = Vec::with_capacity(files.len());
+        for (index, file) in files.iter().enumerate() {
+            let store = HttpBuilder::new()
+                .with_url(file.url.clone())
+                .build()
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!(
+                        "Failed to create HTTP client for shared file {}: {}",
+                        file.id, e
+                    ),
+                })?;
+
+            let object_store_url =
+                ObjectStoreUrl::parse(&format!("deltasharing://{}-{}", dataset_id, index))?;
+            self.ctx
+                .register_object_store(object_store_url.as_ref(), Arc::new(store));
+
+            table_urls.push(ListingTableUrl::parse(&format!(
+                "deltasharing://{}-{}/part-{}.parquet",
+                dataset_id, index, index
+            ))?);
+        }
+
+        let listing_options = parquet_listing_options(FileCompressionType::UNCOMPRESSED);
+        let mut config =
+            ListingTableConfig::new_with_multi_paths(table_urls).with_listing_options(listing_options);
+        config = config.infer_schema(&self.ctx.state()).await?;
+
+        let table = ListingTable::try_new(config)?;
+
+        let statistics = table.statistics();
+        let (size_bytes, row_count) = match statistics {
+            Some(stats) => (
+                precision_value(&stats.total_byte_size).map(|v| v as i64),
+                precision_value(&stats.num_rows).map(|v| v as i64),
+            ),
+            None => (Some(files.iter().map(|f| f.size).sum()), None),
+        };
+        self.dataset_usage.write().await.insert(
+            dataset_id.to_string(),
+            DatasetUsage {
+                size_bytes,
+                row_count,
+                max_size_bytes: None,
+                max_row_count: None,
+            },
+        );
+
+        let catalog =
+            self.ctx
+                .catalog("agentic_analytics")
+                .ok_or_else(|| AnalysisError::ConfigError {
+                    message: "agentic_analytics catalog not found".to_string(),
+                })?;
+        let schema = catalog
+            .schema("public")
+            .ok_or_else(|| AnalysisError::ConfigError {
+                message: "public schema not found in agentic_analytics catalog".to_string(),
+            })?;
+        schema.register_table(dataset_id.to_string(), Arc::new(table))?;
+
+        info!(
+            "Registered Delta Sharing dataset '{}' with DataFusion ({} files)",
+            dataset_id,
+            files.len()
+        );
+        self.metrics.datasets_registered_total.inc();
+        Ok(())
+    }
+
+    /// Infers a dataset's current storage footprint from its `ListingTable`
+    /// statistics. `None` when DataFusion can't determine an exact or
+    /// inexact value (e.g. a format whose `ListingOptions` don't expose
+    /// stats on registration) - left unknown rather than zeroed out, so
+    /// [`DatasetUsage::quota_violation`] can fail closed on a configured quota.
+    async fn infer_dataset_usage(
+        &self,
+        table: &ListingTable,
+        dataset: &DatasetInfo,
+    ) -> DatasetUsage {
+        let statistics = table.statistics();
+
+        let (size_bytes, row_count) = match statistics {
+            Some(stats) => (
+                precision_value(&stats.total_byte_size).map(|v| v as i64),
+                precision_value(&stats.num_rows).map(|v| v as i64),
+            ),
+            None => (None, None),
+        };
+
+        DatasetUsage {
+            size_bytes,
+            row_count,
+            max_size_bytes: dataset.max_size_bytes,
+            max_row_count: dataset.max_row_count,
+        }
+    }
+
+    /// Recomputes a registered dataset's usage counter from scratch, discarding
+    /// whatever was tracked incrementally. Use this as a repair path if the
+    /// counter is ever suspected to have drifted (e.g. after a crash mid-update).
+    pub async fn recompute_dataset_usage(
+        &self,
+        dataset: &DatasetInfo,
+    ) -> Result<(), AnalysisError> {
+        let table_provider = self.get_table(&dataset.id).await?;
+        let table = table_provider
+            .as_any()
+            .downcast_ref::<ListingTable>()
+            .ok_or_else(|| AnalysisError::ConfigError {
+                message: format!("Dataset {} is not a listing table", dataset.id),
+            })?;
+
+        let usage = self.infer_dataset_usage(table, dataset).await;
+        self.dataset_usage
+            .write()
+            .await
+            .insert(dataset.id.clone(), usage);
+
         Ok(())
     }
 
+    /// Reports a registered dataset's current usage against its quota, if any.
+    /// Returns `(size_bytes, row_count, max_size_bytes, max_row_count)`;
+    /// `size_bytes`/`row_count` are `None` when statistics haven't been
+    /// collected rather than falsely reported as zero.
+    pub async fn dataset_usage(
+        &self,
+        dataset_id: &str,
+    ) -> Option<(Option<i64>, Option<i64>, Option<i64>, Option<i32>)> {
+        self.dataset_usage
+            .read()
+            .await
+            .get(dataset_id)
+            .map(|usage| {
+                (
+                    usage.size_bytes,
+                    usage.row_count,
+                    usage.max_size_bytes,
+                    usage.max_row_count,
+                )
+            })
+    }
+
     pub async fn is_dataset_registered(&self, dataset_id: &str) -> bool {
         self.get_table(dataset_id).await.is_ok()
     }
 
+    /// Tears down a dataset's registered `ListingTable` and tracked usage,
+    /// e.g. after the dataset itself has been deleted from the catalog, or
+    /// right before `refresh_dataset` re-registers it against the latest
+    /// files.
+    pub async fn deregister_dataset(&self, dataset_id: &str) -> Result<(), AnalysisError> {
+        let catalog = self
+            .ctx
+            .catalog("agentic_analytics")
+            .ok_or_else(|| AnalysisError::ConfigError {
+                message: "agentic_analytics catalog not found".to_string(),
+            })?;
+        let schema = catalog
+            .schema("public")
+            .ok_or_else(|| AnalysisError::ConfigError {
+                message: "public schema not found in agentic_analytics catalog".to_string(),
+            })?;
+
+        schema.deregister_table(dataset_id)?;
+        self.dataset_usage.write().await.remove(dataset_id);
+
+        Ok(())
+    }
+
     pub async fn get_table_schema(
         &self,
         dataset_id: &str,
@@ -173,20 +713,171 @@ impl DataFusionEngine {
         Ok(columns)
     }
 
-    pub async fn execute_query(
+    /// Reads every value of `text_column` from `dataset_id` in scan order,
+    /// for `semantic_search` embedding computation. A row's position in the
+    /// returned `Vec` (0-based) is the row identifier embeddings are keyed
+    /// by. Values are `CAST` to `VARCHAR` in the query itself so the caller
+    /// doesn't need to special-case the column's underlying Arrow type; a
+    /// SQL `NULL` becomes `None`.
+    pub async fn fetch_text_column(
+        &self,
+        dataset_id: &str,
+        text_column: &str,
+    ) -> Result<Vec<Option<String>>, AnalysisError> {
+        let table_provider = self.get_table(dataset_id).await?;
+        if !table_provider
+            .schema()
+            .fields()
+            .iter()
+            .any(|field| field.name() == text_column)
+        {
+            return Err(AnalysisError::InvalidSqlQuery {
+                message: format!(
+                    "Column '{}' does not exist on dataset '{}'",
+                    text_column, dataset_id
+                ),
+            });
+        }
+
+        let scope = self.new_query_scope();
+        scope.register_table("base", table_provider)?;
+
+        let query = format!(
+            "SELECT CAST({} AS VARCHAR) AS text_value FROM base",
+            quote_identifier(text_column)
+        );
+        let df = scope
+            .sql(&query)
+            .await
+            .map_err(|e| AnalysisError::InvalidSqlQuery {
+                message: e.to_string(),
+            })?;
+
+        let batches = df
+            .collect()
+            .await
+            .map_err(|e| AnalysisError::QueryExecutionFailed {
+                message: e.to_string(),
+            })?;
+
+        let mut values = Vec::new();
+        for batch in &batches {
+            let array = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<datafusion::arrow::array::StringArray>()
+                .ok_or_else(|| AnalysisError::ConfigError {
+                    message: format!("Column '{}' did not cast to a string array", text_column),
+                })?;
+            for i in 0..array.len() {
+                values.push(if array.is_null(i) {
+                    None
+                } else {
+                    Some(array.value(i).to_string())
+                });
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Executes a query and returns its metadata up front plus a
+    /// back-pressured stream of `QueryDataChunk`s, re-sliced to `CHUNK_SIZE`
+    /// rows as batches arrive from DataFusion's `SendableRecordBatchStream`.
+    /// Memory stays bounded by chunk size regardless of total result rows,
+    /// since nothing here buffers the full result set.
+    pub async fn execute_query_stream(
         &self,
         dataset_id: &str,
         sql_query: &str,
         limit: Option<i32>,
-    ) -> Result<QueryStreamResult, AnalysisError> {
-        let start_time = std::time::Instant::now();
+    ) -> Result<
+        (
+            Option<QueryMetadata>,
+            impl Stream<Item = Result<QueryDataChunk, AnalysisError>>,
+        ),
+        AnalysisError,
+    > {
+        if let Some(usage) = self.dataset_usage.read().await.get(dataset_id) {
+            if let Some(violation) = usage.quota_violation(dataset_id) {
+                return Err(violation);
+            }
+        }
 
-        info!("Executing query on dataset '{}': {}", dataset_id, sql_query);
+        let table_provider = self.get_table(dataset_id).await?;
+
+        let scope = self.new_query_scope();
+        scope.register_table("base", table_provider)?;
+
+        self.run_sql_stream(&scope, sql_query, limit).await
+    }
+
+    /// Registers an already-registered dataset's table provider under an
+    /// additional `alias` in `scope`, so a single SQL statement can
+    /// reference several datasets by name (e.g. for a JOIN) instead of
+    /// being confined to `execute_query_stream`'s single implicit `base`
+    /// table. `scope` must come from [`Self::new_query_scope`], so
+    /// concurrent callers never share aliases. Callers are responsible for
+    /// having registered `dataset_id` with `register_dataset` first.
+    ///
+    /// Enforces `dataset_id`'s quota the same way `execute_query_stream`
+    /// does, so a multi-dataset JOIN can't bypass `DatasetUsage::quota_violation`
+    /// by routing a query that touches an over-quota dataset through here
+    /// instead of the single-dataset path - every dataset joined into the
+    /// query gets checked, not just the first.
+    pub async fn register_table_alias(
+        &self,
+        scope: &SessionContext,
+        alias: &str,
+        dataset_id: &str,
+    ) -> Result<(), AnalysisError> {
+        if let Some(usage) = self.dataset_usage.read().await.get(dataset_id) {
+            if let Some(violation) = usage.quota_violation(dataset_id) {
+                return Err(violation);
+            }
+        }
 
         let table_provider = self.get_table(dataset_id).await?;
+        scope.register_table(alias, table_provider)?;
+        Ok(())
+    }
 
-        self.ctx.register_table("base", table_provider)?;
+    /// Like `execute_query_stream`, but for a query whose tables have
+    /// already been registered under their own names in `scope` (via
+    /// `register_table_alias`), rather than a single dataset forced through
+    /// the `base` alias. Used for multi-dataset JOINs across aliases.
+    pub async fn execute_registered_query_stream(
+        &self,
+        scope: &SessionContext,
+        sql_query: &str,
+        limit: Option<i32>,
+    ) -> Result<
+        (
+            Option<QueryMetadata>,
+            impl Stream<Item = Result<QueryDataChunk, AnalysisError>>,
+        ),
+        AnalysisError,
+    > {
+        self.run_sql_stream(scope, sql_query, limit).await
+    }
 
+    /// Shared tail of `execute_query_stream`/`execute_registered_query_stream`:
+    /// runs `sql_query` (appending `LIMIT` when `limit` is set and the query
+    /// doesn't already specify one) against whatever tables are currently
+    /// registered in `ctx`, and re-slices the resulting
+    /// `SendableRecordBatchStream` into `QueryDataChunk`s.
+    async fn run_sql_stream(
+        &self,
+        ctx: &SessionContext,
+        sql_query: &str,
+        limit: Option<i32>,
+    ) -> Result<
+        (
+            Option<QueryMetadata>,
+            impl Stream<Item = Result<QueryDataChunk, AnalysisError>>,
+        ),
+        AnalysisError,
+    > {
         let mut query = sql_query.to_string();
 
         if let Some(limit_val) = limit {
@@ -195,76 +886,159 @@ impl DataFusionEngine {
             }
         }
 
-        let df = self
-            .ctx
+        let df = ctx
             .sql(&query)
             .await
             .map_err(|e| AnalysisError::InvalidSqlQuery {
                 message: e.to_string(),
             })?;
 
-        let batches = df
-            .collect()
+        let arrow_schema: SchemaRef = Arc::new(df.schema().as_arrow().clone());
+
+        let estimated_rows = df
+            .clone()
+            .create_physical_plan()
             .await
-            .map_err(|e| AnalysisError::QueryExecutionFailed {
-                message: e.to_string(),
-            })?;
+            .ok()
+            .and_then(|plan| precision_value(&plan.statistics().ok()?.num_rows))
+            .unwrap_or(0) as i32;
 
-        let mut chunks = Vec::new();
-        let mut metadata = None;
+        let mut schema_bytes = Vec::new();
+        {
+            let mut writer = StreamWriter::try_new(&mut schema_bytes, &arrow_schema)?;
+            writer.finish()?;
+        }
 
-        if !batches.is_empty() {
-            let schema = batches[0].schema();
+        let metadata = Some(QueryMetadata {
+            arrow_schema: schema_bytes,
+            column_names: arrow_schema.fields().iter().map(|f| f.name().clone()).collect(),
+            estimated_rows,
+        });
 
-            let mut schema_bytes = Vec::new();
-            {
-                let mut writer = StreamWriter::try_new(&mut schema_bytes, &schema)?;
-                writer.finish()?;
-            }
+        let record_batch_stream =
+            df.execute_stream()
+                .await
+                .map_err(|e| AnalysisError::QueryExecutionFailed {
+                    message: e.to_string(),
+                })?;
 
-            metadata = Some(QueryMetadata {
-                arrow_schema: schema_bytes,
-                column_names: schema.fields().iter().map(|f| f.name().clone()).collect(),
-                estimated_rows: batches.iter().map(|b| b.num_rows() as i32).sum(),
-            });
+        Ok((metadata, chunk_stream(record_batch_stream, arrow_schema)))
+    }
+
+    /// Buffered convenience wrapper over [`Self::execute_query_stream`] for
+    /// callers (e.g. tests) that want the full `Vec<QueryDataChunk>` rather
+    /// than driving the stream themselves.
+    pub async fn execute_query(
+        &self,
+        dataset_id: &str,
+        sql_query: &str,
+        limit: Option<i32>,
+    ) -> Result<QueryStreamResult, AnalysisError> {
+        let start_time = std::time::Instant::now();
 
-            const CHUNK_SIZE: usize = 1000;
-            let mut chunk_index = 0;
+        info!("Executing query on dataset '{}': {}", dataset_id, sql_query);
 
-            for batch in batches {
-                let mut start_row = 0;
-                while start_row < batch.num_rows() {
-                    let end_row = std::cmp::min(start_row + CHUNK_SIZE, batch.num_rows());
-                    let chunk_batch = batch.slice(start_row, end_row - start_row);
+        let result: Result<QueryStreamResult, AnalysisError> = async {
+            let (metadata, stream) = self
+                .execute_query_stream(dataset_id, sql_query, limit)
+                .await?;
 
-                    let mut chunk_data = Vec::new();
-                    {
-                        let mut writer = StreamWriter::try_new(&mut chunk_data, &schema)?;
-                        writer.write(&chunk_batch)?;
-                        writer.finish()?;
-                    }
+            let chunks: Vec<QueryDataChunk> = stream.try_collect().await?;
 
-                    chunks.push(QueryDataChunk {
-                        arrow_ipc_data: chunk_data,
-                        chunk_rows: chunk_batch.num_rows() as i32,
-                        chunk_index,
-                    });
+            let total_rows: i32 = chunks.iter().map(|c| c.chunk_rows).sum();
+            info!(
+                "Query completed. Generated {} chunks with {} total rows in {}ms",
+                chunks.len(),
+                total_rows,
+                start_time.elapsed().as_millis()
+            );
 
-                    chunk_index += 1;
-                    start_row = end_row;
-                }
+            Ok(QueryStreamResult { metadata, chunks })
+        }
+        .await;
+
+        self.metrics
+            .query_duration_seconds
+            .observe(start_time.elapsed().as_secs_f64());
+
+        match &result {
+            Ok(query_result) => {
+                self.metrics.queries_executed_total.inc();
+                let total_rows: i64 = query_result.chunks.iter().map(|c| c.chunk_rows as i64).sum();
+                let total_bytes: i64 = query_result
+                    .chunks
+                    .iter()
+                    .map(|c| c.arrow_ipc_data.len() as i64)
+                    .sum();
+                self.metrics.rows_returned_total.inc_by(total_rows as u64);
+                self.metrics
+                    .arrow_ipc_bytes_returned_total
+                    .inc_by(total_bytes as u64);
+            }
+            Err(e) => {
+                self.metrics.record_query_failure(e);
             }
         }
 
-        let total_rows: i32 = chunks.iter().map(|c| c.chunk_rows).sum();
-        info!(
-            "Query completed. Generated {} chunks with {} total rows in {}ms",
-            chunks.len(),
-            total_rows,
-            start_time.elapsed().as_millis()
-        );
+        result
+    }
 
-        Ok(QueryStreamResult { metadata, chunks })
+    /// Buffered convenience wrapper over [`Self::execute_registered_query_stream`],
+    /// mirroring [`Self::execute_query`] for queries that span more than one
+    /// table registered via [`Self::register_table_alias`].
+    pub async fn execute_registered_query(
+        &self,
+        scope: &SessionContext,
+        sql_query: &str,
+        limit: Option<i32>,
+    ) -> Result<QueryStreamResult, AnalysisError> {
+        let start_time = std::time::Instant::now();
+
+        info!("Executing multi-dataset query: {}", sql_query);
+
+        let result: Result<QueryStreamResult, AnalysisError> = async {
+            let (metadata, stream) = self
+                .execute_registered_query_stream(scope, sql_query, limit)
+                .await?;
+
+            let chunks: Vec<QueryDataChunk> = stream.try_collect().await?;
+
+            let total_rows: i32 = chunks.iter().map(|c| c.chunk_rows).sum();
+            info!(
+                "Multi-dataset query completed. Generated {} chunks with {} total rows in {}ms",
+                chunks.len(),
+                total_rows,
+                start_time.elapsed().as_millis()
+            );
+
+            Ok(QueryStreamResult { metadata, chunks })
+        }
+        .await;
+
+        self.metrics
+            .query_duration_seconds
+            .observe(start_time.elapsed().as_secs_f64());
+
+        match &result {
+            Ok(query_result) => {
+                self.metrics.queries_executed_total.inc();
+                let total_rows: i64 = query_result.chunks.iter().map(|c| c.chunk_rows as i64).sum();
+                let total_bytes: i64 = query_result
+                    .chunks
+                    .iter()
+                    .map(|c| c.arrow_ipc_data.len() as i64)
+                    .sum();
+                self.metrics.rows_returned_total.inc_by(total_rows as u64);
+                self.metrics
+                    .arrow_ipc_bytes_returned_total
+                    .inc_by(total_bytes as u64);
+            }
+            Err(e) => {
+                self.metrics.record_query_failure(e);
+            }
+        }
+
+        result
     }
 
     pub async fn health_check(&self) -> Result<(), AnalysisError> {
@@ -402,25 +1176,13 @@ impl DataFusionEngine {
                 let bucket = url.host_str().ok_or_else(|| AnalysisError::ConfigError {
                     message: "Invalid S3 URL: missing bucket".to_string(),
                 })?;
-                let s3_store = AmazonS3Builder::new()
-                    .with_bucket_name(bucket)
-                    .build()
-                    .map_err(|e| AnalysisError::ConfigError {
-                        message: format!("Failed to create S3 client: {}", e),
-                    })?;
-                Arc::new(s3_store)
+                create_s3_client(bucket, &url)?
             }
             "gs" => {
                 let bucket = url.host_str().ok_or_else(|| AnalysisError::ConfigError {
                     message: "Invalid GCS URL: missing bucket".to_string(),
                 })?;
-                let gcs_store = GoogleCloudStorageBuilder::new()
-                    .with_bucket_name(bucket)
-                    .build()
-                    .map_err(|e| AnalysisError::ConfigError {
-                        message: format!("Failed to create GCS client: {}", e),
-                    })?;
-                Arc::new(gcs_store)
+                create_gcs_client(bucket)?
             }
             scheme => {
                 return Err(AnalysisError::ConfigError {
@@ -429,6 +1191,9 @@ impl DataFusionEngine {
             }
         };
 
+        let object_store = wrap_object_store(object_store);
+        self.metrics.object_store_requests_total.inc();
+
         self.ctx
             .register_object_store(object_store_url.as_ref(), object_store.clone());
         self.registered_buckets.write().await.insert(dataset_path);
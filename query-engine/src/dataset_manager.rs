@@ -1,18 +1,29 @@
 use chrono::Utc;
 use futures::StreamExt;
-use object_store::{aws::AmazonS3Builder};
 use object_store::{path::Path as ObjectPath, ObjectStore};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tracing::{info, warn};
 use url::Url;
 
-use crate::catalog::{CatalogDatasetEntry, DataFormat, DatasetFile, DatasetMetadataFile};
-use crate::database::DatabaseManager;
+use crate::azure_client::create_azure_client;
+use crate::catalog::{
+    CatalogDatasetEntry, ColumnMetadata, CompressionType, DataFormat, DatasetFile,
+    DatasetMetadataFile,
+};
+use crate::catalog_cache::{CatalogCacheConfig, DatasetEntryCache, MetadataDiskCache};
+use crate::database::{DatabaseConfig, DatabaseManager};
+use crate::delta_sharing_client::{
+    DeltaShareClient, DeltaSharingProfile, DeltaShareSource, PresignedUrlCache, SharedTableFile,
+};
 use crate::error::AnalysisError;
 use crate::gcs_client::create_gcs_client;
+use crate::http_client::create_http_client;
+use crate::local_fs_client::create_local_fs_client;
+use crate::metadata_extraction;
 use crate::proto::analysis::{ColumnInfo, Dataset, DatasetMetadata};
-use crate::storage::DatasetStorage;
+use crate::s3_client::create_s3_client;
+use crate::storage::{redact_source_path, DatasetStorage};
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
@@ -20,19 +31,102 @@ pub struct DatasetInfo {
     pub id: String,
     pub format: DataFormat,
     pub files: Vec<DatasetFile>,
+    pub max_size_bytes: Option<i64>,
+    pub max_row_count: Option<i32>,
+}
+
+/// Counts of what [`DatasetManager::resync_dataset`] changed, so callers can
+/// report something more useful than "done" to whoever triggered the sync.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResyncSummary {
+    pub files_added: usize,
+    pub files_updated: usize,
+    pub files_removed: usize,
 }
 
 pub struct DatasetManager {
     storage: DatasetStorage,
     database: DatabaseManager,
+    entry_cache: DatasetEntryCache,
+    metadata_cache: MetadataDiskCache,
+    presigned_cache: PresignedUrlCache,
+}
+
+/// Combines per-file column metadata (one `Vec<ColumnMetadata>` per dataset
+/// file) into a single dataset-level schema, summing `null_count` and
+/// widening `min`/`max` across files for columns that appear in more than
+/// one. Columns keep the statistics of whichever file introduced them for
+/// any stat that isn't meaningfully combinable (e.g. `mean`/`stddev`).
+fn merge_column_metadata(per_file: Vec<Vec<ColumnMetadata>>) -> Vec<ColumnMetadata> {
+    let mut merged: Vec<ColumnMetadata> = Vec::new();
+
+    for file_columns in per_file {
+        for column in file_columns {
+            match merged.iter_mut().find(|c| c.name == column.name) {
+                Some(existing) => merge_column_statistics(existing, &column.statistics),
+                None => merged.push(column),
+            }
+        }
+    }
+
+    merged
+}
+
+fn merge_column_statistics(existing: &mut ColumnMetadata, other: &HashMap<String, String>) {
+    let parse_i64 = |stats: &HashMap<String, String>| {
+        stats.get("null_count").and_then(|v| v.parse::<i64>().ok())
+    };
+    if let (Some(a), Some(b)) = (parse_i64(&existing.statistics), parse_i64(other)) {
+        existing
+            .statistics
+            .insert("null_count".to_string(), (a + b).to_string());
+    }
+
+    if let Some(other_min) = other.get("min") {
+        existing
+            .statistics
+            .entry("min".to_string())
+            .and_modify(|min| {
+                if other_min < min {
+                    *min = other_min.clone();
+                }
+            })
+            .or_insert_with(|| other_min.clone());
+    }
+
+    if let Some(other_max) = other.get("max") {
+        existing
+            .statistics
+            .entry("max".to_string())
+            .and_modify(|max| {
+                if other_max > max {
+                    *max = other_max.clone();
+                }
+            })
+            .or_insert_with(|| other_max.clone());
+    }
 }
 
 impl DatasetManager {
-    pub async fn new(bucket_name: String, database_url: String) -> Result<Self, AnalysisError> {
+    pub async fn new(
+        bucket_name: String,
+        database_url: String,
+        database_config: &DatabaseConfig,
+        cache_config: &CatalogCacheConfig,
+    ) -> Result<Self, AnalysisError> {
         let storage = DatasetStorage::new(bucket_name).await?;
-        let database = DatabaseManager::new(&database_url).await?;
-
-        Ok(Self { storage, database })
+        let database = DatabaseManager::new(&database_url, database_config).await?;
+        let entry_cache = DatasetEntryCache::new(cache_config.ttl, cache_config.max_entries);
+        let metadata_cache =
+            MetadataDiskCache::new(cache_config.metadata_cache_dir.clone(), cache_config.ttl);
+
+        Ok(Self {
+            storage,
+            database,
+            entry_cache,
+            metadata_cache,
+            presigned_cache: PresignedUrlCache::new(),
+        })
     }
 
     fn is_file_path(path: &str) -> bool {
@@ -40,6 +134,36 @@ impl DatasetManager {
         filename.contains('.') && !filename.ends_with('/')
     }
 
+    /// Lists every file-like object (per [`Self::is_file_path`]) under
+    /// `prefix` in `source_store`, keeping the full [`object_store::ObjectMeta`]
+    /// so callers can use its `e_tag`/`last_modified`/`size` for change
+    /// detection rather than just the path.
+    async fn list_directory_objects(
+        source_store: &Arc<dyn object_store::ObjectStore>,
+        prefix: &str,
+    ) -> Result<Vec<object_store::ObjectMeta>, AnalysisError> {
+        let prefix_path = ObjectPath::from(prefix);
+        let mut objects = Vec::new();
+        let mut stream = source_store.list(Some(&prefix_path));
+
+        while let Some(result) = stream.next().await {
+            match result {
+                Ok(meta) => {
+                    if Self::is_file_path(meta.location.as_ref()) {
+                        objects.push(meta);
+                    }
+                }
+                Err(e) => {
+                    return Err(AnalysisError::ConfigError {
+                        message: format!("Failed to list objects: {}", e),
+                    });
+                }
+            }
+        }
+
+        Ok(objects)
+    }
+
     async fn create_source_store_from_url(
         &self,
         source_url: &Url,
@@ -52,14 +176,7 @@ impl DatasetManager {
                         message: "Invalid S3 URL: missing bucket".to_string(),
                     })?;
 
-                let s3_store = AmazonS3Builder::new()
-                    .with_bucket_name(bucket)
-                    .build()
-                    .map_err(|e| AnalysisError::ConfigError {
-                        message: format!("Failed to create S3 client: {}", e),
-                    })?;
-
-                Ok(Arc::new(s3_store))
+                create_s3_client(bucket, source_url)
             }
             "gs" => {
                 let bucket = source_url
@@ -70,6 +187,17 @@ impl DatasetManager {
 
                 Ok(create_gcs_client(bucket)?)
             }
+            "az" | "abfs" | "azure" | "abfss" => {
+                let container = source_url
+                    .host_str()
+                    .ok_or_else(|| AnalysisError::ConfigError {
+                        message: "Invalid Azure URL: missing container".to_string(),
+                    })?;
+
+                create_azure_client(container, source_url)
+            }
+            "http" | "https" | "webdav" => Ok(create_http_client(source_url)?),
+            "file" => create_local_fs_client(),
             scheme => Err(AnalysisError::ConfigError {
                 message: format!("Unsupported storage scheme: {}", scheme),
             }),
@@ -86,7 +214,8 @@ impl DatasetManager {
     ) -> Result<String, AnalysisError> {
         info!(
             "Adding dataset from external path: {} ({})",
-            name, source_path
+            name,
+            redact_source_path(&source_path)
         );
 
         let dataset_id = format!("ds_{}", Uuid::new_v4().simple());
@@ -100,17 +229,21 @@ impl DatasetManager {
                 .unwrap_or("data")
                 .to_string();
 
-            let storage_path = self
+            let copied = self
                 .storage
                 .copy_from_external_storage(&source_path, &dataset_id, &filename)
                 .await?;
 
             vec![DatasetFile {
+                compression: CompressionType::from_filename(&filename),
                 filename,
-                storage_path,
-                size_bytes: 0,
+                storage_path: copied.storage_path,
+                size_bytes: copied.size_bytes,
                 row_count: 0,
                 created_at: now,
+                content_hash: Some(copied.content_hash),
+                upstream_etag: None,
+                upstream_last_modified: None,
             }]
         } else {
             let source_url = Url::parse(&source_path).map_err(|e| AnalysisError::ConfigError {
@@ -120,37 +253,21 @@ impl DatasetManager {
             let source_store = self.create_source_store_from_url(&source_url).await?;
             let source_prefix = source_url.path().trim_start_matches('/');
 
-            let file_objects = {
-                let prefix_path = ObjectPath::from(source_prefix);
-                let mut objects = Vec::new();
-                let mut stream = source_store.list(Some(&prefix_path));
-
-                while let Some(result) = stream.next().await {
-                    match result {
-                        Ok(meta) => {
-                            let path_str = meta.location.to_string();
-                            if Self::is_file_path(&path_str) {
-                                objects.push(path_str);
-                            }
-                        }
-                        Err(e) => {
-                            return Err(AnalysisError::ConfigError {
-                                message: format!("Failed to list objects: {}", e),
-                            });
-                        }
-                    }
-                }
-                objects
-            };
+            let file_objects =
+                Self::list_directory_objects(&source_store, source_prefix).await?;
 
             if file_objects.is_empty() {
                 return Err(AnalysisError::ConfigError {
-                    message: format!("No files found in directory: {}", source_path),
+                    message: format!(
+                        "No files found in directory: {}",
+                        redact_source_path(&source_path)
+                    ),
                 });
             }
 
             let mut dataset_files = Vec::new();
-            for file_path in file_objects {
+            for meta in file_objects {
+                let file_path = meta.location.to_string();
                 let filename = file_path
                     .split('/')
                     .next_back()
@@ -163,17 +280,21 @@ impl DatasetManager {
                     file_path
                 );
 
-                let storage_path = self
+                let copied = self
                     .storage
                     .copy_from_external_storage(&full_source_path, &dataset_id, &filename)
                     .await?;
 
                 dataset_files.push(DatasetFile {
+                    compression: CompressionType::from_filename(&filename),
                     filename,
-                    storage_path,
-                    size_bytes: 0,
+                    storage_path: copied.storage_path,
+                    size_bytes: copied.size_bytes,
                     row_count: 0,
                     created_at: now,
+                    content_hash: Some(copied.content_hash),
+                    upstream_etag: meta.e_tag,
+                    upstream_last_modified: Some(meta.last_modified),
                 });
             }
             dataset_files
@@ -190,24 +311,57 @@ impl DatasetManager {
             }
         });
 
+        let mut dataset_files = dataset_files;
+        let mut per_file_columns = Vec::with_capacity(dataset_files.len());
+        for file in &mut dataset_files {
+            let object_path = self.storage.resolve_object_path(&file.storage_path);
+            match metadata_extraction::extract_file_metadata(
+                self.storage.object_store(),
+                &object_path,
+                detected_format.clone(),
+            )
+            .await
+            {
+                Ok(extracted) => {
+                    file.row_count = extracted.row_count as i32;
+                    per_file_columns.push(extracted.columns);
+                }
+                Err(e) => metadata_extraction::warn_extraction_failed(&file.filename, &e),
+            }
+        }
+
+        let total_row_count: i64 = dataset_files.iter().map(|f| f.row_count as i64).sum();
+        let total_size_bytes: i64 = dataset_files.iter().map(|f| f.size_bytes).sum();
+        let columns = merge_column_metadata(per_file_columns);
+
+        let mut statistics = HashMap::new();
+        statistics.insert("row_count".to_string(), total_row_count.to_string());
+        statistics.insert("size_bytes".to_string(), total_size_bytes.to_string());
+        statistics.insert("file_count".to_string(), dataset_files.len().to_string());
+        statistics.insert("format".to_string(), detected_format.as_str().to_string());
+
         let dataset_path = format!("datasets/{}", dataset_id);
 
         let metadata = DatasetMetadataFile {
             id: dataset_id.clone(),
             uuid: dataset_uuid,
             name: name.clone(),
-            description: description
-                .unwrap_or_else(|| format!("Dataset imported from {}", source_path)),
+            description: description.unwrap_or_else(|| {
+                format!("Dataset imported from {}", redact_source_path(&source_path))
+            }),
             format: detected_format.clone(),
-            size_bytes: 0,
-            row_count: 0,
+            size_bytes: total_size_bytes,
+            row_count: total_row_count as i32,
             tags: tags.unwrap_or_default(),
             created_at: now,
             updated_at: now,
             dataset_path: dataset_path.clone(),
             files: dataset_files,
-            columns: vec![],
-            statistics: HashMap::new(),
+            columns,
+            statistics,
+            max_size_bytes: None,
+            max_row_count: None,
+            source_path: Some(source_path.clone()),
         };
 
         let metadata_path = format!("datasets/{}/metadata.json", dataset_id);
@@ -218,18 +372,24 @@ impl DatasetManager {
             name,
             description: metadata.description.clone(),
             format: detected_format,
-            size_bytes: 0,
-            row_count: 0,
+            size_bytes: metadata.size_bytes,
+            row_count: metadata.row_count,
             tags: metadata.tags.clone(),
             created_at: now,
             updated_at: now,
             dataset_path,
             metadata_path,
+            max_size_bytes: None,
+            max_row_count: None,
+            source_path: Some(source_path),
         };
 
         self.database.add_dataset(&catalog_entry).await?;
         self.database.save_metadata(&metadata).await?;
 
+        self.entry_cache.insert(catalog_entry).await;
+        self.metadata_cache.put(&metadata).await;
+
         info!(
             "Dataset {} added successfully from external path",
             dataset_id
@@ -237,36 +397,228 @@ impl DatasetManager {
         Ok(dataset_id)
     }
 
+    /// Registers a Delta Sharing table (`share.schema.table` against
+    /// `profile`) as a dataset without copying its data: the catalog only
+    /// ever stores the Delta Sharing coordinate (via
+    /// [`DeltaShareSource::encode`] in `source_path`), and the actual
+    /// pre-signed Parquet file URLs are resolved fresh on every DataFusion
+    /// registration through [`Self::resolve_delta_share_files`], since they
+    /// expire. Returns `ConfigError` if the table has no files matching
+    /// `partition_filters`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_dataset_from_delta_share(
+        &self,
+        name: String,
+        profile: DeltaSharingProfile,
+        share: String,
+        schema: String,
+        table: String,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
+        partition_filters: HashMap<String, String>,
+    ) -> Result<String, AnalysisError> {
+        info!(
+            "Adding Delta Sharing dataset from {}.{}.{} as '{}'",
+            share, schema, table, name
+        );
+
+        let client = DeltaShareClient::new(profile.clone());
+        let files = client
+            .query_table_files(&share, &schema, &table, &partition_filters)
+            .await?;
+
+        if files.is_empty() {
+            return Err(AnalysisError::ConfigError {
+                message: format!(
+                    "Shared table {}.{}.{} has no files matching the given partition filters",
+                    share, schema, table
+                ),
+            });
+        }
+
+        let dataset_id = format!("ds_{}", Uuid::new_v4().simple());
+        let now = Utc::now();
+        let dataset_uuid = Uuid::new_v4();
+        let total_size_bytes: i64 = files.iter().map(|f| f.size).sum();
+
+        let source = DeltaShareSource {
+            endpoint: profile.endpoint,
+            bearer_token: profile.bearer_token,
+            share: share.clone(),
+            schema: schema.clone(),
+            table: table.clone(),
+            partition_filters,
+        };
+        let source_path = source.encode()?;
+
+        let description =
+            description.unwrap_or_else(|| format!("Delta Sharing table {}.{}.{}", share, schema, table));
+
+        let mut statistics = HashMap::new();
+        statistics.insert("size_bytes".to_string(), total_size_bytes.to_string());
+        statistics.insert("file_count".to_string(), files.len().to_string());
+        statistics.insert("format".to_string(), DataFormat::Parquet.as_str().to_string());
+
+        let dataset_path = format!("datasets/{}", dataset_id);
+        let metadata_path = format!("datasets/{}/metadata.json", dataset_id);
+        let tags = tags.unwrap_or_default();
+
+        let metadata = DatasetMetadataFile {
+            id: dataset_id.clone(),
+            uuid: dataset_uuid,
+            name: name.clone(),
+            description: description.clone(),
+            format: DataFormat::Parquet,
+            size_bytes: total_size_bytes,
+            row_count: 0,
+            tags: tags.clone(),
+            created_at: now,
+            updated_at: now,
+            dataset_path: dataset_path.clone(),
+            files: Vec::new(),
+            columns: Vec::new(),
+            statistics,
+            max_size_bytes: None,
+            max_row_count: None,
+            source_path: Some(source_path.clone()),
+        };
+
+        let catalog_entry = CatalogDatasetEntry {
+            id: dataset_id.clone(),
+            uuid: dataset_uuid,
+            name,
+            description,
+            format: DataFormat::Parquet,
+            size_bytes: total_size_bytes,
+            row_count: 0,
+            tags,
+            created_at: now,
+            updated_at: now,
+            dataset_path,
+            metadata_path,
+            max_size_bytes: None,
+            max_row_count: None,
+            source_path: Some(source_path),
+        };
+
+        self.database.add_dataset(&catalog_entry).await?;
+        self.database.save_metadata(&metadata).await?;
+
+        self.entry_cache.insert(catalog_entry).await;
+        self.metadata_cache.put(&metadata).await;
+
+        info!("Delta Sharing dataset {} added successfully", dataset_id);
+        Ok(dataset_id)
+    }
+
+    /// Resolves `dataset_id`'s current pre-signed Delta Sharing file URLs if
+    /// it was imported via [`Self::add_dataset_from_delta_share`], refreshing
+    /// them through the shared [`PresignedUrlCache`] if they're close to
+    /// expiring. Returns `Ok(None)` for an ordinary (non-Delta-Sharing)
+    /// dataset so callers can tell "not this kind of dataset" apart from
+    /// "this kind of dataset, but the server returned nothing".
+    pub async fn resolve_delta_share_files(
+        &self,
+        dataset_id: &str,
+    ) -> Result<Option<Vec<SharedTableFile>>, AnalysisError> {
+        let entry = match self.get_catalog_entry(dataset_id).await? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        let source = match entry.source_path.as_deref().and_then(DeltaShareSource::decode) {
+            Some(source) => source?,
+            None => return Ok(None),
+        };
+
+        let client = DeltaShareClient::new(source.profile());
+        let files = self
+            .presigned_cache
+            .get_or_refresh(
+                &client,
+                &source.share,
+                &source.schema,
+                &source.table,
+                &source.partition_filters,
+            )
+            .await?;
+
+        Ok(Some(files))
+    }
+
+    /// Looks up a dataset's catalog row, checking the in-memory cache
+    /// before falling back to the database.
+    async fn get_catalog_entry(
+        &self,
+        dataset_id: &str,
+    ) -> Result<Option<CatalogDatasetEntry>, AnalysisError> {
+        if let Some(entry) = self.entry_cache.get(dataset_id).await {
+            return Ok(Some(entry));
+        }
+
+        let entry = self.database.get_dataset(dataset_id).await?;
+        if let Some(entry) = &entry {
+            self.entry_cache.insert(entry.clone()).await;
+        }
+        Ok(entry)
+    }
+
+    /// Loads a dataset's full metadata file, checking the local-disk cache
+    /// before falling back to the database.
+    async fn load_metadata_cached(
+        &self,
+        dataset_id: &str,
+    ) -> Result<DatasetMetadataFile, AnalysisError> {
+        if let Some(metadata) = self.metadata_cache.get(dataset_id).await {
+            return Ok(metadata);
+        }
+
+        let metadata = self.database.load_metadata(dataset_id).await?;
+        self.metadata_cache.put(&metadata).await;
+        Ok(metadata)
+    }
+
     pub async fn list_datasets(&self) -> Vec<Dataset> {
-        match self.database.list_datasets().await {
-            Ok(datasets) => datasets
-                .iter()
-                .map(|entry| Dataset {
-                    id: entry.id.clone(),
-                    name: entry.name.clone(),
-                    description: entry.description.clone(),
-                    file_path: entry.dataset_path.clone(),
-                    format: entry.format.as_str().to_string(),
-                    size_bytes: entry.size_bytes,
-                    row_count: entry.row_count,
-                    tags: entry.tags.clone(),
-                    created_at: entry.created_at.to_rfc3339(),
-                    updated_at: entry.updated_at.to_rfc3339(),
-                })
-                .collect(),
-            Err(e) => {
-                warn!("Failed to load datasets from database: {}", e);
-                Vec::new()
+        let entries = if let Some(entries) = self.entry_cache.get_listing().await {
+            entries
+        } else {
+            match self.database.list_datasets().await {
+                Ok(entries) => {
+                    self.entry_cache.set_listing(entries.clone()).await;
+                    entries
+                }
+                Err(e) => {
+                    warn!("Failed to load datasets from database: {}", e);
+                    return Vec::new();
+                }
             }
-        }
+        };
+
+        entries
+            .iter()
+            .map(|entry| Dataset {
+                id: entry.id.clone(),
+                name: entry.name.clone(),
+                description: entry.description.clone(),
+                file_path: entry.dataset_path.clone(),
+                format: entry.format.as_str().to_string(),
+                size_bytes: entry.size_bytes,
+                row_count: entry.row_count,
+                tags: entry.tags.clone(),
+                created_at: entry.created_at.to_rfc3339(),
+                updated_at: entry.updated_at.to_rfc3339(),
+            })
+            .collect()
     }
 
     pub async fn get_dataset(&self, dataset_id: &str) -> Option<DatasetInfo> {
-        match self.database.load_metadata(dataset_id).await {
+        match self.load_metadata_cached(dataset_id).await {
             Ok(metadata) => Some(DatasetInfo {
                 id: metadata.id.clone(),
                 format: metadata.format.clone(),
                 files: metadata.files,
+                max_size_bytes: metadata.max_size_bytes,
+                max_row_count: metadata.max_row_count,
             }),
             Err(e) => {
                 warn!("Failed to load dataset metadata from database: {}", e);
@@ -277,14 +629,13 @@ impl DatasetManager {
 
     pub async fn get_metadata(&self, dataset_id: &str) -> Result<DatasetMetadata, AnalysisError> {
         let _entry = self
-            .database
-            .get_dataset(dataset_id)
+            .get_catalog_entry(dataset_id)
             .await?
             .ok_or_else(|| AnalysisError::DatasetNotFound {
                 dataset_id: dataset_id.to_string(),
             })?;
 
-        let metadata = self.database.load_metadata(dataset_id).await?;
+        let metadata = self.load_metadata_cached(dataset_id).await?;
 
         Ok(DatasetMetadata {
             id: metadata.id,
@@ -310,4 +661,299 @@ impl DatasetManager {
             updated_at: metadata.updated_at.to_rfc3339(),
         })
     }
+
+    pub async fn generate_download_url(
+        &self,
+        dataset_id: &str,
+        filename: Option<&str>,
+        expires_in: std::time::Duration,
+    ) -> Result<String, AnalysisError> {
+        let metadata = self.load_metadata_cached(dataset_id).await?;
+
+        let file = match filename {
+            Some(filename) => metadata
+                .files
+                .iter()
+                .find(|f| f.filename == filename)
+                .ok_or_else(|| AnalysisError::ConfigError {
+                    message: format!(
+                        "File '{}' not found in dataset {}",
+                        filename, dataset_id
+                    ),
+                })?,
+            None => metadata
+                .files
+                .first()
+                .ok_or_else(|| AnalysisError::ConfigError {
+                    message: format!("Dataset {} has no files", dataset_id),
+                })?,
+        };
+
+        self.storage
+            .generate_presigned_url(&file.storage_path, expires_in)
+            .await
+    }
+
+    /// Deletes a dataset from the catalog. Returns `false` if it doesn't
+    /// exist rather than erroring, so callers can decide how to report it.
+    pub async fn delete_dataset(&self, dataset_id: &str) -> Result<bool, AnalysisError> {
+        let deleted = self.database.delete_dataset(dataset_id).await?;
+        self.entry_cache.invalidate(dataset_id).await;
+        self.metadata_cache.invalidate(dataset_id).await;
+        Ok(deleted)
+    }
+
+    /// Re-lists a directory-backed dataset's original `source_path` and
+    /// copies only the objects that are new or whose `e_tag`/`last_modified`/
+    /// `size` changed since the last import or sync, removing `DatasetFile`
+    /// rows for objects that disappeared upstream. Unlike
+    /// [`crate::engine::AnalysisEngine::refresh_dataset`], which just
+    /// re-registers whatever is already stored, this actually re-reads the
+    /// source. Returns `DatasetNotFound` if `dataset_id` doesn't exist, and
+    /// `ConfigError` if it has no recorded `source_path` (imported before
+    /// this was tracked).
+    pub async fn resync_dataset(&self, dataset_id: &str) -> Result<ResyncSummary, AnalysisError> {
+        let metadata = self.database.load_metadata(dataset_id).await.map_err(|_| {
+            AnalysisError::DatasetNotFound {
+                dataset_id: dataset_id.to_string(),
+            }
+        })?;
+
+        let source_path = metadata.source_path.clone().ok_or_else(|| AnalysisError::ConfigError {
+            message: format!(
+                "Dataset {} has no recorded source path to resync from (it was imported before incremental resync was supported)",
+                dataset_id
+            ),
+        })?;
+
+        if DeltaShareSource::decode(&source_path).is_some() {
+            return Err(AnalysisError::ConfigError {
+                message: format!(
+                    "Dataset {} is backed by Delta Sharing, which is always resolved fresh on registration rather than incrementally resynced",
+                    dataset_id
+                ),
+            });
+        }
+
+        let source_url = Url::parse(&source_path).map_err(|e| AnalysisError::ConfigError {
+            message: format!("Invalid source path URL: {}", e),
+        })?;
+        let source_store = self.create_source_store_from_url(&source_url).await?;
+
+        let remote_objects = if Self::is_file_path(&source_path) {
+            let filename = source_path
+                .split('/')
+                .next_back()
+                .unwrap_or("data")
+                .to_string();
+            let object_path = ObjectPath::from(source_url.path().trim_start_matches('/'));
+            let meta = source_store.head(&object_path).await.map_err(|e| {
+                AnalysisError::ConfigError {
+                    message: format!(
+                        "Failed to stat source object {}: {}",
+                        redact_source_path(&source_path),
+                        e
+                    ),
+                }
+            })?;
+            vec![(filename, meta)]
+        } else {
+            let source_prefix = source_url.path().trim_start_matches('/');
+            Self::list_directory_objects(&source_store, source_prefix)
+                .await?
+                .into_iter()
+                .map(|meta| {
+                    let filename = meta
+                        .location
+                        .to_string()
+                        .split('/')
+                        .next_back()
+                        .unwrap_or("data")
+                        .to_string();
+                    (filename, meta)
+                })
+                .collect()
+        };
+
+        let original_files: HashMap<String, DatasetFile> = metadata
+            .files
+            .into_iter()
+            .map(|f| (f.filename.clone(), f))
+            .collect();
+        let mut current_files = original_files.clone();
+
+        let mut upserts = Vec::new();
+        let mut seen_filenames = HashSet::new();
+        let now = Utc::now();
+
+        for (filename, meta) in remote_objects {
+            seen_filenames.insert(filename.clone());
+
+            let unchanged = original_files.get(&filename).is_some_and(|existing| {
+                existing.upstream_etag.as_deref() == meta.e_tag.as_deref()
+                    && existing.upstream_last_modified == Some(meta.last_modified)
+                    && existing.size_bytes == meta.size as i64
+            });
+            if unchanged {
+                continue;
+            }
+
+            let full_source_path = format!(
+                "{}://{}/{}",
+                source_url.scheme(),
+                source_url.host_str().unwrap_or(""),
+                meta.location
+            );
+
+            let copied = self
+                .storage
+                .copy_from_external_storage(&full_source_path, dataset_id, &filename)
+                .await?;
+
+            let mut file = DatasetFile {
+                compression: CompressionType::from_filename(&filename),
+                filename: filename.clone(),
+                storage_path: copied.storage_path,
+                size_bytes: copied.size_bytes,
+                row_count: 0,
+                created_at: now,
+                content_hash: Some(copied.content_hash),
+                upstream_etag: meta.e_tag,
+                upstream_last_modified: Some(meta.last_modified),
+            };
+
+            let object_path = self.storage.resolve_object_path(&file.storage_path);
+            match metadata_extraction::extract_file_metadata(
+                self.storage.object_store(),
+                &object_path,
+                metadata.format.clone(),
+            )
+            .await
+            {
+                Ok(extracted) => file.row_count = extracted.row_count as i32,
+                Err(e) => metadata_extraction::warn_extraction_failed(&filename, &e),
+            }
+
+            current_files.insert(filename.clone(), file.clone());
+            upserts.push(file);
+        }
+
+        let removed_filenames: Vec<String> = original_files
+            .keys()
+            .filter(|filename| !seen_filenames.contains(*filename))
+            .cloned()
+            .collect();
+        for filename in &removed_filenames {
+            current_files.remove(filename);
+        }
+
+        let files_added = upserts
+            .iter()
+            .filter(|f| !original_files.contains_key(&f.filename))
+            .count();
+        let files_updated = upserts.len() - files_added;
+
+        let total_row_count: i64 = current_files.values().map(|f| f.row_count as i64).sum();
+        let total_size_bytes: i64 = current_files.values().map(|f| f.size_bytes).sum();
+
+        self.database
+            .apply_dataset_resync(
+                dataset_id,
+                &upserts,
+                &removed_filenames,
+                total_row_count,
+                total_size_bytes,
+            )
+            .await?;
+
+        self.entry_cache.invalidate(dataset_id).await;
+        self.metadata_cache.invalidate(dataset_id).await;
+
+        info!(
+            "Resynced dataset {}: {} added, {} updated, {} removed",
+            dataset_id, files_added, files_updated, removed_filenames.len()
+        );
+
+        Ok(ResyncSummary {
+            files_added,
+            files_updated,
+            files_removed: removed_filenames.len(),
+        })
+    }
+
+    /// Updates a dataset's description and/or tags, leaving any field left
+    /// as `None` unchanged. Returns `false` if the dataset doesn't exist.
+    pub async fn update_dataset_metadata(
+        &self,
+        dataset_id: &str,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> Result<bool, AnalysisError> {
+        let updated = self
+            .database
+            .update_dataset_metadata(dataset_id, description, tags)
+            .await?;
+        self.entry_cache.invalidate(dataset_id).await;
+        self.metadata_cache.invalidate(dataset_id).await;
+        Ok(updated)
+    }
+
+    /// Yields dataset ids as they're added or updated, driven off Postgres
+    /// `NOTIFY catalog_changed` rather than callers re-polling
+    /// `list_datasets`.
+    pub fn watch_catalog(&self) -> impl futures::Stream<Item = String> {
+        self.database.watch_catalog()
+    }
+
+    /// Enqueues `payload` onto `queue` for asynchronous execution by a
+    /// worker, returning the new job's id immediately. See
+    /// [`crate::database::DatabaseManager::enqueue_job`].
+    #[cfg(feature = "backend-postgres")]
+    pub async fn enqueue_job(
+        &self,
+        queue: &str,
+        payload: &serde_json::Value,
+    ) -> Result<Uuid, AnalysisError> {
+        self.database.enqueue_job(queue, payload).await
+    }
+
+    /// Reads a job's current status/result for a client polling after
+    /// `enqueue_job`. See [`crate::database::DatabaseManager::get_job`].
+    #[cfg(feature = "backend-postgres")]
+    pub async fn get_job(&self, job_id: Uuid) -> Result<Option<crate::job_queue::Job>, AnalysisError> {
+        self.database.get_job(job_id).await
+    }
+
+    /// Atomically claims the oldest pending job on `queue` for a worker to
+    /// run. See [`crate::database::DatabaseManager::claim_job`].
+    #[cfg(feature = "backend-postgres")]
+    pub async fn claim_job(&self, queue: &str) -> Result<Option<crate::job_queue::Job>, AnalysisError> {
+        self.database.claim_job(queue).await
+    }
+
+    /// Marks a job done with its result. See
+    /// [`crate::database::DatabaseManager::complete_job`].
+    #[cfg(feature = "backend-postgres")]
+    pub async fn complete_job(
+        &self,
+        job_id: Uuid,
+        result: &serde_json::Value,
+    ) -> Result<(), AnalysisError> {
+        self.database.complete_job(job_id, result).await
+    }
+
+    /// Marks a job failed with an error message. See
+    /// [`crate::database::DatabaseManager::fail_job`].
+    #[cfg(feature = "backend-postgres")]
+    pub async fn fail_job(&self, job_id: Uuid, error: &str) -> Result<(), AnalysisError> {
+        self.database.fail_job(job_id, error).await
+    }
+
+    /// Blocks until a job is enqueued on any queue, for a worker to await
+    /// between `claim_job` attempts instead of busy-polling. See
+    /// [`crate::database::DatabaseManager::wait_for_job_queue`].
+    #[cfg(feature = "backend-postgres")]
+    pub async fn wait_for_job_queue(&self) {
+        self.database.wait_for_job_queue().await
+    }
 }
@@ -0,0 +1,384 @@
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::error::AnalysisError;
+
+/// How soon before its `expirationTimestamp` a cached pre-signed file URL is
+/// treated as stale and re-issued, so a query that takes a few seconds to
+/// plan and execute doesn't start reading from a URL that expires mid-scan.
+const PRESIGNED_URL_REFRESH_SKEW: chrono::Duration = chrono::Duration::seconds(60);
+
+/// Prefix marking a `DatasetFile`-less dataset's `source_path` as a
+/// [`DeltaShareSource`] rather than a plain external URL, so
+/// `DatasetManager::resolve_delta_share_files` (and `resync_dataset`, which
+/// doesn't support this source kind) can tell the two apart cheaply.
+const DELTA_SHARE_SOURCE_PREFIX: &str = "deltasharing:";
+
+/// A Delta Sharing profile: the sharing server's endpoint plus a short-lived
+/// bearer token, in the standard `shareCredentialsVersion: 1` profile format
+/// distributed by share providers (usually as a downloaded `.share` file).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeltaSharingProfile {
+    #[serde(rename = "shareCredentialsVersion", default = "default_profile_version")]
+    pub share_credentials_version: u32,
+    pub endpoint: String,
+    #[serde(rename = "bearerToken")]
+    pub bearer_token: String,
+}
+
+fn default_profile_version() -> u32 {
+    1
+}
+
+impl DeltaSharingProfile {
+    pub fn from_json(contents: &str) -> Result<Self, AnalysisError> {
+        serde_json::from_str(contents).map_err(|e| AnalysisError::ConfigError {
+            message: format!("Invalid Delta Sharing profile: {}", e),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SharedShare {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SharedSchema {
+    #[serde(default)]
+    pub share: String,
+    pub name: String,
+}
+
+/// One table enumerated by `list_tables`, identified by its full
+/// `share.schema.table` coordinate.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SharedTable {
+    #[serde(default)]
+    pub share: String,
+    #[serde(default)]
+    pub schema: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListResponse<T> {
+    items: Vec<T>,
+    #[serde(rename = "nextPageToken")]
+    next_page_token: Option<String>,
+}
+
+/// One Parquet fragment of a shared table, as returned by a `.../query`
+/// call. `expiration_timestamp` is milliseconds since the epoch, matching
+/// the Delta Sharing protocol's `file.expirationTimestamp`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SharedTableFile {
+    pub id: String,
+    pub url: String,
+    pub size: i64,
+    #[serde(rename = "partitionValues", default)]
+    pub partition_values: HashMap<String, String>,
+    #[serde(rename = "expirationTimestamp")]
+    pub expiration_timestamp: Option<i64>,
+}
+
+impl SharedTableFile {
+    /// Whether this file's pre-signed URL is already expired, or close
+    /// enough to expiring that the cache holding it should re-issue the
+    /// `/query` call rather than hand it out again.
+    fn expires_soon(&self) -> bool {
+        match self.expiration_timestamp {
+            Some(millis) => match DateTime::<Utc>::from_timestamp_millis(millis) {
+                Some(expires_at) => expires_at <= Utc::now() + PRESIGNED_URL_REFRESH_SKEW,
+                None => false,
+            },
+            None => false,
+        }
+    }
+}
+
+/// One line of a `.../query` NDJSON response body. Every line carries
+/// exactly one of `protocol`, `metaData` or `file`; only `file` lines are of
+/// interest here, so the others are left to be ignored by serde's default
+/// unknown-field handling.
+#[derive(Debug, Deserialize)]
+struct QueryResponseLine {
+    #[serde(default)]
+    file: Option<SharedTableFile>,
+}
+
+/// Talks the Delta Sharing REST protocol (shares/schemas/tables enumeration
+/// plus the per-table `/query` endpoint that resolves pre-signed file URLs)
+/// against a single profile's endpoint.
+pub struct DeltaShareClient {
+    http: Client,
+    profile: DeltaSharingProfile,
+}
+
+impl DeltaShareClient {
+    pub fn new(profile: DeltaSharingProfile) -> Self {
+        Self {
+            http: Client::new(),
+            profile,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/{}",
+            self.profile.endpoint.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    }
+
+    async fn get_list<T>(&self, path: &str) -> Result<Vec<T>, AnalysisError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let mut items = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let mut request = self
+                .http
+                .get(self.url(path))
+                .bearer_auth(&self.profile.bearer_token);
+            if let Some(token) = &page_token {
+                request = request.query(&[("pageToken", token.as_str())]);
+            }
+
+            let response = request.send().await?;
+            if !response.status().is_success() {
+                return Err(AnalysisError::ConfigError {
+                    message: format!(
+                        "Delta Sharing server returned {} for {}",
+                        response.status(),
+                        path
+                    ),
+                });
+            }
+
+            let page: ListResponse<T> = response.json().await?;
+            items.extend(page.items);
+            page_token = page.next_page_token;
+            if page_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    pub async fn list_shares(&self) -> Result<Vec<SharedShare>, AnalysisError> {
+        self.get_list("shares").await
+    }
+
+    pub async fn list_schemas(&self, share: &str) -> Result<Vec<SharedSchema>, AnalysisError> {
+        self.get_list(&format!("shares/{}/schemas", share)).await
+    }
+
+    pub async fn list_tables(
+        &self,
+        share: &str,
+        schema: &str,
+    ) -> Result<Vec<SharedTable>, AnalysisError> {
+        self.get_list(&format!("shares/{}/schemas/{}/tables", share, schema))
+            .await
+    }
+
+    /// Enumerates every table visible to this profile across all of its
+    /// shares and schemas, for callers (e.g. the `list_shared_tables` MCP
+    /// tool) that want a flat browsable list rather than drilling down
+    /// through `list_shares`/`list_schemas`/`list_tables` themselves.
+    pub async fn list_all_tables(&self) -> Result<Vec<SharedTable>, AnalysisError> {
+        let mut tables = Vec::new();
+        for share in self.list_shares().await? {
+            for schema in self.list_schemas(&share.name).await? {
+                let mut schema_tables = self.list_tables(&share.name, &schema.name).await?;
+                for table in &mut schema_tables {
+                    table.share = share.name.clone();
+                    table.schema = schema.name.clone();
+                }
+                tables.extend(schema_tables);
+            }
+        }
+        Ok(tables)
+    }
+
+    /// Resolves `share.schema.table`'s current set of pre-signed Parquet
+    /// file URLs, asking for the Parquet response format through the
+    /// `delta-sharing-capabilities` request header (the server's own
+    /// capabilities header is only logged) and passing `partition_filters` through as
+    /// predicate hints so the sharing server can prune files server-side
+    /// where it supports it. `predicateHints` is advisory, not a guarantee,
+    /// so the result is still filtered locally against each file's
+    /// `partition_values` afterwards.
+    pub async fn query_table_files(
+        &self,
+        share: &str,
+        schema: &str,
+        table: &str,
+        partition_filters: &HashMap<String, String>,
+    ) -> Result<Vec<SharedTableFile>, AnalysisError> {
+        let path = format!("shares/{}/schemas/{}/tables/{}/query", share, schema, table);
+        let predicate_hints: Vec<String> = partition_filters
+            .iter()
+            .map(|(column, value)| format!("{} = '{}'", column, value))
+            .collect();
+
+        let response = self
+            .http
+            .post(self.url(&path))
+            .bearer_auth(&self.profile.bearer_token)
+            .header("delta-sharing-capabilities", "responseformat=parquet")
+            .json(&serde_json::json!({ "predicateHints": predicate_hints }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(AnalysisError::ConfigError {
+                message: format!(
+                    "Delta Sharing server returned {} querying {}.{}.{}",
+                    response.status(),
+                    share,
+                    schema,
+                    table
+                ),
+            });
+        }
+
+        if let Some(capabilities) = response.headers().get("delta-sharing-capabilities") {
+            info!(
+                "Delta Sharing server capabilities for {}.{}.{}: {:?}",
+                share, schema, table, capabilities
+            );
+        }
+
+        let body = response.text().await?;
+
+        let mut files = Vec::new();
+        for line in body.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parsed: QueryResponseLine =
+                serde_json::from_str(line).map_err(|e| AnalysisError::ConfigError {
+                    message: format!("Invalid Delta Sharing response line: {}", e),
+                })?;
+            if let Some(file) = parsed.file {
+                files.push(file);
+            }
+        }
+
+        if !partition_filters.is_empty() {
+            files.retain(|file| {
+                partition_filters.iter().all(|(column, value)| {
+                    file.partition_values
+                        .get(column)
+                        .map(|v| v == value)
+                        .unwrap_or(false)
+                })
+            });
+        }
+
+        Ok(files)
+    }
+}
+
+/// Caches a shared table's pre-signed file list, keyed by `share.schema.table`
+/// (plus its partition filters), so a burst of queries against the same
+/// table doesn't re-issue the `/query` REST call every time - only once the
+/// soonest-expiring file in the cached set is close enough to its
+/// `expirationTimestamp` to need reissuing.
+#[derive(Default)]
+pub struct PresignedUrlCache {
+    entries: RwLock<HashMap<String, Vec<SharedTableFile>>>,
+}
+
+impl PresignedUrlCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn cache_key(share: &str, schema: &str, table: &str, partition_filters: &HashMap<String, String>) -> String {
+        let mut filters: Vec<_> = partition_filters.iter().collect();
+        filters.sort();
+        let filters = filters
+            .into_iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!("{}.{}.{}[{}]", share, schema, table, filters)
+    }
+
+    pub async fn get_or_refresh(
+        &self,
+        client: &DeltaShareClient,
+        share: &str,
+        schema: &str,
+        table: &str,
+        partition_filters: &HashMap<String, String>,
+    ) -> Result<Vec<SharedTableFile>, AnalysisError> {
+        let key = Self::cache_key(share, schema, table, partition_filters);
+
+        if let Some(files) = self.entries.read().await.get(&key) {
+            if !files.is_empty() && !files.iter().any(SharedTableFile::expires_soon) {
+                return Ok(files.clone());
+            }
+        }
+
+        let files = client
+            .query_table_files(share, schema, table, partition_filters)
+            .await?;
+        self.entries.write().await.insert(key, files.clone());
+        Ok(files)
+    }
+}
+
+/// A dataset's Delta Sharing origin, encoded into `CatalogDatasetEntry::source_path`
+/// behind the [`DELTA_SHARE_SOURCE_PREFIX`] marker rather than a new database
+/// column, the same way every other dataset source reuses `source_path` for
+/// its external location. Unlike a plain URL, this also carries the
+/// credentials needed to re-resolve the table's (expiring) file URLs on
+/// every registration, since - unlike a managed copy - there's no durable
+/// local copy of the data to fall back on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeltaShareSource {
+    pub endpoint: String,
+    pub bearer_token: String,
+    pub share: String,
+    pub schema: String,
+    pub table: String,
+    #[serde(default)]
+    pub partition_filters: HashMap<String, String>,
+}
+
+impl DeltaShareSource {
+    pub fn encode(&self) -> Result<String, AnalysisError> {
+        let body = serde_json::to_string(self)?;
+        Ok(format!("{}{}", DELTA_SHARE_SOURCE_PREFIX, body))
+    }
+
+    /// Returns `None` if `source_path` isn't a Delta Sharing source at all
+    /// (the common case), `Some(Err(_))` if it is one but failed to decode.
+    pub fn decode(source_path: &str) -> Option<Result<Self, AnalysisError>> {
+        let body = source_path.strip_prefix(DELTA_SHARE_SOURCE_PREFIX)?;
+        Some(
+            serde_json::from_str(body).map_err(|e| AnalysisError::ConfigError {
+                message: format!("Corrupt Delta Sharing source record: {}", e),
+            }),
+        )
+    }
+
+    pub fn profile(&self) -> DeltaSharingProfile {
+        DeltaSharingProfile {
+            share_credentials_version: 1,
+            endpoint: self.endpoint.clone(),
+            bearer_token: self.bearer_token.clone(),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+
+/// Embedding dimensionality used by [`HashingEmbeddingBackend`]. Fixed so
+/// every vector produced by the default backend is directly comparable.
+const EMBEDDING_DIM: usize = 256;
+
+/// Produces embedding vectors for a batch of text values. The only backend
+/// shipped here ([`HashingEmbeddingBackend`]) is a deterministic, offline
+/// bag-of-words hash so `semantic_search` works with no external model
+/// dependency; a real deployment can plug in a network-backed backend
+/// without touching [`EmbeddingIndex`].
+pub trait EmbeddingBackend {
+    fn embed_batch(&self, texts: &[&str]) -> Vec<Vec<f32>>;
+}
+
+/// Hashes each whitespace-separated token into one of [`EMBEDDING_DIM`]
+/// buckets (sign chosen by a second hash, so semantically unrelated tokens
+/// partially cancel rather than only ever adding), giving a cheap
+/// locality-insensitive text vector with no training step or network call.
+#[derive(Debug, Default)]
+pub struct HashingEmbeddingBackend;
+
+impl EmbeddingBackend for HashingEmbeddingBackend {
+    fn embed_batch(&self, texts: &[&str]) -> Vec<Vec<f32>> {
+        texts.iter().map(|text| Self::embed_one(text)).collect()
+    }
+}
+
+impl HashingEmbeddingBackend {
+    fn embed_one(text: &str) -> Vec<f32> {
+        let mut vector = vec![0f32; EMBEDDING_DIM];
+        for token in text.split_whitespace() {
+            let bucket = (fnv1a(token) as usize) % EMBEDDING_DIM;
+            let sign = if fnv1a(&format!("{}#sign", token)) % 2 == 0 {
+                1.0
+            } else {
+                -1.0
+            };
+            vector[bucket] += sign;
+        }
+        vector
+    }
+}
+
+fn fnv1a(value: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    value
+        .bytes()
+        .fold(OFFSET_BASIS, |hash, byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
+
+fn norm(vector: &[f32]) -> f32 {
+    vector.iter().map(|v| v * v).sum::<f32>().sqrt()
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// One dataset row's precomputed embedding, keyed by its 0-based scan
+/// position (see `DataFusionEngine::fetch_text_column`). `norm` is
+/// precomputed so scoring a candidate is one dot product plus a divide.
+#[derive(Debug, Clone)]
+pub struct RowEmbedding {
+    pub row_id: i64,
+    pub vector: Vec<f32>,
+    pub norm: f32,
+}
+
+/// Above this many rows, [`EmbeddingIndex::search`] probes a random-projection
+/// LSH index instead of scanning every row. Below it, an exact scan is both
+/// simpler and fast enough that building an index wouldn't pay for itself.
+const ANN_ROW_THRESHOLD: usize = 5_000;
+
+const LSH_HYPERPLANES: usize = 16;
+
+/// Buckets rows by the sign pattern of `LSH_HYPERPLANES` random projections,
+/// so a query only needs to be scored exactly against rows that hash near it
+/// instead of the whole dataset.
+struct LshIndex {
+    hyperplanes: Vec<Vec<f32>>,
+    buckets: HashMap<u64, Vec<usize>>,
+}
+
+impl LshIndex {
+    fn build(rows: &[RowEmbedding], dim: usize, seed: u64) -> Self {
+        let mut hyperplanes = Vec::with_capacity(LSH_HYPERPLANES);
+        let mut state = seed ^ 0x9e3779b97f4a7c15;
+        for _ in 0..LSH_HYPERPLANES {
+            let mut plane = Vec::with_capacity(dim);
+            for _ in 0..dim {
+                // xorshift64, deterministic and dependency-free.
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let unit = (state >> 40) as f32 / (1u64 << 24) as f32;
+                plane.push(unit * 2.0 - 1.0);
+            }
+            hyperplanes.push(plane);
+        }
+
+        let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (idx, row) in rows.iter().enumerate() {
+            let signature = Self::signature(&hyperplanes, &row.vector);
+            buckets.entry(signature).or_default().push(idx);
+        }
+
+        Self { hyperplanes, buckets }
+    }
+
+    fn signature(hyperplanes: &[Vec<f32>], vector: &[f32]) -> u64 {
+        let mut signature = 0u64;
+        for (bit, plane) in hyperplanes.iter().enumerate() {
+            if dot(plane, vector) >= 0.0 {
+                signature |= 1 << bit;
+            }
+        }
+        signature
+    }
+
+    /// Candidate row indices for `vector`: its own bucket plus every bucket
+    /// one bit-flip away, so a query that falls near a hyperplane boundary
+    /// still finds its nearest neighbors.
+    fn candidates(&self, vector: &[f32]) -> Vec<usize> {
+        let signature = Self::signature(&self.hyperplanes, vector);
+        let mut candidates = Vec::new();
+        if let Some(bucket) = self.buckets.get(&signature) {
+            candidates.extend(bucket.iter().copied());
+        }
+        for bit in 0..self.hyperplanes.len() {
+            let neighbor = signature ^ (1 << bit);
+            if let Some(bucket) = self.buckets.get(&neighbor) {
+                candidates.extend(bucket.iter().copied());
+            }
+        }
+        candidates
+    }
+}
+
+/// A scored match, ordered by `score` so a `BinaryHeap<Candidate>` behaves
+/// as a min-heap of the current top-K (`Reverse`-free: we just compare on
+/// `score` and pop the smallest when the heap overflows K).
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    index: usize,
+    score: f32,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score
+    }
+}
+impl Eq for Candidate {}
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so `BinaryHeap` (a max-heap) pops the *smallest* score
+        // first, which is what we want to evict when the heap exceeds K.
+        other
+            .score
+            .partial_cmp(&self.score)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Precomputed per-row embeddings for one `(dataset_id, text_column)` pair,
+/// searchable by cosine similarity. Rows whose text was empty/NULL embed to
+/// a zero vector and are excluded from both construction and search, per
+/// `semantic_search`'s edge-case handling.
+pub struct EmbeddingIndex {
+    rows: Vec<RowEmbedding>,
+    dim: usize,
+    lsh: Option<LshIndex>,
+}
+
+impl EmbeddingIndex {
+    /// Builds an index over `rows`, each a `(row_id, vector)` pair produced
+    /// by the source dataset's configured `EmbeddingBackend`. Above
+    /// `ANN_ROW_THRESHOLD` rows, also builds a random-projection LSH index so
+    /// `search` doesn't have to score every row.
+    pub fn build(rows: Vec<(i64, Vec<f32>)>) -> Self {
+        let dim = rows.first().map(|(_, v)| v.len()).unwrap_or(0);
+        let rows: Vec<RowEmbedding> = rows
+            .into_iter()
+            .filter_map(|(row_id, vector)| {
+                let n = norm(&vector);
+                if n == 0.0 {
+                    None
+                } else {
+                    Some(RowEmbedding { row_id, vector, norm: n })
+                }
+            })
+            .collect();
+
+        let lsh = if rows.len() > ANN_ROW_THRESHOLD && dim > 0 {
+            Some(LshIndex::build(&rows, dim, rows.len() as u64))
+        } else {
+            None
+        };
+
+        Self { rows, dim, lsh }
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Returns up to `k` `(row_id, cosine_similarity)` pairs nearest to
+    /// `query_vector`, sorted by descending similarity. `k` is clamped to
+    /// the number of candidate rows, and a zero-norm query matches nothing.
+    pub fn search(&self, query_vector: &[f32], k: usize) -> Vec<(i64, f32)> {
+        let query_norm = norm(query_vector);
+        if query_norm == 0.0 || self.rows.is_empty() || k == 0 {
+            return Vec::new();
+        }
+
+        let candidate_indices: Vec<usize> = match &self.lsh {
+            Some(lsh) => {
+                let mut indices = lsh.candidates(query_vector);
+                indices.sort_unstable();
+                indices.dedup();
+                indices
+            }
+            None => (0..self.rows.len()).collect(),
+        };
+
+        let k = k.min(candidate_indices.len());
+        let mut heap: BinaryHeap<Candidate> = BinaryHeap::with_capacity(k + 1);
+
+        for index in candidate_indices {
+            let row = &self.rows[index];
+            let score = dot(query_vector, &row.vector) / (query_norm * row.norm);
+            heap.push(Candidate { index, score });
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        // `Candidate`'s `Ord` is inverted (lower score orders higher) so
+        // `BinaryHeap::pop` evicts the worst match when over capacity; that
+        // same inversion means `into_sorted_vec`'s ascending order is
+        // already best-score-first.
+        let results: Vec<Candidate> = heap.into_sorted_vec();
+        results
+            .into_iter()
+            .map(|c| (self.rows[c.index].row_id, c.score))
+            .collect()
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+}
@@ -1,35 +1,100 @@
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use tracing::info;
 
 use crate::catalog::DataFormat;
+use crate::catalog_cache::CatalogCacheConfig;
+use crate::cluster::{ClusterConfig, ClusterCoordinator};
+use crate::database::DatabaseConfig;
 use crate::datafusion_engine::DataFusionEngine;
-use crate::dataset_manager::DatasetManager;
+use crate::dataset_manager::{DatasetManager, ResyncSummary};
+use crate::delta_sharing_client::{DeltaShareClient, DeltaSharingProfile, SharedTable};
 use crate::domain::QueryStreamResult;
+use crate::embeddings::{EmbeddingBackend, EmbeddingIndex, HashingEmbeddingBackend};
 use crate::error::AnalysisError;
+use crate::metrics::Metrics;
+
+/// One [`EmbeddingIndex`] per `(dataset_id, text_column)` pair that has had
+/// `semantic_search` run against it, lazily built on first use. Keyed by
+/// owned strings rather than the dataset/column themselves since entries
+/// must survive independently of any particular query.
+type EmbeddingCacheKey = (String, String);
 
 pub struct AnalysisEngine {
     datafusion: DataFusionEngine,
     dataset_manager: DatasetManager,
+    metrics: Arc<Metrics>,
+    cluster: Arc<ClusterCoordinator>,
+    embedding_backend: Box<dyn EmbeddingBackend + Send + Sync>,
+    embedding_cache: RwLock<std::collections::HashMap<EmbeddingCacheKey, Arc<EmbeddingIndex>>>,
 }
 
 impl AnalysisEngine {
-    pub async fn new(bucket_name: String, database_url: String) -> Result<Self, AnalysisError> {
+    pub async fn new(
+        bucket_name: String,
+        database_url: String,
+        database_config: &DatabaseConfig,
+        cache_config: &CatalogCacheConfig,
+        metrics: Arc<Metrics>,
+        cluster_config: ClusterConfig,
+    ) -> Result<Self, AnalysisError> {
         info!("Initializing Analysis Engine");
 
-        let datafusion = DataFusionEngine::new(bucket_name.clone()).await?;
-        let dataset_manager = DatasetManager::new(bucket_name, database_url).await?;
+        let datafusion = DataFusionEngine::new(bucket_name.clone(), metrics.clone()).await?;
+        let dataset_manager =
+            DatasetManager::new(bucket_name, database_url, database_config, cache_config).await?;
+        let cluster = Arc::new(ClusterCoordinator::new(cluster_config));
 
         info!("Analysis Engine initialized successfully");
 
         Ok(Self {
             datafusion,
             dataset_manager,
+            metrics,
+            cluster,
+            embedding_backend: Box::new(HashingEmbeddingBackend),
+            embedding_cache: RwLock::new(std::collections::HashMap::new()),
         })
     }
 
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.metrics.clone()
+    }
+
+    pub fn cluster_namespace(&self) -> &str {
+        self.cluster.namespace()
+    }
+
+    /// Forwards a query to a specific cluster peer and reassembles its
+    /// streamed response, for callers that want to route a sub-query to
+    /// another node rather than executing it locally.
+    pub async fn execute_remote_query(
+        &self,
+        peer: &str,
+        dataset_id: &str,
+        sql_query: &str,
+        limit: Option<i32>,
+    ) -> Result<QueryStreamResult, AnalysisError> {
+        self.cluster
+            .execute_remote_query(peer, dataset_id, sql_query, limit)
+            .await
+    }
+
     async fn register_dataset_with_datafusion(
         &self,
         dataset_id: &str,
     ) -> Result<(), AnalysisError> {
+        if let Some(files) = self
+            .dataset_manager
+            .resolve_delta_share_files(dataset_id)
+            .await?
+        {
+            return self
+                .datafusion
+                .register_delta_share_table(dataset_id, &files)
+                .await;
+        }
+
         let dataset = self
             .dataset_manager
             .get_dataset(dataset_id)
@@ -52,7 +117,35 @@ impl AnalysisEngine {
         }
 
         self.datafusion
-            .execute_query(dataset_id, dataset_id, sql_query, limit)
+            .execute_query(dataset_id, sql_query, limit)
+            .await
+    }
+
+    /// Registers every `(alias, dataset_id)` pair with DataFusion under its
+    /// alias (auto-registering any dataset_id not already registered, same
+    /// as `execute_query`), then runs `sql_query` once against all of them
+    /// in a single combined session - e.g. for a JOIN across datasets,
+    /// rather than being confined to `execute_query`'s single implicit
+    /// `base` table.
+    pub async fn execute_query_multi(
+        &self,
+        datasets: &[(String, String)],
+        sql_query: &str,
+        limit: Option<i32>,
+    ) -> Result<QueryStreamResult, AnalysisError> {
+        let scope = self.datafusion.new_query_scope();
+
+        for (alias, dataset_id) in datasets {
+            if !self.datafusion.is_dataset_registered(dataset_id).await {
+                self.register_dataset_with_datafusion(dataset_id).await?;
+            }
+            self.datafusion
+                .register_table_alias(&scope, alias, dataset_id)
+                .await?;
+        }
+
+        self.datafusion
+            .execute_registered_query(&scope, sql_query, limit)
             .await
     }
 
@@ -70,8 +163,26 @@ impl AnalysisEngine {
             self.register_dataset_with_datafusion(dataset_id).await?;
         }
 
+        // DataFusion's live schema is authoritative for name/type/nullable
+        // (it reflects the files actually on disk), but it doesn't compute
+        // per-column statistics, so carry those over from whatever
+        // extraction populated at import time rather than losing them.
+        let mut persisted_statistics: std::collections::HashMap<_, _> = metadata
+            .columns
+            .into_iter()
+            .map(|col| (col.name.clone(), col.statistics))
+            .collect();
+
         let domain_columns = self.datafusion.get_table_schema(dataset_id).await?;
-        metadata.columns = domain_columns.into_iter().map(|col| col.into()).collect();
+        metadata.columns = domain_columns
+            .into_iter()
+            .map(|col| {
+                let statistics = persisted_statistics.remove(&col.name).unwrap_or_default();
+                let mut proto_col: crate::proto::analysis::ColumnInfo = col.into();
+                proto_col.statistics = statistics;
+                proto_col
+            })
+            .collect();
 
         Ok(metadata)
     }
@@ -84,10 +195,9 @@ impl AnalysisEngine {
         tags: Option<Vec<String>>,
         format: Option<String>,
     ) -> Result<String, AnalysisError> {
-        let format_enum = format.map(|f| match f.to_lowercase().as_str() {
-            "parquet" => DataFormat::Parquet,
-            _ => DataFormat::Csv,
-        });
+        let format_enum = format
+            .map(|f| DataFormat::try_from(f.as_str()))
+            .transpose()?;
 
         let dataset_id = self
             .dataset_manager
@@ -96,10 +206,402 @@ impl AnalysisEngine {
 
         self.register_dataset_with_datafusion(&dataset_id).await?;
 
+        if !self.cluster.peers().is_empty() {
+            let cluster = self.cluster.clone();
+            let propagated_id = dataset_id.clone();
+            tokio::spawn(async move {
+                cluster.notify_dataset_added(&propagated_id).await;
+            });
+        }
+
         Ok(dataset_id)
     }
 
-    pub async fn health_check(&self) -> Result<(), AnalysisError> {
-        self.datafusion.health_check().await
+    /// Registers a Delta Sharing table (`share.schema.table` served by
+    /// `profile`) as a dataset, without copying its data: only the Delta
+    /// Sharing coordinate is persisted, and its pre-signed Parquet file URLs
+    /// are resolved fresh on every DataFusion registration. See
+    /// `DatasetManager::add_dataset_from_delta_share` for the details.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_dataset_from_delta_share(
+        &self,
+        name: String,
+        profile: DeltaSharingProfile,
+        share: String,
+        schema: String,
+        table: String,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
+        partition_filters: std::collections::HashMap<String, String>,
+    ) -> Result<String, AnalysisError> {
+        let dataset_id = self
+            .dataset_manager
+            .add_dataset_from_delta_share(
+                name,
+                profile,
+                share,
+                schema,
+                table,
+                description,
+                tags,
+                partition_filters,
+            )
+            .await?;
+
+        self.register_dataset_with_datafusion(&dataset_id).await?;
+
+        if !self.cluster.peers().is_empty() {
+            let cluster = self.cluster.clone();
+            let propagated_id = dataset_id.clone();
+            tokio::spawn(async move {
+                cluster.notify_dataset_added(&propagated_id).await;
+            });
+        }
+
+        Ok(dataset_id)
+    }
+
+    /// Enumerates every share/schema/table a Delta Sharing profile can see,
+    /// for browsing what's available to import before calling
+    /// `add_dataset_from_delta_share`.
+    pub async fn list_shared_tables(
+        &self,
+        profile: DeltaSharingProfile,
+    ) -> Result<Vec<SharedTable>, AnalysisError> {
+        DeltaShareClient::new(profile).list_all_tables().await
+    }
+
+    /// Runs the local health check and, when running in cluster mode,
+    /// reports whether any configured peer failed its own liveness check.
+    pub async fn health_check(&self) -> Result<String, AnalysisError> {
+        self.datafusion.health_check().await?;
+
+        if self.cluster.peers().is_empty() {
+            return Ok("healthy".to_string());
+        }
+
+        let unhealthy_peers = self.cluster.unhealthy_peers().await;
+        if unhealthy_peers.is_empty() {
+            Ok("healthy".to_string())
+        } else {
+            Ok(format!(
+                "degraded: unreachable peers [{}]",
+                unhealthy_peers.join(", ")
+            ))
+        }
+    }
+
+    pub async fn generate_download_url(
+        &self,
+        dataset_id: &str,
+        filename: Option<&str>,
+        expires_in: std::time::Duration,
+    ) -> Result<String, AnalysisError> {
+        self.dataset_manager
+            .generate_download_url(dataset_id, filename, expires_in)
+            .await
+    }
+
+    /// Reports a dataset's current usage against its quota, if any,
+    /// registering it with DataFusion first if it isn't yet (an unknown id
+    /// is `DatasetNotFound`). Returns `(size_bytes, row_count, max_size_bytes,
+    /// max_row_count)`.
+    /// `size_bytes`/`row_count` are `None` when DataFusion hasn't collected
+    /// statistics for the dataset, rather than falsely reported as zero.
+    pub async fn dataset_usage(
+        &self,
+        dataset_id: &str,
+    ) -> Result<Option<(Option<i64>, Option<i64>, Option<i64>, Option<i32>)>, AnalysisError> {
+        if !self.datafusion.is_dataset_registered(dataset_id).await {
+            self.register_dataset_with_datafusion(dataset_id).await?;
+        }
+
+        Ok(self.datafusion.dataset_usage(dataset_id).await)
+    }
+
+    /// Recomputes a dataset's usage counter from scratch. Use as a repair path
+    /// if the incrementally tracked usage is ever suspected to have drifted.
+    pub async fn recompute_dataset_usage(&self, dataset_id: &str) -> Result<(), AnalysisError> {
+        let dataset = self
+            .dataset_manager
+            .get_dataset(dataset_id)
+            .await
+            .ok_or_else(|| AnalysisError::DatasetNotFound {
+                dataset_id: dataset_id.to_string(),
+            })?;
+
+        self.datafusion.recompute_dataset_usage(&dataset).await
+    }
+
+    /// Deletes a dataset from the catalog and tears down its cached
+    /// DataFusion table registration, if any. Returns `DatasetNotFound` if
+    /// the id doesn't exist.
+    pub async fn delete_dataset(&self, dataset_id: &str) -> Result<(), AnalysisError> {
+        let deleted = self.dataset_manager.delete_dataset(dataset_id).await?;
+        if !deleted {
+            return Err(AnalysisError::DatasetNotFound {
+                dataset_id: dataset_id.to_string(),
+            });
+        }
+
+        if self.datafusion.is_dataset_registered(dataset_id).await {
+            self.datafusion.deregister_dataset(dataset_id).await?;
+        }
+        self.invalidate_embeddings(dataset_id).await;
+
+        Ok(())
+    }
+
+    /// Updates a dataset's description and/or tags, leaving any field left
+    /// as `None` unchanged, and returns its refreshed metadata. Returns
+    /// `DatasetNotFound` if the id doesn't exist.
+    pub async fn update_dataset_metadata(
+        &self,
+        dataset_id: &str,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> Result<crate::proto::analysis::DatasetMetadata, AnalysisError> {
+        let updated = self
+            .dataset_manager
+            .update_dataset_metadata(dataset_id, description, tags)
+            .await?;
+        if !updated {
+            return Err(AnalysisError::DatasetNotFound {
+                dataset_id: dataset_id.to_string(),
+            });
+        }
+
+        self.get_metadata(dataset_id).await
+    }
+
+    /// Re-registers a dataset with DataFusion, picking up any files added to
+    /// its storage path or schema changes since it was last registered.
+    /// Directory-backed datasets are listed fresh on every registration, so
+    /// this is just a forced deregister-then-register rather than a separate
+    /// scan step. Returns `DatasetNotFound` if the id doesn't exist.
+    pub async fn refresh_dataset(&self, dataset_id: &str) -> Result<(), AnalysisError> {
+        if self.dataset_manager.get_dataset(dataset_id).await.is_none() {
+            return Err(AnalysisError::DatasetNotFound {
+                dataset_id: dataset_id.to_string(),
+            });
+        }
+
+        if self.datafusion.is_dataset_registered(dataset_id).await {
+            self.datafusion.deregister_dataset(dataset_id).await?;
+        }
+
+        self.register_dataset_with_datafusion(dataset_id).await?;
+        self.recompute_dataset_usage(dataset_id).await?;
+        self.invalidate_embeddings(dataset_id).await;
+
+        Ok(())
+    }
+
+    /// Incrementally re-syncs a directory-backed dataset against its
+    /// original `source_path`, copying only new or changed files and
+    /// dropping rows for files that disappeared upstream, then
+    /// re-registering it with DataFusion so the change is visible to the
+    /// next query. Returns `DatasetNotFound` if the id doesn't exist, and
+    /// `ConfigError` if it has no recorded `source_path` to resync from.
+    pub async fn resync_dataset(&self, dataset_id: &str) -> Result<ResyncSummary, AnalysisError> {
+        let summary = self.dataset_manager.resync_dataset(dataset_id).await?;
+
+        if self.datafusion.is_dataset_registered(dataset_id).await {
+            self.datafusion.deregister_dataset(dataset_id).await?;
+        }
+        self.register_dataset_with_datafusion(dataset_id).await?;
+        self.recompute_dataset_usage(dataset_id).await?;
+        self.invalidate_embeddings(dataset_id).await;
+
+        Ok(summary)
+    }
+
+    /// Finds the `k` rows of `dataset_id` whose `text_column` value is most
+    /// similar (by cosine similarity) to `query`, computing and caching an
+    /// embedding index for `(dataset_id, text_column)` on first use rather
+    /// than re-embedding the whole column on every call. The cache is
+    /// invalidated by `delete_dataset`/`refresh_dataset`/`resync_dataset`,
+    /// since each of those can change which rows - and so which text values
+    /// - exist.
+    pub async fn semantic_search(
+        &self,
+        dataset_id: &str,
+        text_column: &str,
+        query: &str,
+        k: usize,
+    ) -> Result<Vec<(i64, f32)>, AnalysisError> {
+        let index = self
+            .get_or_build_embedding_index(dataset_id, text_column)
+            .await?;
+        let query_vector = self
+            .embedding_backend
+            .embed_batch(&[query])
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+
+        Ok(index.search(&query_vector, k))
+    }
+
+    async fn get_or_build_embedding_index(
+        &self,
+        dataset_id: &str,
+        text_column: &str,
+    ) -> Result<Arc<EmbeddingIndex>, AnalysisError> {
+        let key = (dataset_id.to_string(), text_column.to_string());
+
+        if let Some(index) = self.embedding_cache.read().await.get(&key) {
+            return Ok(index.clone());
+        }
+
+        if !self.datafusion.is_dataset_registered(dataset_id).await {
+            self.register_dataset_with_datafusion(dataset_id).await?;
+        }
+
+        let values = self.datafusion.fetch_text_column(dataset_id, text_column).await?;
+        let texts: Vec<&str> = values.iter().map(|v| v.as_deref().unwrap_or("")).collect();
+        let vectors = self.embedding_backend.embed_batch(&texts);
+        let rows: Vec<(i64, Vec<f32>)> = vectors
+            .into_iter()
+            .enumerate()
+            .map(|(row_id, vector)| (row_id as i64, vector))
+            .collect();
+
+        let index = Arc::new(EmbeddingIndex::build(rows));
+        self.embedding_cache.write().await.insert(key, index.clone());
+
+        Ok(index)
+    }
+
+    async fn invalidate_embeddings(&self, dataset_id: &str) {
+        self.embedding_cache
+            .write()
+            .await
+            .retain(|(id, _), _| id != dataset_id);
+    }
+
+    /// Yields dataset ids as they're added or updated in the catalog, so
+    /// callers can react to changes live instead of re-polling
+    /// `list_datasets`.
+    pub fn watch_catalog(&self) -> impl futures::Stream<Item = String> {
+        self.dataset_manager.watch_catalog()
+    }
+
+    /// Enqueues `sql_query` against `dataset_id` as a job on
+    /// [`crate::job_queue::QUERY_JOB_QUEUE`] and returns its id immediately,
+    /// for a caller that would rather poll/subscribe for the result than
+    /// hold a request open for however long the query takes. A worker
+    /// spawned by [`Self::spawn_query_job_worker`] runs it; see
+    /// [`Self::job_status`] to check on it afterwards.
+    #[cfg(feature = "backend-postgres")]
+    pub async fn submit_query_job(
+        &self,
+        dataset_id: &str,
+        sql_query: &str,
+        limit: Option<i32>,
+    ) -> Result<uuid::Uuid, AnalysisError> {
+        let payload = serde_json::json!({
+            "dataset_id": dataset_id,
+            "sql_query": sql_query,
+            "limit": limit,
+        });
+
+        self.dataset_manager
+            .enqueue_job(crate::job_queue::QUERY_JOB_QUEUE, &payload)
+            .await
+    }
+
+    /// Reads a job's current status/result, for a caller polling after
+    /// `submit_query_job`. Returns `None` if `job_id` doesn't exist.
+    #[cfg(feature = "backend-postgres")]
+    pub async fn job_status(
+        &self,
+        job_id: uuid::Uuid,
+    ) -> Result<Option<crate::job_queue::Job>, AnalysisError> {
+        self.dataset_manager.get_job(job_id).await
+    }
+
+    /// Runs forever, claiming and executing jobs off
+    /// [`crate::job_queue::QUERY_JOB_QUEUE`] one at a time. Blocks on
+    /// `wait_for_job_queue` between empty polls rather than busy-looping,
+    /// with a bounded timeout as a backstop against a missed `NOTIFY`.
+    /// Intended to be spawned once per process (e.g. from `main`) on an
+    /// `Arc<AnalysisEngine>`.
+    #[cfg(feature = "backend-postgres")]
+    pub fn spawn_query_job_worker(self: Arc<Self>) {
+        tokio::spawn(async move {
+            loop {
+                match self.dataset_manager.claim_job(crate::job_queue::QUERY_JOB_QUEUE).await {
+                    Ok(Some(job)) => {
+                        self.run_query_job(job).await;
+                    }
+                    Ok(None) => {
+                        let _ = tokio::time::timeout(
+                            std::time::Duration::from_secs(5),
+                            self.dataset_manager.wait_for_job_queue(),
+                        )
+                        .await;
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to claim query job: {}", e);
+                        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    }
+                }
+            }
+        });
+    }
+
+    #[cfg(feature = "backend-postgres")]
+    async fn run_query_job(&self, job: crate::job_queue::Job) {
+        #[derive(serde::Deserialize)]
+        struct QueryJobPayload {
+            dataset_id: String,
+            sql_query: String,
+            limit: Option<i32>,
+        }
+
+        let payload: QueryJobPayload = match serde_json::from_value(job.job.clone()) {
+            Ok(payload) => payload,
+            Err(e) => {
+                let message = format!("Malformed query job payload: {}", e);
+                if let Err(e) = self.dataset_manager.fail_job(job.id, &message).await {
+                    tracing::error!("Failed to record job {} failure: {}", job.id, e);
+                }
+                return;
+            }
+        };
+
+        let outcome = self
+            .execute_query(&payload.dataset_id, &payload.sql_query, payload.limit)
+            .await;
+
+        let report = match outcome {
+            Ok(result) => {
+                let total_rows: i32 = result.chunks.iter().map(|c| c.chunk_rows).sum();
+                let result_json = serde_json::json!({
+                    "column_names": result
+                        .metadata
+                        .as_ref()
+                        .map(|m| m.column_names.clone())
+                        .unwrap_or_default(),
+                    "total_rows": total_rows,
+                    "chunks": result
+                        .chunks
+                        .iter()
+                        .map(|c| serde_json::json!({
+                            "chunk_index": c.chunk_index,
+                            "chunk_rows": c.chunk_rows,
+                            "arrow_ipc_data": c.arrow_ipc_data,
+                        }))
+                        .collect::<Vec<_>>(),
+                });
+                self.dataset_manager.complete_job(job.id, &result_json).await
+            }
+            Err(e) => self.dataset_manager.fail_job(job.id, &e.to_string()).await,
+        };
+
+        if let Err(e) = report {
+            tracing::error!("Failed to record outcome of job {}: {}", job.id, e);
+        }
     }
 }
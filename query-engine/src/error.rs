@@ -35,8 +35,42 @@ pub enum AnalysisError {
     #[error("Configuration error: {message}")]
     ConfigError { message: String },
 
+    #[error("Database pool error: {message}")]
+    PoolError { message: String },
+
     #[error("Internal server error: {message}")]
     InternalError { message: String },
+
+    #[error("Dataset '{dataset_id}' exceeds its storage quota: {message}")]
+    QuotaExceeded { dataset_id: String, message: String },
+
+    #[error("Query engine is overloaded: {message}")]
+    ResourceExhausted { message: String },
+}
+
+impl AnalysisError {
+    /// A short, stable label for this error's variant, suitable for use as a
+    /// metrics label value (unlike `Display`, it never includes the error's
+    /// message, so it stays low-cardinality).
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            AnalysisError::DatasetNotFound { .. } => "dataset_not_found",
+            AnalysisError::InvalidSqlQuery { .. } => "invalid_sql_query",
+            AnalysisError::QueryExecutionFailed { .. } => "query_execution_failed",
+            AnalysisError::IoError { .. } => "io_error",
+            AnalysisError::DataFusionError(_) => "datafusion_error",
+            AnalysisError::ArrowError(_) => "arrow_error",
+            AnalysisError::JsonError(_) => "json_error",
+            AnalysisError::HttpError(_) => "http_error",
+            AnalysisError::GrpcError(_) => "grpc_error",
+            AnalysisError::GrpcStatusError(_) => "grpc_status_error",
+            AnalysisError::ConfigError { .. } => "config_error",
+            AnalysisError::PoolError { .. } => "pool_error",
+            AnalysisError::InternalError { .. } => "internal_error",
+            AnalysisError::QuotaExceeded { .. } => "quota_exceeded",
+            AnalysisError::ResourceExhausted { .. } => "resource_exhausted",
+        }
+    }
 }
 
 impl From<std::io::Error> for AnalysisError {
@@ -62,9 +96,28 @@ impl From<AnalysisError> for tonic::Status {
             AnalysisError::InvalidSqlQuery { .. } => {
                 tonic::Status::invalid_argument(err.to_string())
             }
-            AnalysisError::QueryExecutionFailed { .. } => tonic::Status::internal(err.to_string()),
             AnalysisError::ConfigError { .. } => tonic::Status::invalid_argument(err.to_string()),
-            _ => tonic::Status::internal(err.to_string()),
+            AnalysisError::QuotaExceeded { .. } | AnalysisError::ResourceExhausted { .. } => {
+                tonic::Status::resource_exhausted(err.to_string())
+            }
+            // The pool itself is overloaded or can't reach the database, as
+            // opposed to a genuine misconfiguration, so clients can tell
+            // "retry me" apart from "fix your request"/"fix your config".
+            AnalysisError::PoolError { .. } => tonic::Status::unavailable(err.to_string()),
+            // Preserve the original code/message when re-propagating a status
+            // we already received from another gRPC peer, rather than
+            // flattening it to INTERNAL.
+            AnalysisError::GrpcStatusError(status) => {
+                tonic::Status::new(status.code(), status.message().to_string())
+            }
+            AnalysisError::QueryExecutionFailed { .. }
+            | AnalysisError::IoError { .. }
+            | AnalysisError::DataFusionError(_)
+            | AnalysisError::ArrowError(_)
+            | AnalysisError::JsonError(_)
+            | AnalysisError::HttpError(_)
+            | AnalysisError::GrpcError(_)
+            | AnalysisError::InternalError { .. } => tonic::Status::internal(err.to_string()),
         }
     }
 }
@@ -1,10 +1,12 @@
-use object_store::{gcp::GoogleCloudStorageBuilder, ObjectStore};
+use object_store::gcp::{GoogleCloudStorage, GoogleCloudStorageBuilder};
 use std::sync::Arc;
 use crate::error::AnalysisError;
+use crate::resilient_store::retry_config_from_env;
 
-pub fn create_gcs_client(bucket_name: &str) -> Result<Arc<dyn ObjectStore>, AnalysisError> {
+pub fn create_gcs_client(bucket_name: &str) -> Result<Arc<GoogleCloudStorage>, AnalysisError> {
     let mut builder = GoogleCloudStorageBuilder::new()
-        .with_bucket_name(bucket_name);
+        .with_bucket_name(bucket_name)
+        .with_retry(retry_config_from_env());
 
     if let Ok(service_account_path) = std::env::var("GOOGLE_APPLICATION_CREDENTIALS") {
         builder = builder.with_service_account_path(service_account_path);
@@ -16,4 +18,4 @@ pub fn create_gcs_client(bucket_name: &str) -> Result<Arc<dyn ObjectStore>, Anal
         })?;
 
     Ok(Arc::new(store))
-}
\ No newline at end of file
+}
@@ -1,26 +1,123 @@
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tonic::{transport::Server, Request, Response, Status};
 use tracing::{error, info};
 
 use crate::engine::AnalysisEngine;
 use crate::error::AnalysisError;
+use crate::delta_sharing_client::DeltaSharingProfile;
 use crate::proto::analysis::{
     analysis_service_server::{AnalysisService, AnalysisServiceServer},
-    AddDatasetRequest, AddDatasetResponse, ExecuteQueryRequest, ExecuteQueryResponse,
-    GetMetadataRequest, GetMetadataResponse, HealthCheckRequest, HealthCheckResponse,
-    ListDatasetsRequest, ListDatasetsResponse, QueryComplete,
+    AddDatasetRequest, AddDatasetResponse, AddSharedDatasetRequest, AddSharedDatasetResponse,
+    DatasetUsageRequest, DatasetUsageResponse, DeleteDatasetRequest, DeleteDatasetResponse,
+    ExecuteMultiQueryRequest, ExecuteQueryRequest,
+    ExecuteQueryResponse, GenerateDownloadUrlRequest, GenerateDownloadUrlResponse,
+    GetJobStatusRequest, GetJobStatusResponse, GetMetadataRequest, GetMetadataResponse,
+    HealthCheckRequest, HealthCheckResponse, ListDatasetsRequest, ListDatasetsResponse,
+    ListSharedTablesRequest, ListSharedTablesResponse, QueryComplete, RefreshDatasetRequest,
+    RefreshDatasetResponse, ResyncDatasetRequest, ResyncDatasetResponse, SemanticSearchMatch,
+    SemanticSearchRequest, SemanticSearchResponse, SharedTableInfo, SubmitQueryJobRequest,
+    SubmitQueryJobResponse, UpdateDatasetMetadataRequest, UpdateDatasetMetadataResponse,
+    WatchCatalogRequest, WatchCatalogResponse,
 };
+use crate::tls::TlsConfig;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 
+/// Default lifetime for a `generate_download_url` link when the caller
+/// leaves `expires_in_seconds` unset (0).
+const DEFAULT_DOWNLOAD_URL_EXPIRY: Duration = Duration::from_secs(3600);
+
+/// Bounds how many `execute_query` streams can be in flight at once. A burst
+/// of requests beyond `max_concurrent_queries` waits up to `acquire_timeout`
+/// for a permit to free up before being shed with `RESOURCE_EXHAUSTED`,
+/// rather than spawning unbounded tasks that buffer Arrow batches in memory.
+struct ConcurrencyLimiter {
+    semaphore: Arc<Semaphore>,
+    acquire_timeout: Duration,
+    in_flight: AtomicUsize,
+    rejected_total: AtomicU64,
+}
+
+impl ConcurrencyLimiter {
+    fn new(max_concurrent_queries: usize, acquire_timeout: Duration) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrent_queries)),
+            acquire_timeout,
+            in_flight: AtomicUsize::new(0),
+            rejected_total: AtomicU64::new(0),
+        }
+    }
+
+    /// Acquires a permit for one query, waiting up to `acquire_timeout` for
+    /// one to free up. Returns `None` if the limit is still exhausted once
+    /// the wait elapses, in which case the caller should shed the request.
+    /// The returned guard releases the permit and decrements `in_flight`
+    /// when dropped, whether the query completes or the client disconnects.
+    async fn acquire(self: &Arc<Self>) -> Option<ConcurrencyPermit> {
+        match tokio::time::timeout(self.acquire_timeout, self.semaphore.clone().acquire_owned())
+            .await
+        {
+            Ok(Ok(permit)) => {
+                self.in_flight.fetch_add(1, Ordering::Relaxed);
+                Some(ConcurrencyPermit {
+                    _permit: permit,
+                    limiter: self.clone(),
+                })
+            }
+            _ => {
+                self.rejected_total.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Number of `execute_query` streams currently holding a permit.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    /// Total number of queries shed because no permit became available.
+    pub fn rejected_total(&self) -> u64 {
+        self.rejected_total.load(Ordering::Relaxed)
+    }
+}
+
+struct ConcurrencyPermit {
+    _permit: OwnedSemaphorePermit,
+    limiter: Arc<ConcurrencyLimiter>,
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        self.limiter.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 pub struct GrpcServer {
     engine: Arc<AnalysisEngine>,
+    limiter: Arc<ConcurrencyLimiter>,
+    tls: Option<TlsConfig>,
 }
 
 impl GrpcServer {
-    pub fn new(engine: Arc<AnalysisEngine>) -> Self {
-        Self { engine }
+    pub fn new(
+        engine: Arc<AnalysisEngine>,
+        max_concurrent_queries: usize,
+        acquire_timeout: Duration,
+        tls: Option<TlsConfig>,
+    ) -> Self {
+        Self {
+            engine,
+            limiter: Arc::new(ConcurrencyLimiter::new(
+                max_concurrent_queries,
+                acquire_timeout,
+            )),
+            tls,
+        }
     }
 
     pub async fn start(&self, addr: SocketAddr) -> Result<(), AnalysisError> {
@@ -28,9 +125,25 @@ impl GrpcServer {
 
         let analysis_service = AnalysisServiceImpl {
             engine: self.engine.clone(),
+            limiter: self.limiter.clone(),
         };
 
-        Server::builder()
+        let mut server = Server::builder();
+
+        if let Some(tls) = self.tls.clone() {
+            let requires_client_cert = tls.requires_client_cert();
+            server = server
+                .tls_config(tls.into_server_tls_config()?)
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!("Failed to configure gRPC TLS: {}", e),
+                })?;
+            info!(
+                "gRPC server TLS enabled (client certificate required: {})",
+                requires_client_cert
+            );
+        }
+
+        server
             .add_service(AnalysisServiceServer::new(analysis_service))
             .serve(addr)
             .await?;
@@ -41,11 +154,14 @@ impl GrpcServer {
 
 struct AnalysisServiceImpl {
     engine: Arc<AnalysisEngine>,
+    limiter: Arc<ConcurrencyLimiter>,
 }
 
 #[tonic::async_trait]
 impl AnalysisService for AnalysisServiceImpl {
     type ExecuteQueryStream = ReceiverStream<Result<ExecuteQueryResponse, Status>>;
+    type ExecuteMultiQueryStream = ReceiverStream<Result<ExecuteQueryResponse, Status>>;
+    type WatchCatalogStream = ReceiverStream<Result<WatchCatalogResponse, Status>>;
 
     async fn list_datasets(
         &self,
@@ -97,10 +213,20 @@ impl AnalysisService for AnalysisServiceImpl {
             req.dataset_id, req.sql_query
         );
 
+        let permit = self.limiter.acquire().await.ok_or_else(|| {
+            error!(
+                "gRPC: Shedding execute_query request for dataset '{}': concurrency limit reached ({} in flight)",
+                req.dataset_id,
+                self.limiter.in_flight()
+            );
+            Status::resource_exhausted("Query engine is at capacity, please retry later")
+        })?;
+
         let (tx, rx) = mpsc::channel(32);
         let engine = self.engine.clone();
 
         tokio::spawn(async move {
+            let _permit = permit;
             let start_time = std::time::Instant::now();
             let limit = if req.limit > 0 { Some(req.limit) } else { None };
 
@@ -160,11 +286,87 @@ impl AnalysisService for AnalysisServiceImpl {
                 }
                 Err(e) => {
                     error!("gRPC: Query failed: {}", e);
+                    let _ = tx.send(Err(Status::from(e))).await;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    /// Like `execute_query`, but registers every dataset in `req.datasets`
+    /// under its own `alias` before running `req.sql_query` once, so a
+    /// single statement can reference more than one dataset (e.g. a JOIN)
+    /// instead of being scoped to the one implicit table `execute_query`
+    /// provides.
+    async fn execute_multi_query(
+        &self,
+        request: Request<ExecuteMultiQueryRequest>,
+    ) -> Result<Response<ReceiverStream<Result<ExecuteQueryResponse, Status>>>, Status> {
+        let req = request.into_inner();
+        info!(
+            "gRPC: Received execute_multi_query request for {} datasets with query: {}",
+            req.datasets.len(),
+            req.sql_query
+        );
+
+        let permit = self.limiter.acquire().await.ok_or_else(|| {
+            error!(
+                "gRPC: Shedding execute_multi_query request: concurrency limit reached ({} in flight)",
+                self.limiter.in_flight()
+            );
+            Status::resource_exhausted("Query engine is at capacity, please retry later")
+        })?;
+
+        let (tx, rx) = mpsc::channel(32);
+        let engine = self.engine.clone();
+
+        tokio::spawn(async move {
+            let _permit = permit;
+            let start_time = std::time::Instant::now();
+            let limit = if req.limit > 0 { Some(req.limit) } else { None };
+            let datasets: Vec<(String, String)> = req
+                .datasets
+                .into_iter()
+                .map(|d| (d.alias, d.dataset_id))
+                .collect();
+
+            match engine
+                .execute_query_multi(&datasets, &req.sql_query, limit)
+                .await
+            {
+                Ok(stream) => {
+                    let mut chunk_index = 0;
+                    let mut total_rows = 0;
+
+                    let (metadata, chunks) = stream.into_proto_parts();
+
+                    if let Some(metadata) = metadata {
+                        let response = ExecuteQueryResponse {
+                            response_type: Some(crate::proto::analysis::execute_query_response::ResponseType::Metadata(metadata)),
+                        };
+                        if tx.send(Ok(response)).await.is_err() {
+                            return;
+                        }
+                    }
+
+                    for chunk in chunks {
+                        total_rows += chunk.chunk_rows;
+                        let response = ExecuteQueryResponse {
+                            response_type: Some(crate::proto::analysis::execute_query_response::ResponseType::DataChunk(chunk)),
+                        };
+                        if tx.send(Ok(response)).await.is_err() {
+                            return; // Client disconnected
+                        }
+                        chunk_index += 1;
+                    }
+
+                    let execution_time = start_time.elapsed();
                     let complete = QueryComplete {
-                        total_rows: 0,
-                        execution_time_ms: start_time.elapsed().as_millis().to_string(),
-                        success: false,
-                        error_message: e.to_string(),
+                        total_rows,
+                        execution_time_ms: execution_time.as_millis().to_string(),
+                        success: true,
+                        error_message: String::new(),
                     };
                     let response = ExecuteQueryResponse {
                         response_type: Some(
@@ -174,6 +376,17 @@ impl AnalysisService for AnalysisServiceImpl {
                         ),
                     };
                     let _ = tx.send(Ok(response)).await;
+
+                    info!(
+                        "gRPC: Multi-dataset query completed. Sent {} chunks with {} total rows in {}ms",
+                        chunk_index,
+                        total_rows,
+                        execution_time.as_millis()
+                    );
+                }
+                Err(e) => {
+                    error!("gRPC: Multi-dataset query failed: {}", e);
+                    let _ = tx.send(Err(Status::from(e))).await;
                 }
             }
         });
@@ -273,6 +486,490 @@ impl AnalysisService for AnalysisServiceImpl {
         }
     }
 
+    async fn list_shared_tables(
+        &self,
+        request: Request<ListSharedTablesRequest>,
+    ) -> Result<Response<ListSharedTablesResponse>, Status> {
+        let req = request.into_inner();
+        info!("gRPC: Received list_shared_tables request");
+
+        let profile = DeltaSharingProfile {
+            share_credentials_version: 1,
+            endpoint: req.endpoint,
+            bearer_token: req.bearer_token,
+        };
+
+        match self.engine.list_shared_tables(profile).await {
+            Ok(tables) => {
+                info!("gRPC: Found {} shared tables", tables.len());
+                Ok(Response::new(ListSharedTablesResponse {
+                    tables: tables
+                        .into_iter()
+                        .map(|t| SharedTableInfo {
+                            share: t.share,
+                            schema: t.schema,
+                            name: t.name,
+                        })
+                        .collect(),
+                }))
+            }
+            Err(e) => {
+                error!("gRPC: Failed to list shared tables: {}", e);
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    async fn add_shared_dataset(
+        &self,
+        request: Request<AddSharedDatasetRequest>,
+    ) -> Result<Response<AddSharedDatasetResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            "gRPC: Received add_shared_dataset request for '{}' ({}.{}.{})",
+            req.name, req.share, req.schema, req.table
+        );
+
+        if req.name.is_empty() {
+            return Ok(Response::new(AddSharedDatasetResponse {
+                success: false,
+                dataset_id: String::new(),
+                message: "Dataset name is required".to_string(),
+                dataset: None,
+            }));
+        }
+
+        let profile = DeltaSharingProfile {
+            share_credentials_version: 1,
+            endpoint: req.endpoint,
+            bearer_token: req.bearer_token,
+        };
+        let description = if req.description.is_empty() {
+            None
+        } else {
+            Some(req.description)
+        };
+        let tags = if req.tags.is_empty() {
+            None
+        } else {
+            Some(req.tags)
+        };
+
+        match self
+            .engine
+            .add_dataset_from_delta_share(
+                req.name,
+                profile,
+                req.share,
+                req.schema,
+                req.table,
+                description,
+                tags,
+                req.partition_filters,
+            )
+            .await
+        {
+            Ok(dataset_id) => {
+                info!("Successfully added Delta Sharing dataset '{}'", dataset_id);
+
+                let dataset = self
+                    .engine
+                    .list_datasets()
+                    .await
+                    .into_iter()
+                    .find(|d| d.id == dataset_id);
+
+                Ok(Response::new(AddSharedDatasetResponse {
+                    success: true,
+                    dataset_id: dataset_id.clone(),
+                    message: format!("Dataset '{}' added successfully", dataset_id),
+                    dataset,
+                }))
+            }
+            Err(e) => {
+                error!(
+                    "gRPC: Failed to add Delta Sharing dataset '{}.{}.{}': {}",
+                    req.share, req.schema, req.table, e
+                );
+                Ok(Response::new(AddSharedDatasetResponse {
+                    success: false,
+                    dataset_id: String::new(),
+                    message: "Failed to add Delta Sharing dataset. Please check the profile and table coordinate and try again."
+                        .to_string(),
+                    dataset: None,
+                }))
+            }
+        }
+    }
+
+    async fn delete_dataset(
+        &self,
+        request: Request<DeleteDatasetRequest>,
+    ) -> Result<Response<DeleteDatasetResponse>, Status> {
+        let req = request.into_inner();
+        info!("gRPC: Received delete_dataset request for '{}'", req.dataset_id);
+
+        match self.engine.delete_dataset(&req.dataset_id).await {
+            Ok(()) => {
+                info!("gRPC: Deleted dataset '{}'", req.dataset_id);
+                Ok(Response::new(DeleteDatasetResponse {
+                    success: true,
+                    message: format!("Dataset '{}' deleted successfully", req.dataset_id),
+                }))
+            }
+            Err(e) => {
+                error!("gRPC: Failed to delete dataset '{}': {}", req.dataset_id, e);
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    async fn update_dataset_metadata(
+        &self,
+        request: Request<UpdateDatasetMetadataRequest>,
+    ) -> Result<Response<UpdateDatasetMetadataResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            "gRPC: Received update_dataset_metadata request for '{}'",
+            req.dataset_id
+        );
+
+        let description = if req.description.is_empty() {
+            None
+        } else {
+            Some(req.description)
+        };
+        let tags = if req.tags.is_empty() { None } else { Some(req.tags) };
+
+        match self
+            .engine
+            .update_dataset_metadata(&req.dataset_id, description, tags)
+            .await
+        {
+            Ok(metadata) => {
+                info!("gRPC: Updated metadata for dataset '{}'", req.dataset_id);
+                Ok(Response::new(UpdateDatasetMetadataResponse {
+                    success: true,
+                    message: format!("Dataset '{}' updated successfully", req.dataset_id),
+                    metadata: Some(metadata),
+                }))
+            }
+            Err(e) => {
+                error!(
+                    "gRPC: Failed to update metadata for dataset '{}': {}",
+                    req.dataset_id, e
+                );
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    async fn refresh_dataset(
+        &self,
+        request: Request<RefreshDatasetRequest>,
+    ) -> Result<Response<RefreshDatasetResponse>, Status> {
+        let req = request.into_inner();
+        info!("gRPC: Received refresh_dataset request for '{}'", req.dataset_id);
+
+        match self.engine.refresh_dataset(&req.dataset_id).await {
+            Ok(()) => {
+                info!("gRPC: Refreshed dataset '{}'", req.dataset_id);
+                Ok(Response::new(RefreshDatasetResponse {
+                    success: true,
+                    message: format!("Dataset '{}' refreshed successfully", req.dataset_id),
+                }))
+            }
+            Err(e) => {
+                error!("gRPC: Failed to refresh dataset '{}': {}", req.dataset_id, e);
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    async fn resync_dataset(
+        &self,
+        request: Request<ResyncDatasetRequest>,
+    ) -> Result<Response<ResyncDatasetResponse>, Status> {
+        let req = request.into_inner();
+        info!("gRPC: Received resync_dataset request for '{}'", req.dataset_id);
+
+        match self.engine.resync_dataset(&req.dataset_id).await {
+            Ok(summary) => {
+                info!(
+                    "gRPC: Resynced dataset '{}': {} added, {} updated, {} removed",
+                    req.dataset_id, summary.files_added, summary.files_updated, summary.files_removed
+                );
+                Ok(Response::new(ResyncDatasetResponse {
+                    success: true,
+                    message: format!("Dataset '{}' resynced successfully", req.dataset_id),
+                    files_added: summary.files_added as i32,
+                    files_updated: summary.files_updated as i32,
+                    files_removed: summary.files_removed as i32,
+                }))
+            }
+            Err(e) => {
+                error!("gRPC: Failed to resync dataset '{}': {}", req.dataset_id, e);
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    /// Issues a time-limited download URL for one file in a dataset (or its
+    /// first file, if `filename` is omitted). `expires_in_seconds` falls
+    /// back to [`DEFAULT_DOWNLOAD_URL_EXPIRY`] when unset (0).
+    async fn generate_download_url(
+        &self,
+        request: Request<GenerateDownloadUrlRequest>,
+    ) -> Result<Response<GenerateDownloadUrlResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            "gRPC: Received generate_download_url request for '{}'",
+            req.dataset_id
+        );
+
+        let filename = if req.filename.is_empty() {
+            None
+        } else {
+            Some(req.filename.as_str())
+        };
+        let expires_in = if req.expires_in_seconds == 0 {
+            DEFAULT_DOWNLOAD_URL_EXPIRY
+        } else {
+            Duration::from_secs(req.expires_in_seconds as u64)
+        };
+
+        match self
+            .engine
+            .generate_download_url(&req.dataset_id, filename, expires_in)
+            .await
+        {
+            Ok(url) => {
+                info!("gRPC: Generated download url for dataset '{}'", req.dataset_id);
+                Ok(Response::new(GenerateDownloadUrlResponse {
+                    url,
+                    expires_in_seconds: expires_in.as_secs() as i64,
+                }))
+            }
+            Err(e) => {
+                error!(
+                    "gRPC: Failed to generate download url for dataset '{}': {}",
+                    req.dataset_id, e
+                );
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    /// Reports a dataset's current usage against its quota, if any.
+    /// `stats_available` is `false` when `size_bytes`/`row_count` couldn't be
+    /// determined (statistics not yet collected) - treat them as unknown,
+    /// not zero, in that case.
+    async fn dataset_usage(
+        &self,
+        request: Request<DatasetUsageRequest>,
+    ) -> Result<Response<DatasetUsageResponse>, Status> {
+        let req = request.into_inner();
+        info!("gRPC: Received dataset_usage request for '{}'", req.dataset_id);
+
+        match self.engine.dataset_usage(&req.dataset_id).await {
+            Ok(Some((size_bytes, row_count, max_size_bytes, max_row_count))) => {
+                Ok(Response::new(DatasetUsageResponse {
+                    found: true,
+                    stats_available: size_bytes.is_some() && row_count.is_some(),
+                    size_bytes: size_bytes.unwrap_or(0),
+                    row_count: row_count.unwrap_or(0),
+                    max_size_bytes: max_size_bytes.unwrap_or(0),
+                    max_row_count: max_row_count.unwrap_or(0),
+                }))
+            }
+            Ok(None) => Ok(Response::new(DatasetUsageResponse {
+                found: false,
+                stats_available: false,
+                size_bytes: 0,
+                row_count: 0,
+                max_size_bytes: 0,
+                max_row_count: 0,
+            })),
+            Err(e) => {
+                error!(
+                    "gRPC: Failed to report usage for dataset '{}': {}",
+                    req.dataset_id, e
+                );
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    /// Finds the rows of a dataset whose `text_column` value is closest in
+    /// meaning to `query`, by cosine similarity over a lazily computed and
+    /// cached embedding index. `k` is clamped to the number of eligible
+    /// (non-empty) rows by the engine.
+    async fn semantic_search(
+        &self,
+        request: Request<SemanticSearchRequest>,
+    ) -> Result<Response<SemanticSearchResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            "gRPC: Received semantic_search request for dataset '{}' column '{}'",
+            req.dataset_id, req.text_column
+        );
+
+        let k = req.k.max(0) as usize;
+
+        match self
+            .engine
+            .semantic_search(&req.dataset_id, &req.text_column, &req.query, k)
+            .await
+        {
+            Ok(matches) => {
+                info!(
+                    "gRPC: semantic_search on '{}' returned {} matches",
+                    req.dataset_id,
+                    matches.len()
+                );
+                Ok(Response::new(SemanticSearchResponse {
+                    matches: matches
+                        .into_iter()
+                        .map(|(row_id, score)| SemanticSearchMatch { row_id, score })
+                        .collect(),
+                }))
+            }
+            Err(e) => {
+                error!(
+                    "gRPC: semantic_search failed for dataset '{}': {}",
+                    req.dataset_id, e
+                );
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    /// Streams dataset ids as they're added or updated in the catalog, so a
+    /// connected client (e.g. the MCP server) can react live instead of
+    /// re-polling `list_datasets`. Never completes on its own; it ends only
+    /// when the client disconnects.
+    async fn watch_catalog(
+        &self,
+        _request: Request<WatchCatalogRequest>,
+    ) -> Result<Response<ReceiverStream<Result<WatchCatalogResponse, Status>>>, Status> {
+        info!("gRPC: Received watch_catalog request");
+
+        let (tx, rx) = mpsc::channel(32);
+        let mut changes = Box::pin(self.engine.watch_catalog());
+
+        tokio::spawn(async move {
+            use tokio_stream::StreamExt;
+
+            while let Some(dataset_id) = changes.next().await {
+                let response = WatchCatalogResponse { dataset_id };
+                if tx.send(Ok(response)).await.is_err() {
+                    return; // Client disconnected
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    /// Enqueues `sql_query` against `dataset_id` as a background job and
+    /// returns its id immediately, for a caller that would rather poll
+    /// `get_job_status` than hold this RPC open for however long the query
+    /// takes. Only available on the postgres backend, which is where the
+    /// job queue lives.
+    #[cfg(feature = "backend-postgres")]
+    async fn submit_query_job(
+        &self,
+        request: Request<SubmitQueryJobRequest>,
+    ) -> Result<Response<SubmitQueryJobResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            "gRPC: Received submit_query_job request for dataset '{}'",
+            req.dataset_id
+        );
+
+        let limit = if req.limit > 0 { Some(req.limit) } else { None };
+
+        match self
+            .engine
+            .submit_query_job(&req.dataset_id, &req.sql_query, limit)
+            .await
+        {
+            Ok(job_id) => {
+                info!("gRPC: Submitted query job '{}'", job_id);
+                Ok(Response::new(SubmitQueryJobResponse {
+                    job_id: job_id.to_string(),
+                }))
+            }
+            Err(e) => {
+                error!(
+                    "gRPC: Failed to submit query job for dataset '{}': {}",
+                    req.dataset_id, e
+                );
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[cfg(not(feature = "backend-postgres"))]
+    async fn submit_query_job(
+        &self,
+        _request: Request<SubmitQueryJobRequest>,
+    ) -> Result<Response<SubmitQueryJobResponse>, Status> {
+        Err(Status::unimplemented(
+            "The job queue is only supported on the postgres backend",
+        ))
+    }
+
+    /// Reports a job's current status/result for a caller polling after
+    /// `submit_query_job`. `found` is `false` if `job_id` doesn't exist.
+    #[cfg(feature = "backend-postgres")]
+    async fn get_job_status(
+        &self,
+        request: Request<GetJobStatusRequest>,
+    ) -> Result<Response<GetJobStatusResponse>, Status> {
+        let req = request.into_inner();
+        info!("gRPC: Received get_job_status request for job '{}'", req.job_id);
+
+        let job_id = match uuid::Uuid::parse_str(&req.job_id) {
+            Ok(job_id) => job_id,
+            Err(e) => {
+                return Err(Status::invalid_argument(format!(
+                    "Invalid job id '{}': {}",
+                    req.job_id, e
+                )))
+            }
+        };
+
+        match self.engine.job_status(job_id).await {
+            Ok(Some(job)) => Ok(Response::new(GetJobStatusResponse {
+                found: true,
+                status: job.status.to_string(),
+                job_payload_json: job.job.to_string(),
+                result_json: job.result.map(|r| r.to_string()).unwrap_or_default(),
+            })),
+            Ok(None) => Ok(Response::new(GetJobStatusResponse {
+                found: false,
+                status: String::new(),
+                job_payload_json: String::new(),
+                result_json: String::new(),
+            })),
+            Err(e) => {
+                error!("gRPC: Failed to load job '{}': {}", req.job_id, e);
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[cfg(not(feature = "backend-postgres"))]
+    async fn get_job_status(
+        &self,
+        _request: Request<GetJobStatusRequest>,
+    ) -> Result<Response<GetJobStatusResponse>, Status> {
+        Err(Status::unimplemented(
+            "The job queue is only supported on the postgres backend",
+        ))
+    }
+
     async fn health_check(
         &self,
         _request: Request<HealthCheckRequest>,
@@ -280,10 +977,10 @@ impl AnalysisService for AnalysisServiceImpl {
         info!("gRPC: Received health_check request");
 
         match self.engine.health_check().await {
-            Ok(_) => {
-                info!("gRPC: Health check passed");
+            Ok(status) => {
+                info!("gRPC: Health check passed with status '{}'", status);
                 Ok(Response::new(HealthCheckResponse {
-                    status: "healthy".to_string(),
+                    status,
                     version: "0.1.0".to_string(),
                 }))
             }
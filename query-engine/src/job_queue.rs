@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use diesel::sql_types::{Jsonb, Nullable, Text, Timestamptz, Uuid as UuidType};
+use uuid::Uuid;
+
+/// The `job_queue.queue` value `submit_query_job`/the query-job worker use,
+/// so a single job queue table can host other kinds of jobs later without
+/// a worker accidentally claiming one meant for a different consumer.
+pub const QUERY_JOB_QUEUE: &str = "query";
+
+/// Mirrors the Postgres `job_status` enum created by the
+/// `create_job_queue` migration. Stored and queried as `Text` (cast via
+/// `::text`/the literal string on the Postgres side) rather than mapped
+/// through a dedicated SQL type, unlike [`crate::catalog::DataFormat`],
+/// which `DbEnum` maps to its native `data_format` enum column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    New,
+    Running,
+    Done,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::New => "new",
+            JobStatus::Running => "running",
+            JobStatus::Done => "done",
+            JobStatus::Failed => "failed",
+        }
+    }
+}
+
+impl std::fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<&str> for JobStatus {
+    fn from(value: &str) -> Self {
+        match value {
+            "running" => JobStatus::Running,
+            "done" => JobStatus::Done,
+            "failed" => JobStatus::Failed,
+            _ => JobStatus::New,
+        }
+    }
+}
+
+/// A row claimed off `job_queue`, handed to a worker so it can run the job
+/// and report back via `complete_job`/`fail_job`.
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub id: Uuid,
+    pub queue: String,
+    pub job: serde_json::Value,
+    pub status: JobStatus,
+    pub result: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Raw row shape returned by the hand-written SQL in
+/// [`crate::database::DatabaseManager`]'s job-queue methods. `claim_job` uses
+/// `FOR UPDATE SKIP LOCKED`, which Diesel's query builder can't express, so
+/// those methods go through `diesel::sql_query`/`QueryableByName` instead of
+/// the usual `schema.rs` table DSL.
+#[derive(Debug, diesel::QueryableByName)]
+pub(crate) struct JobRow {
+    #[diesel(sql_type = UuidType)]
+    pub id: Uuid,
+    #[diesel(sql_type = Text)]
+    pub queue: String,
+    #[diesel(sql_type = Jsonb)]
+    pub job: serde_json::Value,
+    #[diesel(sql_type = Text)]
+    pub status: String,
+    #[diesel(sql_type = Nullable<Jsonb>)]
+    pub result: Option<serde_json::Value>,
+    #[diesel(sql_type = Timestamptz)]
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<JobRow> for Job {
+    fn from(row: JobRow) -> Self {
+        Job {
+            id: row.id,
+            queue: row.queue,
+            job: row.job,
+            status: row.status.as_str().into(),
+            result: row.result,
+            created_at: row.created_at,
+        }
+    }
+}
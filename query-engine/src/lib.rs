@@ -4,18 +4,32 @@ pub mod proto {
     }
 }
 
+pub mod azure_client;
 pub mod catalog;
+pub mod catalog_cache;
+pub mod cluster;
 pub mod database;
 pub mod datafusion_engine;
 pub mod dataset_manager;
+pub mod delta_sharing_client;
 pub mod domain;
+pub mod embeddings;
 pub mod engine;
 pub mod error;
 pub mod gcs_client;
 pub mod grpc_server;
+pub mod http_client;
+pub mod job_queue;
+pub mod local_fs_client;
+pub mod metadata_extraction;
+pub mod metrics;
 pub mod models;
+pub mod notifications;
+pub mod resilient_store;
+pub mod s3_client;
 pub mod schema;
 pub mod storage;
+pub mod tls;
 
 pub use engine::AnalysisEngine;
 pub use error::AnalysisError;
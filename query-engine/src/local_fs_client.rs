@@ -0,0 +1,32 @@
+use object_store::local::LocalFileSystem;
+use object_store::ObjectStore;
+use std::sync::Arc;
+
+use crate::error::AnalysisError;
+
+/// Builds a `LocalFileSystem` store rooted at the directory configured via
+/// `LOCAL_FS_ALLOWED_ROOT`.
+///
+/// `file://` sources are disabled by default: without an explicit allow-list
+/// root, any caller who can register or resync a dataset could otherwise
+/// point it at an arbitrary path on the query-engine host (`/etc/passwd`,
+/// local credential files, ...) and read it out via SQL. Setting
+/// `LOCAL_FS_ALLOWED_ROOT` opts in and confines every `file://` dataset to
+/// that directory, the same way `LocalFileSystem::new_with_prefix` confines
+/// all its paths.
+pub fn create_local_fs_client() -> Result<Arc<dyn ObjectStore>, AnalysisError> {
+    let root = std::env::var("LOCAL_FS_ALLOWED_ROOT").map_err(|_| AnalysisError::ConfigError {
+        message: "file:// sources are disabled; set LOCAL_FS_ALLOWED_ROOT to the directory \
+                  they should be confined to in order to enable them"
+            .to_string(),
+    })?;
+
+    let store = LocalFileSystem::new_with_prefix(&root).map_err(|e| AnalysisError::ConfigError {
+        message: format!(
+            "Failed to create local filesystem client rooted at '{}': {}",
+            root, e
+        ),
+    })?;
+
+    Ok(Arc::new(store))
+}
@@ -1,5 +1,10 @@
+use hyper::server::conn::http1;
+use hyper::{body::Incoming, service::service_fn, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
 use tokio::signal;
 use tracing::{error, info};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -10,20 +15,59 @@ pub mod proto {
     }
 }
 
+mod azure_client;
 mod catalog;
+mod catalog_cache;
+mod cluster;
 mod database;
 mod datafusion_engine;
 mod dataset_manager;
+mod delta_sharing_client;
 mod domain;
+mod embeddings;
 mod engine;
 mod error;
+mod gcs_client;
 mod grpc_server;
+mod http_client;
+mod job_queue;
+mod local_fs_client;
+mod metadata_extraction;
+mod metrics;
 mod models;
+mod resilient_store;
+mod s3_client;
 mod schema;
 mod storage;
+mod tls;
 
+use cluster::ClusterConfig;
+use database::DatabaseConfig;
 use engine::AnalysisEngine;
 use grpc_server::GrpcServer;
+use metrics::Metrics;
+
+async fn handle_metrics_request(
+    _req: Request<Incoming>,
+    metrics: Arc<Metrics>,
+) -> Result<Response<String>, hyper::Error> {
+    let response = match metrics.render() {
+        Ok(body) => Response::builder()
+            .status(StatusCode::OK)
+            .header(hyper::header::CONTENT_TYPE, "text/plain; version=0.0.4")
+            .body(body)
+            .unwrap(),
+        Err(e) => {
+            error!("Failed to render metrics: {}", e);
+            Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(e.to_string())
+                .unwrap()
+        }
+    };
+
+    Ok(response)
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -42,27 +86,116 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .parse()
         .expect("Invalid GRPC_PORT");
 
+    let metrics_port: u16 = std::env::var("METRICS_PORT")
+        .unwrap_or_else(|_| "9100".to_string())
+        .parse()
+        .expect("Invalid METRICS_PORT");
+
+    let max_concurrent_queries: usize = std::env::var("MAX_CONCURRENT_QUERIES")
+        .unwrap_or_else(|_| "64".to_string())
+        .parse()
+        .expect("Invalid MAX_CONCURRENT_QUERIES");
+
+    let query_acquire_timeout = Duration::from_millis(
+        std::env::var("QUERY_ACQUIRE_TIMEOUT_MS")
+            .unwrap_or_else(|_| "50".to_string())
+            .parse()
+            .expect("Invalid QUERY_ACQUIRE_TIMEOUT_MS"),
+    );
+
     let bucket_name =
         std::env::var("GCS_BUCKET_NAME").expect("GCS_BUCKET_NAME environment variable is required");
 
     let database_url =
         std::env::var("DATABASE_URL").expect("DATABASE_URL environment variable is required");
 
+    let retry_config = resilient_store::retry_config_from_env();
+
+    let tls_config = tls::TlsConfig::from_env()?;
+
     info!("Configuration loaded:");
     info!("  gRPC Port: {}", grpc_port);
+    info!("  Metrics Port: {}", metrics_port);
+    info!("  Max concurrent queries: {}", max_concurrent_queries);
+    info!("  Query acquire timeout: {:?}", query_acquire_timeout);
     info!("  GCS Bucket: {}", bucket_name);
     info!(
         "  Database URL: {}",
-        database_url.replace(
-            &database_url[database_url.find("://").unwrap() + 3..database_url.rfind("@").unwrap()],
-            "***"
-        )
+        match (database_url.find("://"), database_url.rfind('@')) {
+            (Some(scheme_end), Some(at)) if at > scheme_end => database_url
+                .replace(&database_url[scheme_end + 3..at], "***"),
+            _ => database_url.clone(),
+        }
+    );
+    info!(
+        "  Object store retries: max_retries={} init_backoff={:?} max_backoff={:?} retry_timeout={:?}",
+        retry_config.max_retries,
+        retry_config.backoff.init_backoff,
+        retry_config.backoff.max_backoff,
+        retry_config.retry_timeout
+    );
+    info!(
+        "  Object store concurrency cap: {}",
+        resilient_store::max_concurrency_from_env()
+    );
+    if let Some(latency) = resilient_store::throttle_latency_from_env() {
+        info!("  Object store throttle latency: {:?}", latency);
+    }
+    match &tls_config {
+        Some(tls) => info!(
+            "  gRPC TLS: enabled (client certificate required: {})",
+            tls.requires_client_cert()
+        ),
+        None => info!("  gRPC TLS: disabled (plaintext)"),
+    }
+
+    let cluster_config = ClusterConfig::from_env();
+    info!("  Cluster namespace: {}", cluster_config.namespace);
+    if cluster_config.is_standalone() {
+        info!("  Cluster mode: standalone (no peers configured)");
+    } else {
+        info!("  Cluster peers: {}", cluster_config.peers.join(", "));
+    }
+
+    let database_config = DatabaseConfig::from_env();
+    info!("  Database pool max size: {}", database_config.max_size);
+    info!(
+        "  Database pool timeouts: wait={:?} create={:?} recycle={:?}",
+        database_config.wait_timeout, database_config.create_timeout, database_config.recycle_timeout
     );
 
-    let engine = Arc::new(AnalysisEngine::new(bucket_name, database_url).await?);
+    let catalog_cache_config = catalog_cache::CatalogCacheConfig::from_env();
+    info!(
+        "  Catalog cache: ttl={:?} max_entries={} metadata_cache_dir={:?}",
+        catalog_cache_config.ttl,
+        catalog_cache_config.max_entries,
+        catalog_cache_config.metadata_cache_dir
+    );
+
+    let metrics = Arc::new(Metrics::new()?);
+
+    let engine = Arc::new(
+        AnalysisEngine::new(
+            bucket_name,
+            database_url,
+            &database_config,
+            &catalog_cache_config,
+            metrics.clone(),
+            cluster_config,
+        )
+        .await?,
+    );
     info!("Analysis engine initialized successfully");
 
-    let grpc_server = GrpcServer::new(engine.clone());
+    #[cfg(feature = "backend-postgres")]
+    engine.clone().spawn_query_job_worker();
+
+    let grpc_server = GrpcServer::new(
+        engine.clone(),
+        max_concurrent_queries,
+        query_acquire_timeout,
+        tls_config,
+    );
     let grpc_addr: SocketAddr = ([0, 0, 0, 0], grpc_port).into();
     let grpc_handle = tokio::spawn(async move {
         if let Err(e) = grpc_server.start(grpc_addr).await {
@@ -70,8 +203,36 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
+    let metrics_addr: SocketAddr = ([0, 0, 0, 0], metrics_port).into();
+    let metrics_listener = TcpListener::bind(metrics_addr).await?;
+    let metrics_handle = tokio::spawn(async move {
+        loop {
+            let (stream, remote_addr) = match metrics_listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    error!("Metrics server accept error: {}", e);
+                    continue;
+                }
+            };
+
+            let metrics = metrics.clone();
+            tokio::spawn(async move {
+                let io = TokioIo::new(stream);
+                let service = service_fn(move |req| {
+                    let metrics = metrics.clone();
+                    async move { handle_metrics_request(req, metrics).await }
+                });
+
+                if let Err(e) = http1::Builder::new().serve_connection(io, service).await {
+                    error!("Metrics HTTP connection error for {}: {}", remote_addr, e);
+                }
+            });
+        }
+    });
+
     info!("Query Engine Service started successfully");
     info!("gRPC server listening on {}", grpc_addr);
+    info!("Metrics server listening on {}", metrics_addr);
 
     match signal::ctrl_c().await {
         Ok(()) => {
@@ -83,6 +244,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     grpc_handle.abort();
+    metrics_handle.abort();
 
     info!("Query Engine Service shutdown complete");
     Ok(())
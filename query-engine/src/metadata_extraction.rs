@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::csv::reader::Format as CsvFormat;
+use object_store::{path::Path as ObjectPath, ObjectStore};
+use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+use parquet::file::statistics::Statistics as ParquetStatistics;
+use tracing::warn;
+
+use crate::catalog::{ColumnMetadata, DataFormat};
+use crate::error::AnalysisError;
+
+/// How many leading rows of a CSV file to sample when inferring column types.
+/// Counting rows and gathering per-column statistics still requires a full
+/// pass, but this keeps schema inference itself cheap.
+const CSV_SCHEMA_SAMPLE_ROWS: usize = 100;
+
+/// Schema and statistics gathered for a single dataset file.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractedFileMetadata {
+    pub row_count: i64,
+    pub columns: Vec<ColumnMetadata>,
+}
+
+/// Extracts column schema and statistics for `object_path`, dispatching on
+/// `format`. Best-effort: callers should treat an `Err` as "skip extraction
+/// for this file" rather than failing the whole import, since a dataset is
+/// still usable without enriched metadata.
+pub async fn extract_file_metadata(
+    store: Arc<dyn ObjectStore>,
+    object_path: &ObjectPath,
+    format: DataFormat,
+) -> Result<ExtractedFileMetadata, AnalysisError> {
+    match format {
+        DataFormat::Parquet => extract_parquet_metadata(store, object_path).await,
+        DataFormat::Csv => extract_csv_metadata(store, object_path).await,
+        other => Err(AnalysisError::ConfigError {
+            message: format!("Metadata extraction is not implemented for format {}", other),
+        }),
+    }
+}
+
+/// Reads only the Parquet footer and row-group statistics - never the row
+/// data itself - to recover column names/types and per-column null counts
+/// and min/max bounds.
+async fn extract_parquet_metadata(
+    store: Arc<dyn ObjectStore>,
+    object_path: &ObjectPath,
+) -> Result<ExtractedFileMetadata, AnalysisError> {
+    let reader = ParquetObjectReader::new(store, object_path.clone());
+    let builder = ParquetRecordBatchStreamBuilder::new(reader)
+        .await
+        .map_err(|e| AnalysisError::ConfigError {
+            message: format!("Failed to read Parquet footer for {}: {}", object_path, e),
+        })?;
+
+    let arrow_schema = builder.schema().clone();
+    let parquet_metadata = builder.metadata();
+
+    let row_count: i64 = parquet_metadata
+        .row_groups()
+        .iter()
+        .map(|rg| rg.num_rows())
+        .sum();
+
+    let mut columns = Vec::with_capacity(arrow_schema.fields().len());
+    for (col_index, field) in arrow_schema.fields().iter().enumerate() {
+        let mut null_count: i64 = 0;
+        let mut min_str: Option<String> = None;
+        let mut max_str: Option<String> = None;
+
+        for row_group in parquet_metadata.row_groups() {
+            let Some(column_chunk) = row_group.columns().get(col_index) else {
+                continue;
+            };
+            let Some(stats) = column_chunk.statistics() else {
+                continue;
+            };
+
+            null_count += stats.null_count_opt().unwrap_or(0) as i64;
+
+            if let (Some(min), Some(max)) = (parquet_min_as_string(stats), parquet_max_as_string(stats))
+            {
+                min_str = Some(match min_str {
+                    Some(existing) if existing <= min => existing,
+                    _ => min,
+                });
+                max_str = Some(match max_str {
+                    Some(existing) if existing >= max => existing,
+                    _ => max,
+                });
+            }
+        }
+
+        let mut statistics = HashMap::new();
+        statistics.insert("null_count".to_string(), null_count.to_string());
+        if let Some(min) = min_str {
+            statistics.insert("min".to_string(), min);
+        }
+        if let Some(max) = max_str {
+            statistics.insert("max".to_string(), max);
+        }
+
+        columns.push(ColumnMetadata {
+            name: field.name().clone(),
+            data_type: format!("{:?}", field.data_type()),
+            nullable: field.is_nullable(),
+            description: String::new(),
+            statistics,
+        });
+    }
+
+    Ok(ExtractedFileMetadata { row_count, columns })
+}
+
+fn parquet_min_as_string(stats: &ParquetStatistics) -> Option<String> {
+    match stats {
+        ParquetStatistics::Boolean(s) => s.min_opt().map(|v| v.to_string()),
+        ParquetStatistics::Int32(s) => s.min_opt().map(|v| v.to_string()),
+        ParquetStatistics::Int64(s) => s.min_opt().map(|v| v.to_string()),
+        ParquetStatistics::Float(s) => s.min_opt().map(|v| v.to_string()),
+        ParquetStatistics::Double(s) => s.min_opt().map(|v| v.to_string()),
+        ParquetStatistics::ByteArray(s) => s
+            .min_opt()
+            .map(|v| String::from_utf8_lossy(v.data()).to_string()),
+        _ => None,
+    }
+}
+
+fn parquet_max_as_string(stats: &ParquetStatistics) -> Option<String> {
+    match stats {
+        ParquetStatistics::Boolean(s) => s.max_opt().map(|v| v.to_string()),
+        ParquetStatistics::Int32(s) => s.max_opt().map(|v| v.to_string()),
+        ParquetStatistics::Int64(s) => s.max_opt().map(|v| v.to_string()),
+        ParquetStatistics::Float(s) => s.max_opt().map(|v| v.to_string()),
+        ParquetStatistics::Double(s) => s.max_opt().map(|v| v.to_string()),
+        ParquetStatistics::ByteArray(s) => s
+            .max_opt()
+            .map(|v| String::from_utf8_lossy(v.data()).to_string()),
+        _ => None,
+    }
+}
+
+/// Running per-column accumulator for the CSV streaming pass.
+#[derive(Default)]
+struct ColumnAccumulator {
+    null_count: i64,
+    distinct: std::collections::HashSet<String>,
+    min: Option<String>,
+    max: Option<String>,
+    numeric_count: u64,
+    numeric_mean: f64,
+    numeric_m2: f64,
+}
+
+/// Distinct-value tracking beyond this many entries is dropped - the
+/// dataset is still counted, we just stop calling it an exact estimate.
+const DISTINCT_SAMPLE_CAP: usize = 1000;
+
+impl ColumnAccumulator {
+    fn observe(&mut self, value: Option<&str>) {
+        let Some(value) = value.filter(|v| !v.is_empty()) else {
+            self.null_count += 1;
+            return;
+        };
+
+        if self.distinct.len() < DISTINCT_SAMPLE_CAP {
+            self.distinct.insert(value.to_string());
+        }
+
+        self.min = Some(match self.min.take() {
+            Some(existing) if existing.as_str() <= value => existing,
+            _ => value.to_string(),
+        });
+        self.max = Some(match self.max.take() {
+            Some(existing) if existing.as_str() >= value => existing,
+            _ => value.to_string(),
+        });
+
+        if let Ok(numeric) = value.parse::<f64>() {
+            // Welford's online algorithm, so mean/stddev fall out of the
+            // same single pass used for null/min/max.
+            self.numeric_count += 1;
+            let delta = numeric - self.numeric_mean;
+            self.numeric_mean += delta / self.numeric_count as f64;
+            let delta2 = numeric - self.numeric_mean;
+            self.numeric_m2 += delta * delta2;
+        }
+    }
+
+    fn into_statistics(self, row_count: u64) -> HashMap<String, String> {
+        let mut statistics = HashMap::new();
+        statistics.insert("null_count".to_string(), self.null_count.to_string());
+        statistics.insert(
+            "distinct_count".to_string(),
+            if self.distinct.len() < DISTINCT_SAMPLE_CAP {
+                self.distinct.len().to_string()
+            } else {
+                format!(">={}", DISTINCT_SAMPLE_CAP)
+            },
+        );
+        if let Some(min) = self.min {
+            statistics.insert("min".to_string(), min);
+        }
+        if let Some(max) = self.max {
+            statistics.insert("max".to_string(), max);
+        }
+        if self.numeric_count == row_count && self.numeric_count > 0 {
+            statistics.insert("mean".to_string(), self.numeric_mean.to_string());
+            let variance = self.numeric_m2 / self.numeric_count as f64;
+            statistics.insert("stddev".to_string(), variance.sqrt().to_string());
+        }
+        statistics
+    }
+}
+
+/// Samples the first [`CSV_SCHEMA_SAMPLE_ROWS`] rows to infer a schema, then
+/// makes a single streaming pass over the whole file to count rows and
+/// accumulate per-column null counts, min/max, and (for numeric columns)
+/// mean/stddev.
+async fn extract_csv_metadata(
+    store: Arc<dyn ObjectStore>,
+    object_path: &ObjectPath,
+) -> Result<ExtractedFileMetadata, AnalysisError> {
+    let bytes = store
+        .get(object_path)
+        .await
+        .map_err(|e| AnalysisError::ConfigError {
+            message: format!("Failed to read CSV source {}: {}", object_path, e),
+        })?
+        .bytes()
+        .await
+        .map_err(|e| AnalysisError::ConfigError {
+            message: format!("Failed to buffer CSV source {}: {}", object_path, e),
+        })?;
+
+    let (schema, _) = CsvFormat::default()
+        .with_header(true)
+        .infer_schema(
+            &mut std::io::Cursor::new(&bytes),
+            Some(CSV_SCHEMA_SAMPLE_ROWS),
+        )
+        .map_err(|e| AnalysisError::ConfigError {
+            message: format!("Failed to infer CSV schema for {}: {}", object_path, e),
+        })?;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(std::io::Cursor::new(&bytes));
+
+    let header_names: Vec<String> = reader
+        .headers()
+        .map_err(|e| AnalysisError::ConfigError {
+            message: format!("Failed to read CSV header for {}: {}", object_path, e),
+        })?
+        .iter()
+        .map(|h| h.to_string())
+        .collect();
+
+    let mut accumulators: Vec<ColumnAccumulator> =
+        (0..header_names.len()).map(|_| ColumnAccumulator::default()).collect();
+    let mut row_count: u64 = 0;
+
+    for record in reader.records() {
+        let record = record.map_err(|e| AnalysisError::ConfigError {
+            message: format!("Failed to read CSV row in {}: {}", object_path, e),
+        })?;
+        for (i, accumulator) in accumulators.iter_mut().enumerate() {
+            accumulator.observe(record.get(i));
+        }
+        row_count += 1;
+    }
+
+    let columns = header_names
+        .into_iter()
+        .zip(accumulators)
+        .zip(schema.fields().iter())
+        .map(|((name, accumulator), field)| ColumnMetadata {
+            name,
+            data_type: format!("{:?}", field.data_type()),
+            nullable: true,
+            description: String::new(),
+            statistics: accumulator.into_statistics(row_count),
+        })
+        .collect();
+
+    Ok(ExtractedFileMetadata {
+        row_count: row_count as i64,
+        columns,
+    })
+}
+
+pub fn warn_extraction_failed(filename: &str, error: &AnalysisError) {
+    warn!(
+        "Metadata extraction failed for {}, continuing without enriched schema: {}",
+        filename, error
+    );
+}
@@ -0,0 +1,140 @@
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, Opts, Registry, TextEncoder};
+
+use crate::error::AnalysisError;
+
+fn registration_error(e: prometheus::Error) -> AnalysisError {
+    AnalysisError::ConfigError {
+        message: format!("Failed to register metric: {}", e),
+    }
+}
+
+/// Prometheus counters and histograms for query execution and object-store
+/// activity. Incremented at the same points the service already logs via
+/// `info!`/`error!`, so the log lines and the metrics stay in sync.
+pub struct Metrics {
+    registry: Registry,
+    pub queries_executed_total: IntCounter,
+    pub query_failures_total: IntCounterVec,
+    pub query_duration_seconds: Histogram,
+    pub rows_returned_total: IntCounter,
+    pub arrow_ipc_bytes_returned_total: IntCounter,
+    pub datasets_registered_total: IntCounter,
+    pub object_store_requests_total: IntCounter,
+    pub object_store_retries_total: IntCounter,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self, AnalysisError> {
+        let registry = Registry::new();
+
+        let queries_executed_total = IntCounter::new(
+            "query_engine_queries_executed_total",
+            "Total number of queries executed",
+        )
+        .map_err(registration_error)?;
+
+        let query_failures_total = IntCounterVec::new(
+            Opts::new(
+                "query_engine_query_failures_total",
+                "Total number of failed queries, labeled by AnalysisError variant",
+            ),
+            &["error_type"],
+        )
+        .map_err(registration_error)?;
+
+        let query_duration_seconds = Histogram::with_opts(HistogramOpts::new(
+            "query_engine_query_duration_seconds",
+            "Query execution duration in seconds",
+        ))
+        .map_err(registration_error)?;
+
+        let rows_returned_total = IntCounter::new(
+            "query_engine_rows_returned_total",
+            "Total number of rows returned across all queries",
+        )
+        .map_err(registration_error)?;
+
+        let arrow_ipc_bytes_returned_total = IntCounter::new(
+            "query_engine_arrow_ipc_bytes_returned_total",
+            "Total Arrow IPC bytes returned across all queries",
+        )
+        .map_err(registration_error)?;
+
+        let datasets_registered_total = IntCounter::new(
+            "query_engine_datasets_registered_total",
+            "Total number of datasets registered with DataFusion",
+        )
+        .map_err(registration_error)?;
+
+        let object_store_requests_total = IntCounter::new(
+            "query_engine_object_store_requests_total",
+            "Total number of object stores built and registered with DataFusion",
+        )
+        .map_err(registration_error)?;
+
+        let object_store_retries_total = IntCounter::new(
+            "query_engine_object_store_retries_total",
+            "Total number of object-store requests retried by the retry middleware",
+        )
+        .map_err(registration_error)?;
+
+        registry
+            .register(Box::new(queries_executed_total.clone()))
+            .map_err(registration_error)?;
+        registry
+            .register(Box::new(query_failures_total.clone()))
+            .map_err(registration_error)?;
+        registry
+            .register(Box::new(query_duration_seconds.clone()))
+            .map_err(registration_error)?;
+        registry
+            .register(Box::new(rows_returned_total.clone()))
+            .map_err(registration_error)?;
+        registry
+            .register(Box::new(arrow_ipc_bytes_returned_total.clone()))
+            .map_err(registration_error)?;
+        registry
+            .register(Box::new(datasets_registered_total.clone()))
+            .map_err(registration_error)?;
+        registry
+            .register(Box::new(object_store_requests_total.clone()))
+            .map_err(registration_error)?;
+        registry
+            .register(Box::new(object_store_retries_total.clone()))
+            .map_err(registration_error)?;
+
+        Ok(Self {
+            registry,
+            queries_executed_total,
+            query_failures_total,
+            query_duration_seconds,
+            rows_returned_total,
+            arrow_ipc_bytes_returned_total,
+            datasets_registered_total,
+            object_store_requests_total,
+            object_store_retries_total,
+        })
+    }
+
+    pub fn record_query_failure(&self, error: &AnalysisError) {
+        self.query_failures_total
+            .with_label_values(&[error.variant_name()])
+            .inc();
+    }
+
+    /// Renders all registered metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> Result<String, AnalysisError> {
+        let encoder = TextEncoder::new();
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        encoder
+            .encode(&metric_families, &mut buffer)
+            .map_err(|e| AnalysisError::ConfigError {
+                message: format!("Failed to encode metrics: {}", e),
+            })?;
+
+        String::from_utf8(buffer).map_err(|e| AnalysisError::ConfigError {
+            message: format!("Metrics output was not valid UTF-8: {}", e),
+        })
+    }
+}
@@ -4,7 +4,7 @@ use uuid::Uuid;
 use std::collections::HashMap;
 
 use crate::schema::{datasets, dataset_files, dataset_columns, dataset_statistics};
-use crate::catalog::{DataFormat, CatalogDatasetEntry, DatasetFile, ColumnMetadata};
+use crate::catalog::{CatalogDatasetEntry, ColumnMetadata, CompressionType, DataFormat, DatasetFile};
 
 #[derive(Queryable, Selectable, Identifiable, Debug, Clone)]
 #[diesel(table_name = datasets)]
@@ -14,7 +14,7 @@ pub struct Dataset {
     pub uuid: Uuid,
     pub name: String,
     pub description: String,
-    pub format: String,
+    pub format: DataFormat,
     pub size_bytes: i64,
     pub row_count: i32,
     pub tags: Vec<String>,
@@ -22,6 +22,9 @@ pub struct Dataset {
     pub updated_at: DateTime<Utc>,
     pub dataset_path: String,
     pub metadata_path: String,
+    pub max_size_bytes: Option<i64>,
+    pub max_row_count: Option<i32>,
+    pub source_path: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -31,7 +34,7 @@ pub struct NewDataset<'a> {
     pub uuid: &'a Uuid,
     pub name: &'a str,
     pub description: &'a str,
-    pub format: &'a str,
+    pub format: DataFormat,
     pub size_bytes: i64,
     pub row_count: i32,
     pub tags: &'a Vec<String>,
@@ -39,6 +42,9 @@ pub struct NewDataset<'a> {
     pub updated_at: DateTime<Utc>,
     pub dataset_path: &'a str,
     pub metadata_path: &'a str,
+    pub max_size_bytes: Option<i64>,
+    pub max_row_count: Option<i32>,
+    pub source_path: Option<&'a str>,
 }
 
 #[derive(Queryable, Selectable, Identifiable, Associations, Debug, Clone)]
@@ -52,6 +58,10 @@ pub struct DatasetFileModel {
     pub size_bytes: i64,
     pub row_count: i32,
     pub created_at: DateTime<Utc>,
+    pub content_hash: Option<String>,
+    pub upstream_etag: Option<String>,
+    pub upstream_last_modified: Option<DateTime<Utc>>,
+    pub compression: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -63,6 +73,10 @@ pub struct NewDatasetFile<'a> {
     pub size_bytes: i64,
     pub row_count: i32,
     pub created_at: DateTime<Utc>,
+    pub content_hash: Option<&'a str>,
+    pub upstream_etag: Option<&'a str>,
+    pub upstream_last_modified: Option<DateTime<Utc>>,
+    pub compression: Option<&'a str>,
 }
 
 #[derive(Queryable, Selectable, Identifiable, Associations, Debug, Clone)]
@@ -109,18 +123,12 @@ pub struct NewDatasetStatistic<'a> {
 
 impl From<Dataset> for CatalogDatasetEntry {
     fn from(dataset: Dataset) -> Self {
-        let format = match dataset.format.as_str() {
-            "csv" => DataFormat::Csv,
-            "parquet" => DataFormat::Parquet,
-            _ => DataFormat::Csv,
-        };
-
         CatalogDatasetEntry {
             id: dataset.id,
             uuid: dataset.uuid,
             name: dataset.name,
             description: dataset.description,
-            format,
+            format: dataset.format,
             size_bytes: dataset.size_bytes,
             row_count: dataset.row_count,
             tags: dataset.tags,
@@ -128,6 +136,9 @@ impl From<Dataset> for CatalogDatasetEntry {
             updated_at: dataset.updated_at,
             dataset_path: dataset.dataset_path,
             metadata_path: dataset.metadata_path,
+            max_size_bytes: dataset.max_size_bytes,
+            max_row_count: dataset.max_row_count,
+            source_path: dataset.source_path,
         }
     }
 }
@@ -140,6 +151,13 @@ impl From<DatasetFileModel> for DatasetFile {
             size_bytes: file.size_bytes,
             row_count: file.row_count,
             created_at: file.created_at,
+            compression: file
+                .compression
+                .as_deref()
+                .and_then(|c| CompressionType::try_from(c).ok()),
+            content_hash: file.content_hash,
+            upstream_etag: file.upstream_etag,
+            upstream_last_modified: file.upstream_last_modified,
         }
     }
 }
@@ -0,0 +1,104 @@
+use dashmap::DashMap;
+use futures::{Stream, StreamExt};
+use std::sync::Arc;
+use tokio::sync::{broadcast, Notify};
+use tokio_stream::wrappers::BroadcastStream;
+use tracing::warn;
+
+/// The Postgres `LISTEN`/`NOTIFY` channel dataset-catalog changes are
+/// published on. `add_dataset` and `save_metadata` `NOTIFY` it with the
+/// affected dataset's id as payload.
+pub const CATALOG_CHANGED_CHANNEL: &str = "catalog_changed";
+
+/// The Postgres `LISTEN`/`NOTIFY` channel a `job_queue` insert is published
+/// on, so idle workers can block on [`CatalogNotifier::wait`] instead of
+/// polling `claim_job` in a busy loop. `enqueue_job` `NOTIFY`s it with the
+/// target queue name as payload.
+pub const JOB_QUEUE_CHANNEL: &str = "job_queue_changed";
+
+struct Inner {
+    /// Per-channel wake-up, for callers that only care that *something*
+    /// changed on a given channel rather than the notification payload.
+    waiters: DashMap<String, Arc<Notify>>,
+    /// Carries the actual payload (the changed dataset's id) to every
+    /// `watch_catalog` subscriber.
+    catalog_tx: broadcast::Sender<String>,
+}
+
+/// Fans Postgres `NOTIFY` messages, forwarded off a dedicated `LISTEN`
+/// connection opened per pooled connection's `custom_setup`, out to
+/// in-process waiters. Lets `DatabaseManager` surface catalog changes to
+/// callers in real time instead of forcing them to re-poll `list_datasets`,
+/// and lets job-queue workers block on a channel instead of busy-polling
+/// `claim_job`.
+#[derive(Clone)]
+pub struct CatalogNotifier {
+    inner: Arc<Inner>,
+}
+
+impl CatalogNotifier {
+    pub fn new() -> Self {
+        let (catalog_tx, _) = broadcast::channel(256);
+        Self {
+            inner: Arc::new(Inner {
+                waiters: DashMap::new(),
+                catalog_tx,
+            }),
+        }
+    }
+
+    fn waiter(&self, channel: &str) -> Arc<Notify> {
+        self.inner
+            .waiters
+            .entry(channel.to_string())
+            .or_insert_with(|| Arc::new(Notify::new()))
+            .clone()
+    }
+
+    /// Drains forwarded notifications off `rx` for the life of the process,
+    /// waking any waiter registered for the notification's channel and, for
+    /// [`CATALOG_CHANGED_CHANNEL`], broadcasting the changed dataset id to
+    /// every `watch_catalog` subscriber.
+    pub fn spawn_delegate(self, rx: flume::Receiver<tokio_postgres::Notification>) {
+        tokio::spawn(async move {
+            while let Ok(notification) = rx.recv_async().await {
+                self.waiter(notification.channel()).notify_waiters();
+
+                if notification.channel() == CATALOG_CHANGED_CHANNEL {
+                    if self
+                        .inner
+                        .catalog_tx
+                        .send(notification.payload().to_string())
+                        .is_err()
+                    {
+                        warn!("Received catalog change notification with no active watchers");
+                    }
+                }
+            }
+        });
+    }
+
+    /// Yields dataset ids as they change in the catalog (added, metadata
+    /// updated), driven off `NOTIFY catalog_changed` rather than callers
+    /// re-polling `list_datasets`. Notifications sent before a subscriber
+    /// calls this are not replayed.
+    pub fn watch_catalog(&self) -> impl Stream<Item = String> {
+        BroadcastStream::new(self.inner.catalog_tx.subscribe())
+            .filter_map(|msg| async move { msg.ok() })
+    }
+
+    /// Waits for the next `NOTIFY` on `channel`, without caring about its
+    /// payload. A job-queue worker awaits this on [`JOB_QUEUE_CHANNEL`]
+    /// between `claim_job` attempts instead of polling on a fixed interval;
+    /// any notification arriving while nobody's waiting is simply missed,
+    /// so callers should still retry `claim_job` periodically as a backstop.
+    pub async fn wait(&self, channel: &str) {
+        self.waiter(channel).notified().await;
+    }
+}
+
+impl Default for CatalogNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
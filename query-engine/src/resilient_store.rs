@@ -0,0 +1,85 @@
+use object_store::limit::LimitStore;
+use object_store::throttle::{ThrottleConfig, ThrottledStore};
+use object_store::{BackoffConfig, ObjectStore, RetryConfig};
+use std::sync::Arc;
+use std::time::Duration;
+
+const DEFAULT_MAX_RETRIES: usize = 3;
+const DEFAULT_RETRY_TIMEOUT_SECS: u64 = 180;
+const DEFAULT_INIT_BACKOFF_MS: u64 = 100;
+const DEFAULT_MAX_BACKOFF_SECS: u64 = 15;
+const DEFAULT_BACKOFF_BASE: f64 = 2.0;
+const DEFAULT_MAX_CONCURRENCY: usize = 64;
+
+fn env_var<T: std::str::FromStr>(key: &str, default: T, valid: impl Fn(&T) -> bool) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse::<T>().ok())
+        .filter(valid)
+        .unwrap_or(default)
+}
+
+/// Builds a `RetryConfig` from `OBJECT_STORE_*` environment variables, retrying
+/// retryable statuses (429/500/503, connection resets) with exponential backoff
+/// and jitter, as implemented by `object_store`'s built-in retry middleware.
+pub fn retry_config_from_env() -> RetryConfig {
+    RetryConfig {
+        backoff: BackoffConfig {
+            init_backoff: Duration::from_millis(env_var(
+                "OBJECT_STORE_INIT_BACKOFF_MS",
+                DEFAULT_INIT_BACKOFF_MS,
+                |v| *v > 0,
+            )),
+            max_backoff: Duration::from_secs(env_var(
+                "OBJECT_STORE_MAX_BACKOFF_SECS",
+                DEFAULT_MAX_BACKOFF_SECS,
+                |v| *v > 0,
+            )),
+            base: env_var("OBJECT_STORE_BACKOFF_BASE", DEFAULT_BACKOFF_BASE, |v| {
+                *v > 1.0
+            }),
+        },
+        max_retries: env_var("OBJECT_STORE_MAX_RETRIES", DEFAULT_MAX_RETRIES, |_| true),
+        retry_timeout: Duration::from_secs(env_var(
+            "OBJECT_STORE_RETRY_TIMEOUT_SECS",
+            DEFAULT_RETRY_TIMEOUT_SECS,
+            |v| *v > 0,
+        )),
+    }
+}
+
+pub fn max_concurrency_from_env() -> usize {
+    env_var(
+        "OBJECT_STORE_MAX_CONCURRENCY",
+        DEFAULT_MAX_CONCURRENCY,
+        |v| *v > 0,
+    )
+}
+
+pub fn throttle_latency_from_env() -> Option<Duration> {
+    std::env::var("OBJECT_STORE_THROTTLE_LATENCY_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|ms| *ms > 0)
+        .map(Duration::from_millis)
+}
+
+/// Decorates a freshly built object store with a concurrency cap (so a wide
+/// Parquet scan can't overwhelm the backend with unbounded GET/LIST fan-out)
+/// and, when `OBJECT_STORE_THROTTLE_LATENCY_MS` is set, a fixed per-request
+/// latency injector used to exercise timeout/backpressure handling in tests.
+pub fn wrap_object_store(store: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+    let store: Arc<dyn ObjectStore> = Arc::new(LimitStore::new(store, max_concurrency_from_env()));
+
+    match throttle_latency_from_env() {
+        Some(latency) => {
+            let throttle_config = ThrottleConfig {
+                wait_get_per_call: latency,
+                wait_list_per_call: latency,
+                ..Default::default()
+            };
+            Arc::new(ThrottledStore::new(store, throttle_config))
+        }
+        None => store,
+    }
+}
@@ -0,0 +1,45 @@
+use object_store::{aws::AmazonS3Builder, ObjectStore};
+use std::sync::Arc;
+use url::Url;
+
+use crate::error::AnalysisError;
+use crate::resilient_store::retry_config_from_env;
+
+/// Builds an S3-compatible object store from an `s3://bucket/...` URL.
+///
+/// Ambient credentials (access key/secret, region, endpoint, web-identity token file)
+/// are picked up from the standard `AWS_*` environment variables via
+/// [`AmazonS3Builder::from_env`]. Query params on the URL take precedence over the
+/// environment so a single process can talk to multiple S3-compatible stores (e.g.
+/// MinIO alongside real AWS) at once:
+///
+///   s3://my-bucket/path?region=us-west-2&endpoint=http://localhost:9000&path_style=true
+///   &access_key_id=...&secret_access_key=...
+pub fn create_s3_client(bucket: &str, source_url: &Url) -> Result<Arc<dyn ObjectStore>, AnalysisError> {
+    let mut builder = AmazonS3Builder::from_env()
+        .with_bucket_name(bucket)
+        .with_retry(retry_config_from_env());
+
+    for (key, value) in source_url.query_pairs() {
+        builder = match key.as_ref() {
+            "region" => builder.with_region(value.as_ref()),
+            "endpoint" => builder.with_endpoint(value.as_ref()),
+            "path_style" => {
+                let use_path_style = value.as_ref() == "true";
+                builder.with_virtual_hosted_style_request(!use_path_style)
+            }
+            "access_key_id" => builder.with_access_key_id(value.as_ref()),
+            "secret_access_key" => builder.with_secret_access_key(value.as_ref()),
+            "token" => builder.with_token(value.as_ref()),
+            _ => builder,
+        };
+    }
+
+    let store = builder
+        .build()
+        .map_err(|e| AnalysisError::ConfigError {
+            message: format!("Failed to create S3 client for bucket '{}': {}", bucket, e),
+        })?;
+
+    Ok(Arc::new(store))
+}
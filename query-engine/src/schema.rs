@@ -1,10 +1,22 @@
+pub mod sql_types {
+    /// Maps to the native Postgres `data_format` enum created by the
+    /// `native_data_format_enum` migration; paired with
+    /// `#[derive(DbEnum)]` on `crate::catalog::DataFormat`.
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "data_format"))]
+    pub struct DataFormat;
+}
+
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DataFormat;
+
     datasets (id) {
         id -> Text,
         uuid -> Uuid,
         name -> Text,
         description -> Text,
-        format -> Text,
+        format -> DataFormat,
         size_bytes -> Int8,
         row_count -> Int4,
         tags -> Array<Text>,
@@ -12,6 +24,9 @@ diesel::table! {
         updated_at -> Timestamptz,
         dataset_path -> Text,
         metadata_path -> Text,
+        max_size_bytes -> Nullable<Int8>,
+        max_row_count -> Nullable<Int4>,
+        source_path -> Nullable<Text>,
     }
 }
 
@@ -23,6 +38,10 @@ diesel::table! {
         size_bytes -> Int8,
         row_count -> Int4,
         created_at -> Timestamptz,
+        content_hash -> Nullable<Text>,
+        upstream_etag -> Nullable<Text>,
+        upstream_last_modified -> Nullable<Timestamptz>,
+        compression -> Nullable<Text>,
     }
 }
 
@@ -1,15 +1,156 @@
-use futures::StreamExt;
-use object_store::{aws::AmazonS3Builder, path::Path as ObjectPath, MultipartUpload, ObjectStore};
+use bytes::Bytes;
+use futures::stream::{BoxStream, FuturesUnordered};
+use futures::{StreamExt, TryStreamExt};
+use http::Method;
+use object_store::gcp::GoogleCloudStorage;
+use object_store::signer::Signer;
+use object_store::{
+    path::Path as ObjectPath, GetOptions, GetRange, MultipartUpload, ObjectStore,
+};
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
-use tracing::info;
+use std::time::Duration;
+use tracing::{info, warn};
 use url::Url;
+use uuid::Uuid;
 
+use crate::azure_client::create_azure_client;
+use crate::delta_sharing_client::DeltaShareSource;
 use crate::error::AnalysisError;
 use crate::gcs_client::create_gcs_client;
+use crate::http_client::create_http_client;
+use crate::local_fs_client::create_local_fs_client;
+use crate::s3_client::create_s3_client;
+
+const DEFAULT_MULTIPART_CONCURRENCY: usize = 6;
+const MAX_RESUME_ATTEMPTS: u32 = 5;
+
+/// Query parameters the storage clients in this crate (`s3_client`,
+/// `azure_client`) read credentials from. Never let these reach a log line
+/// or the catalog's human-facing `description` field.
+const CREDENTIAL_QUERY_PARAMS: &[&str] = &[
+    "access_key_id",
+    "secret_access_key",
+    "token",
+    "access_key",
+    "sas_token",
+    "client_secret",
+];
+
+/// Renders a `source_path` the way it's safe to log or show back to a user.
+///
+/// `source_path` can carry credentials two ways: a Delta Sharing
+/// [`DeltaShareSource`] embeds a `bearer_token`, and S3/Azure URLs carry
+/// `access_key_id`/`secret_access_key`/... in the query string (or
+/// `user:pass@host` userinfo, for HTTP/WebDAV). Both end up in `info!` logs
+/// and the default dataset `description` - places read far more widely than
+/// the `datasets` table itself - so redact before either.
+pub(crate) fn redact_source_path(source_path: &str) -> String {
+    if let Some(decoded) = DeltaShareSource::decode(source_path) {
+        return match decoded {
+            Ok(source) => format!(
+                "delta-share://{}/{}.{}.{} (bearer token redacted)",
+                source.endpoint, source.share, source.schema, source.table
+            ),
+            Err(_) => "delta-share://<unparseable source>".to_string(),
+        };
+    }
+
+    let Ok(mut url) = Url::parse(source_path) else {
+        return source_path.to_string();
+    };
+
+    if !url.username().is_empty() || url.password().is_some() {
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+    }
+
+    let redacted_pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| {
+            if CREDENTIAL_QUERY_PARAMS.contains(&k.as_ref()) {
+                (k.into_owned(), "***".to_string())
+            } else {
+                (k.into_owned(), v.into_owned())
+            }
+        })
+        .collect();
+
+    if !redacted_pairs.is_empty() {
+        url.query_pairs_mut().clear().extend_pairs(&redacted_pairs);
+    }
+
+    url.to_string()
+}
+
+/// S3 (and S3-compatible) object stores reject multipart parts smaller than 5 MiB,
+/// except for the final part of an upload.
+const S3_MIN_PART_SIZE_BYTES: u64 = 5 * 1024 * 1024;
+const DEFAULT_PART_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+
+fn multipart_concurrency() -> usize {
+    std::env::var("MULTIPART_UPLOAD_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_MULTIPART_CONCURRENCY)
+}
+
+fn multipart_part_size() -> u64 {
+    let configured = std::env::var("MULTIPART_PART_SIZE_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_PART_SIZE_BYTES);
+
+    if configured < S3_MIN_PART_SIZE_BYTES {
+        warn!(
+            "Configured multipart part size {} bytes is below the {} byte minimum required by S3-compatible stores, clamping up",
+            configured, S3_MIN_PART_SIZE_BYTES
+        );
+        S3_MIN_PART_SIZE_BYTES
+    } else {
+        configured
+    }
+}
+
+/// Opens the source object as a byte stream, resuming from `offset` via an HTTP
+/// Range request when resuming after a dropped connection.
+async fn open_source_stream(
+    source_store: &Arc<dyn ObjectStore>,
+    source_object_path: &ObjectPath,
+    offset: u64,
+) -> Result<BoxStream<'static, object_store::Result<Bytes>>, AnalysisError> {
+    let options = GetOptions {
+        range: (offset > 0).then_some(GetRange::Offset(offset)),
+        ..Default::default()
+    };
+
+    let get_result = source_store
+        .get_opts(source_object_path, options)
+        .await
+        .map_err(|e| AnalysisError::ConfigError {
+            message: format!(
+                "Failed to open source stream {} at offset {}: {}",
+                source_object_path, offset, e
+            ),
+        })?;
+
+    Ok(get_result.into_stream())
+}
+
+/// Result of copying a single file into content-addressed storage.
+#[derive(Debug, Clone)]
+pub struct CopiedFile {
+    pub storage_path: String,
+    pub content_hash: String,
+    pub size_bytes: i64,
+}
 
 #[derive(Debug)]
 pub struct DatasetStorage {
     store: Arc<dyn ObjectStore>,
+    signer: Arc<GoogleCloudStorage>,
     bucket_name: String,
 }
 
@@ -20,9 +161,102 @@ impl DatasetStorage {
             bucket_name
         );
 
-        let store = create_gcs_client(&bucket_name)?;
+        let gcs_client = create_gcs_client(&bucket_name)?;
+
+        Ok(Self {
+            store: gcs_client.clone(),
+            signer: gcs_client,
+            bucket_name,
+        })
+    }
+
+    /// Generates a time-limited, pre-signed download URL for an object already
+    /// stored in our destination bucket (e.g. a dataset file's `storage_path`).
+    pub async fn generate_presigned_url(
+        &self,
+        object_path: &str,
+        expires_in: Duration,
+    ) -> Result<String, AnalysisError> {
+        let path = ObjectPath::from(
+            object_path
+                .trim_start_matches(&format!("gs://{}/", self.bucket_name))
+                .trim_start_matches('/'),
+        );
+
+        let url = self
+            .signer
+            .signed_url(Method::GET, &path, expires_in)
+            .await
+            .map_err(|e| AnalysisError::ConfigError {
+                message: format!("Failed to generate presigned URL for {}: {}", path, e),
+            })?;
+
+        Ok(url.to_string())
+    }
+
+    /// The underlying object store backing this dataset's bucket, for callers
+    /// (e.g. metadata extraction) that need to read a file's bytes directly.
+    pub fn object_store(&self) -> Arc<dyn ObjectStore> {
+        self.store.clone()
+    }
+
+    /// Resolves a `DatasetFile::storage_path` (e.g. `gs://bucket/blobs/{hash}`)
+    /// to the `ObjectPath` used to address it within `object_store()`.
+    pub fn resolve_object_path(&self, storage_path: &str) -> ObjectPath {
+        ObjectPath::from(
+            storage_path
+                .trim_start_matches(&format!("gs://{}/", self.bucket_name))
+                .trim_start_matches('/'),
+        )
+    }
+
+    /// Canonical path for the content-addressed blob holding `digest`'s bytes.
+    fn blob_path(digest: &str) -> ObjectPath {
+        ObjectPath::from(format!("blobs/{}", digest))
+    }
+
+    /// Promotes a just-uploaded temporary object to the canonical
+    /// content-addressed blob path for `digest`, de-duplicating against a
+    /// blob already written by a previous import of the same bytes (whether
+    /// for this dataset or another one). We keep whichever copy already
+    /// exists rather than re-validating it against `size_bytes` byte-for-byte,
+    /// since the digest itself is already a cryptographic proof of content
+    /// equality.
+    async fn finalize_content_addressed_blob(
+        &self,
+        temp_path: &ObjectPath,
+        digest: &str,
+        size_bytes: i64,
+    ) -> Result<String, AnalysisError> {
+        let blob_path = Self::blob_path(digest);
+
+        match self.store.head(&blob_path).await {
+            Ok(existing) if existing.size as i64 == size_bytes => {
+                info!(
+                    "Blob {} already exists ({} bytes), reusing it and discarding the temporary upload",
+                    blob_path, size_bytes
+                );
+                if let Err(e) = self.store.delete(temp_path).await {
+                    warn!("Failed to clean up temporary object {}: {}", temp_path, e);
+                }
+            }
+            _ => {
+                self.store
+                    .copy(temp_path, &blob_path)
+                    .await
+                    .map_err(|e| AnalysisError::ConfigError {
+                        message: format!(
+                            "Failed to promote {} to content-addressed blob {}: {}",
+                            temp_path, blob_path, e
+                        ),
+                    })?;
+                if let Err(e) = self.store.delete(temp_path).await {
+                    warn!("Failed to clean up temporary object {}: {}", temp_path, e);
+                }
+            }
+        }
 
-        Ok(Self { store, bucket_name })
+        Ok(format!("gs://{}/{}", self.bucket_name, blob_path))
     }
 
     pub async fn copy_from_external_storage(
@@ -30,145 +264,309 @@ impl DatasetStorage {
         source_path: &str,
         dataset_id: &str,
         filename: &str,
-    ) -> Result<String, AnalysisError> {
+    ) -> Result<CopiedFile, AnalysisError> {
         info!(
             "Copying dataset from {} to {}/{}",
-            source_path, dataset_id, filename
+            redact_source_path(source_path),
+            dataset_id,
+            filename
         );
 
         let source_url = Url::parse(source_path).map_err(|e| AnalysisError::ConfigError {
             message: format!("Invalid source path URL: {}", e),
         })?;
 
-        let source_store: Arc<dyn ObjectStore> = match source_url.scheme() {
-            "s3" => {
-                let bucket = source_url
-                    .host_str()
-                    .ok_or_else(|| AnalysisError::ConfigError {
-                        message: "Invalid S3 URL: missing bucket".to_string(),
-                    })?;
+        // A blob's destination path is derived purely from its content, so
+        // even a source object already in our own bucket has to be read and
+        // hashed like any other source - there's no zero-copy shortcut that
+        // preserves the content-addressing invariant.
+        let source_store: Arc<dyn ObjectStore> = if source_url.scheme() == "gs"
+            && source_url.host_str() == Some(self.bucket_name.as_str())
+        {
+            self.store.clone()
+        } else {
+            match source_url.scheme() {
+                "s3" => {
+                    let bucket = source_url
+                        .host_str()
+                        .ok_or_else(|| AnalysisError::ConfigError {
+                            message: "Invalid S3 URL: missing bucket".to_string(),
+                        })?;
 
-                info!("Creating S3 client for bucket: {}", bucket);
-                let s3_store = AmazonS3Builder::new()
-                    .with_bucket_name(bucket)
-                    .build()
-                    .map_err(|e| AnalysisError::ConfigError {
-                        message: format!("Failed to create S3 client: {}", e),
-                    })?;
+                    info!("Creating S3 client for bucket: {}", bucket);
+                    create_s3_client(bucket, &source_url)?
+                }
+                "gs" => {
+                    let bucket = source_url
+                        .host_str()
+                        .ok_or_else(|| AnalysisError::ConfigError {
+                            message: "Invalid GCS URL: missing bucket".to_string(),
+                        })?;
 
-                Arc::new(s3_store)
-            }
-            "gs" => {
-                let bucket = source_url
-                    .host_str()
-                    .ok_or_else(|| AnalysisError::ConfigError {
-                        message: "Invalid GCS URL: missing bucket".to_string(),
-                    })?;
+                    info!("Creating GCS client for bucket: {}", bucket);
+                    create_gcs_client(bucket)?
+                }
+                "az" | "abfs" | "azure" | "abfss" => {
+                    let container = source_url
+                        .host_str()
+                        .ok_or_else(|| AnalysisError::ConfigError {
+                            message: "Invalid Azure URL: missing container".to_string(),
+                        })?;
 
-                info!("Creating GCS client for bucket: {}", bucket);
-                create_gcs_client(bucket)?
-            }
-            scheme => {
-                return Err(AnalysisError::ConfigError {
-                    message: format!("Unsupported storage scheme: {}", scheme),
-                });
+                    info!("Creating Azure Blob client for container: {}", container);
+                    create_azure_client(container, &source_url)?
+                }
+                "http" | "https" | "webdav" => {
+                    info!(
+                        "Creating HTTP/WebDAV client for {}",
+                        redact_source_path(source_url.as_str())
+                    );
+                    create_http_client(&source_url)?
+                }
+                "file" => {
+                    info!("Using local filesystem source at {}", source_url.path());
+                    create_local_fs_client()?
+                }
+                scheme => {
+                    return Err(AnalysisError::ConfigError {
+                        message: format!("Unsupported storage scheme: {}", scheme),
+                    });
+                }
             }
         };
 
         let source_object_path = ObjectPath::from(source_url.path().trim_start_matches('/'));
-        let dest_path = ObjectPath::from(format!("datasets/{}/{}", dataset_id, filename));
 
-        info!(
-            "Copying from source {} to destination {}",
-            source_object_path, dest_path
-        );
+        info!("Copying from source {}", source_object_path);
 
-        info!("Using streaming copy with chunked upload");
+        let part_size = multipart_part_size();
 
-        let get_result = source_store.get(&source_object_path).await.map_err(|e| {
+        let source_meta = source_store.head(&source_object_path).await.map_err(|e| {
             AnalysisError::ConfigError {
-                message: format!("Failed to open source stream {}: {}", source_path, e),
+                message: format!(
+                    "Failed to stat source object {}: {}",
+                    redact_source_path(source_path),
+                    e
+                ),
             }
         })?;
 
-        let source_stream = get_result.into_stream();
+        if source_meta.size <= part_size {
+            info!(
+                "Source object is {} bytes (<= {} byte part size), using single-PUT fast path",
+                source_meta.size, part_size
+            );
+
+            let bytes = source_store
+                .get(&source_object_path)
+                .await
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!(
+                        "Failed to read source object {}: {}",
+                        redact_source_path(source_path),
+                        e
+                    ),
+                })?
+                .bytes()
+                .await
+                .map_err(|e| AnalysisError::ConfigError {
+                    message: format!(
+                        "Failed to buffer source object {}: {}",
+                        redact_source_path(source_path),
+                        e
+                    ),
+                })?;
+
+            let digest = format!("{:x}", Sha256::digest(&bytes));
+            let size_bytes = bytes.len() as i64;
+            let blob_path = Self::blob_path(&digest);
+
+            match self.store.head(&blob_path).await {
+                Ok(existing) if existing.size as i64 == size_bytes => {
+                    info!(
+                        "Blob {} already exists ({} bytes), skipping upload entirely",
+                        blob_path, size_bytes
+                    );
+                }
+                _ => {
+                    self.store.put(&blob_path, bytes.into()).await.map_err(|e| {
+                        AnalysisError::ConfigError {
+                            message: format!("Failed to upload {}: {}", blob_path, e),
+                        }
+                    })?;
+                }
+            }
 
+            let storage_path = format!("gs://{}/{}", self.bucket_name, blob_path);
+            info!("Successfully copied dataset to {}", storage_path);
+            return Ok(CopiedFile {
+                storage_path,
+                content_hash: digest,
+                size_bytes,
+            });
+        }
+
+        info!("Using streaming copy with chunked upload");
         info!("Starting multipart streaming upload to destination");
 
+        let temp_path = ObjectPath::from(format!("blobs/.tmp-{}", Uuid::new_v4()));
+
         let mut multipart =
             self.store
-                .put_multipart(&dest_path)
+                .put_multipart(&temp_path)
                 .await
                 .map_err(|e| AnalysisError::ConfigError {
                     message: format!("Failed to initiate multipart upload: {}", e),
                 })?;
 
-        let mut total_bytes = 0u64;
-        let mut part_number = 0;
-        let mut buffer = Vec::new();
-        const BUFFER_SIZE: usize = 10 * 1024 * 1024;
+        let buffer_size = part_size as usize;
+        let max_in_flight = multipart_concurrency();
+        info!("Uploading parts with up to {} in flight", max_in_flight);
 
-        let mut stream = source_stream;
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| AnalysisError::ConfigError {
-                message: format!("Failed to read chunk from source stream: {}", e),
-            })?;
+        let upload_result: Result<(u64, String), AnalysisError> = async {
+            let mut total_bytes = 0u64;
+            let mut committed_offset = 0u64;
+            let mut part_number = 0;
+            let mut buffer = Vec::new();
+            let mut in_flight = FuturesUnordered::new();
+            let mut resume_attempt = 0u32;
+            let mut hasher = Sha256::new();
+
+            let mut stream =
+                open_source_stream(&source_store, &source_object_path, committed_offset).await?;
+
+            loop {
+                match stream.next().await {
+                    Some(Ok(chunk)) => {
+                        resume_attempt = 0;
+                        buffer.extend_from_slice(&chunk);
+                        total_bytes += chunk.len() as u64;
+
+                        if buffer.len() >= buffer_size {
+                            if in_flight.len() >= max_in_flight {
+                                in_flight.try_next().await.map_err(|e| {
+                                    AnalysisError::ConfigError {
+                                        message: format!("Failed to upload part: {}", e),
+                                    }
+                                })?;
+                            }
+
+                            info!(
+                                "Uploading buffered part {} ({} bytes, total {} bytes)",
+                                part_number,
+                                buffer.len(),
+                                total_bytes
+                            );
+
+                            // Hashed only as a part is actually committed so the
+                            // digest stays consistent with `committed_offset`
+                            // across resumes, rather than including bytes from a
+                            // buffer that a subsequent read error discards.
+                            let part_bytes = std::mem::take(&mut buffer);
+                            hasher.update(&part_bytes);
+                            in_flight.push(multipart.put_part(part_bytes.into()));
+                            part_number += 1;
+                            committed_offset = total_bytes;
+                        }
+                    }
+                    Some(Err(e)) => {
+                        resume_attempt += 1;
+                        if resume_attempt > MAX_RESUME_ATTEMPTS {
+                            return Err(AnalysisError::ConfigError {
+                                message: format!(
+                                    "Failed to read chunk from source stream after {} attempts: {}",
+                                    resume_attempt - 1,
+                                    e
+                                ),
+                            });
+                        }
 
-            buffer.extend_from_slice(&chunk);
-            total_bytes += chunk.len() as u64;
+                        let backoff = std::time::Duration::from_millis(
+                            200 * 2u64.pow(resume_attempt - 1),
+                        );
+                        warn!(
+                            "Transient read error resuming from offset {} (attempt {}/{}): {}. Retrying in {:?}",
+                            committed_offset, resume_attempt, MAX_RESUME_ATTEMPTS, e, backoff
+                        );
+                        tokio::time::sleep(backoff).await;
 
-            if buffer.len() >= BUFFER_SIZE {
+                        buffer.clear();
+                        total_bytes = committed_offset;
+                        stream =
+                            open_source_stream(&source_store, &source_object_path, committed_offset)
+                                .await?;
+                    }
+                    None => break,
+                }
+            }
+
+            if !buffer.is_empty() {
                 info!(
-                    "Uploading buffered part {} ({} bytes, total {} bytes)",
+                    "Uploading final buffered part {} ({} bytes)",
                     part_number,
-                    buffer.len(),
-                    total_bytes
+                    buffer.len()
                 );
 
-                multipart
-                    .put_part(buffer.clone().into())
-                    .await
-                    .map_err(|e| AnalysisError::ConfigError {
-                        message: format!("Failed to upload part {}: {}", part_number, e),
-                    })?;
-
-                buffer.clear();
+                hasher.update(&buffer);
+                in_flight.push(multipart.put_part(buffer.into()));
                 part_number += 1;
             }
-        }
 
-        if !buffer.is_empty() {
-            info!(
-                "Uploading final buffered part {} ({} bytes)",
-                part_number,
-                buffer.len()
-            );
-
-            multipart
-                .put_part(buffer.into())
+            while in_flight
+                .try_next()
                 .await
                 .map_err(|e| AnalysisError::ConfigError {
-                    message: format!("Failed to upload final part {}: {}", part_number, e),
-                })?;
+                    message: format!("Failed to upload part: {}", e),
+                })?
+                .is_some()
+            {}
 
-            part_number += 1;
+            Ok((total_bytes, format!("{:x}", hasher.finalize())))
         }
+        .await;
 
-        multipart
-            .complete()
-            .await
-            .map_err(|e| AnalysisError::ConfigError {
+        let (total_bytes, digest) = match upload_result {
+            Ok(result) => result,
+            Err(e) => {
+                warn!(
+                    "Copy from {} failed, aborting multipart upload: {}",
+                    redact_source_path(source_path),
+                    e
+                );
+                if let Err(abort_err) = multipart.abort().await {
+                    warn!("Failed to abort multipart upload: {}", abort_err);
+                }
+                return Err(e);
+            }
+        };
+
+        if let Err(e) = multipart.complete().await {
+            warn!(
+                "Failed to complete multipart upload to {}, aborting: {}",
+                temp_path, e
+            );
+            if let Err(abort_err) = multipart.abort().await {
+                warn!("Failed to abort multipart upload: {}", abort_err);
+            }
+            return Err(AnalysisError::ConfigError {
                 message: format!("Failed to complete multipart upload: {}", e),
-            })?;
+            });
+        }
 
         info!(
             "Successfully completed multipart streaming upload of {} bytes",
             total_bytes
         );
 
-        let storage_path = format!("gs://{}/{}", self.bucket_name, dest_path);
+        let storage_path = self
+            .finalize_content_addressed_blob(&temp_path, &digest, total_bytes as i64)
+            .await?;
         info!("Successfully copied dataset to {}", storage_path);
 
-        Ok(storage_path)
+        Ok(CopiedFile {
+            storage_path,
+            content_hash: digest,
+            size_bytes: total_bytes as i64,
+        })
     }
 }
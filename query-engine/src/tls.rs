@@ -0,0 +1,74 @@
+use tonic::transport::{Certificate, Identity, ServerTlsConfig};
+
+use crate::error::AnalysisError;
+
+/// TLS material for the gRPC server, read from `TLS_SERVER_CERT_PATH` /
+/// `TLS_SERVER_KEY_PATH` (the server's own identity) and optionally
+/// `TLS_CLIENT_CA_CERT_PATH` (a CA to verify incoming client certificates
+/// against, enabling mTLS). Absent entirely means `GrpcServer::start` serves
+/// plaintext, matching the previous behavior, so this is safe to roll out
+/// incrementally without breaking existing deployments.
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub server_cert_path: String,
+    pub server_key_path: String,
+    pub client_ca_cert_path: Option<String>,
+}
+
+impl TlsConfig {
+    /// Returns `None` if neither `TLS_SERVER_CERT_PATH` nor
+    /// `TLS_SERVER_KEY_PATH` is set. Returns an error if only one is set,
+    /// since a partial TLS configuration is almost certainly a mistake.
+    pub fn from_env() -> Result<Option<Self>, AnalysisError> {
+        let server_cert_path = std::env::var("TLS_SERVER_CERT_PATH").ok();
+        let server_key_path = std::env::var("TLS_SERVER_KEY_PATH").ok();
+
+        let (server_cert_path, server_key_path) = match (server_cert_path, server_key_path) {
+            (None, None) => return Ok(None),
+            (Some(cert), Some(key)) => (cert, key),
+            _ => {
+                return Err(AnalysisError::ConfigError {
+                    message: "TLS_SERVER_CERT_PATH and TLS_SERVER_KEY_PATH must both be set to enable gRPC TLS".to_string(),
+                })
+            }
+        };
+
+        let client_ca_cert_path = std::env::var("TLS_CLIENT_CA_CERT_PATH").ok();
+
+        Ok(Some(Self {
+            server_cert_path,
+            server_key_path,
+            client_ca_cert_path,
+        }))
+    }
+
+    pub fn into_server_tls_config(self) -> Result<ServerTlsConfig, AnalysisError> {
+        let cert = std::fs::read(&self.server_cert_path).map_err(|e| AnalysisError::ConfigError {
+            message: format!(
+                "Failed to read TLS server cert {}: {}",
+                self.server_cert_path, e
+            ),
+        })?;
+        let key = std::fs::read(&self.server_key_path).map_err(|e| AnalysisError::ConfigError {
+            message: format!(
+                "Failed to read TLS server key {}: {}",
+                self.server_key_path, e
+            ),
+        })?;
+
+        let mut tls = ServerTlsConfig::new().identity(Identity::from_pem(cert, key));
+
+        if let Some(ca_path) = &self.client_ca_cert_path {
+            let ca = std::fs::read(ca_path).map_err(|e| AnalysisError::ConfigError {
+                message: format!("Failed to read TLS client CA cert {}: {}", ca_path, e),
+            })?;
+            tls = tls.client_ca_root(Certificate::from_pem(ca));
+        }
+
+        Ok(tls)
+    }
+
+    pub fn requires_client_cert(&self) -> bool {
+        self.client_ca_cert_path.is_some()
+    }
+}